@@ -0,0 +1,50 @@
+// gpio_read.rs - Measures the average time it takes to read the logic level
+// of an input pin.
+//
+// InputPin::read() performs a single volatile read of the GPIO level register,
+// without acquiring any locks or making any system calls. Run this benchmark
+// on a Raspberry Pi with `cargo bench --bench gpio_read`.
+
+use std::error::Error;
+use std::time::Instant;
+
+use rppal::gpio::{Gpio, Level};
+
+// Gpio uses BCM pin numbering. BCM GPIO 23 is tied to physical pin 16.
+const GPIO_INPUT: u8 = 23;
+
+const WARMUP_READS: u32 = 10_000;
+const READS: u32 = 1_000_000;
+
+fn main() -> Result<(), Box<dyn Error>> {
+    // Retrieve the GPIO pin and configure it as an input.
+    let pin = Gpio::new()?.get(GPIO_INPUT)?.into_input();
+
+    for _ in 0..WARMUP_READS {
+        pin.read();
+    }
+
+    // Count the high levels, so the reads can't be optimized out.
+    let mut high: u32 = 0;
+    let start = Instant::now();
+
+    for _ in 0..READS {
+        if pin.read() == Level::High {
+            high += 1;
+        }
+    }
+
+    let elapsed = start.elapsed();
+    let per_read_ns = elapsed.as_nanos() as f64 / f64::from(READS);
+
+    println!(
+        "{} reads in {:.2} ms: {:.1} ns per read ({:.0} kHz), {} high",
+        READS,
+        elapsed.as_secs_f64() * 1000.0,
+        per_read_ns,
+        1_000_000.0 / per_read_ns,
+        high
+    );
+
+    Ok(())
+}
@@ -0,0 +1,67 @@
+// gpio_write.rs - Measures the maximum rate at which an output pin can be
+// toggled.
+//
+// OutputPin::set_high() and OutputPin::set_low() perform a single volatile write
+// to the GPIO set or clear register, without acquiring any locks or making any
+// system calls. OutputPin::toggle() derives the new output state from the last
+// state it set, so it performs the same single write. Run this benchmark on a
+// Raspberry Pi with `cargo bench --bench gpio_write`.
+
+use std::error::Error;
+use std::time::{Duration, Instant};
+
+use rppal::gpio::{Gpio, OutputPin};
+
+// Gpio uses BCM pin numbering. BCM GPIO 23 is tied to physical pin 16.
+const GPIO_OUTPUT: u8 = 23;
+
+const WARMUP_CYCLES: u32 = 10_000;
+const CYCLES: u32 = 1_000_000;
+
+fn report(name: &str, elapsed: Duration) {
+    let per_cycle_ns = elapsed.as_nanos() as f64 / f64::from(CYCLES);
+
+    println!(
+        "{}: {} cycles in {:.2} ms: {:.1} ns per cycle ({:.0} kHz square wave)",
+        name,
+        CYCLES,
+        elapsed.as_secs_f64() * 1000.0,
+        per_cycle_ns,
+        1_000_000.0 / per_cycle_ns
+    );
+}
+
+fn set_high_low(pin: &mut OutputPin, cycles: u32) -> Duration {
+    let start = Instant::now();
+
+    for _ in 0..cycles {
+        pin.set_high();
+        pin.set_low();
+    }
+
+    start.elapsed()
+}
+
+fn toggle(pin: &mut OutputPin, cycles: u32) -> Duration {
+    let start = Instant::now();
+
+    for _ in 0..cycles {
+        pin.toggle();
+        pin.toggle();
+    }
+
+    start.elapsed()
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    // Retrieve the GPIO pin and configure it as an output.
+    let mut pin = Gpio::new()?.get(GPIO_OUTPUT)?.into_output_low();
+
+    set_high_low(&mut pin, WARMUP_CYCLES);
+    report("set_high/set_low", set_high_low(&mut pin, CYCLES));
+
+    toggle(&mut pin, WARMUP_CYCLES);
+    report("toggle", toggle(&mut pin, CYCLES));
+
+    Ok(())
+}
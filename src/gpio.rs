@@ -1,8 +1,12 @@
 //! Interface for the GPIO peripheral.
 //!
 //! To ensure fast performance, RPPAL controls the GPIO peripheral by directly
-//! accessing the registers through either `/dev/gpiomem` or `/dev/mem`. GPIO interrupts
-//! are configured using the `gpiochip` character device.
+//! accessing the registers through either `/dev/gpiomem` or `/dev/mem`. On the Raspberry
+//! Pi 5, the GPIO pins are controlled by the RP1 I/O controller, which has a different
+//! register layout. RPPAL detects the RP1 automatically, and accesses its registers
+//! through `/dev/gpiomem0` instead. GPIO interrupts
+//! are configured using the `gpiochip` character device. RPPAL uses the character device's
+//! v2 uAPI, and falls back to the deprecated v1 uAPI on kernels older than 5.10.
 //!
 //! ## Pins
 //!
@@ -11,6 +15,7 @@
 //! mode and logic level. Converting the [`Pin`] to an [`InputPin`], [`OutputPin`] or
 //! [`IoPin`] through the various `into_` methods available on [`Pin`] configures the
 //! appropriate mode, and provides access to additional methods relevant to the selected pin mode.
+//! [`Pin::into_alt`] configures one of the pin's alternate functions, and returns an [`AltPin`].
 //!
 //! Retrieving a GPIO pin with [`Gpio::get`] grants access to the pin through an owned [`Pin`]
 //! instance. If the pin is already in use, or the GPIO peripheral doesn't expose a pin with
@@ -18,9 +23,19 @@
 //! (or a derived [`InputPin`], [`OutputPin`] or [`IoPin`]) goes out of scope, it can be
 //! retrieved again through another [`Gpio::get`] call.
 //!
+//! Pins remain fully functional after the [`Gpio`] instance they were retrieved from
+//! goes out of scope, including any configured interrupts. The shared GPIO state is only
+//! released after all [`Gpio`] and pin instances have been dropped, so pins can be moved
+//! to a different thread or component that outlives the [`Gpio`] instance.
+//!
+//! [`Gpio::get`] accepts either a `u8` or a [`BcmPin`]. The [`BcmPin`] and [`PhysicalPin`]
+//! newtypes make it explicit which numbering scheme is used, and can be converted into
+//! each other based on the GPIO header layout of the Raspberry Pi model.
+//!
 //! By default, pins are reset to their original state when they go out of scope.
 //! Use [`InputPin::set_reset_on_drop(false)`], [`OutputPin::set_reset_on_drop(false)`]
-//! or [`IoPin::set_reset_on_drop(false)`], respectively, to disable this behavior.
+//! or [`IoPin::set_reset_on_drop(false)`], respectively, to disable this behavior, or
+//! [`Gpio::set_reset_on_drop(false)`] to change the default for all pins configured afterwards.
 //! Note that `drop` methods aren't called when a process is abnormally terminated (for
 //! instance when a `SIGINT` signal isn't caught).
 //!
@@ -32,7 +47,9 @@
 //! An interrupt trigger for a single pin can be polled with [`InputPin::poll_interrupt`],
 //! which blocks the current thread until a trigger event occurs, or until the timeout period
 //! elapses. [`Gpio::poll_interrupts`] should be used when multiple pins have been configured
-//! for synchronous interrupt triggers, and need to be polled simultaneously.
+//! for synchronous interrupt triggers, and need to be polled simultaneously. [`Gpio::poll_any`]
+//! polls all pins with a synchronous interrupt trigger, and returns the BCM GPIO number of the
+//! pin that triggered.
 //!
 //! Asynchronous interrupt triggers are configured using [`InputPin::set_async_interrupt`]. The
 //! specified callback function will be executed on a separate thread when a trigger event occurs.
@@ -41,7 +58,10 @@
 //!
 //! [`OutputPin`] and [`IoPin`] feature a software-based PWM implementation. The PWM signal is
 //! emulated by toggling the pin's output state on a separate thread, combined with sleep and
-//! busy-waiting.
+//! busy-waiting. The PWM signals for all pins are multiplexed onto a single thread, which
+//! schedules the next output state change across all active signals, so adding more pins
+//! doesn't add more busy-waiting threads. Patterns played with [`OutputPin::play_pattern`]
+//! run on their own thread.
 //!
 //! Software-based PWM is inherently inaccurate on a multi-threaded OS due to scheduling/preemption.
 //! If an accurate or faster PWM signal is required, use the hardware [`Pwm`] peripheral instead.
@@ -49,7 +69,25 @@
 //! PWM threads may occasionally sleep longer than needed. If the active or inactive part of the
 //! signal is shorter than 250 µs, only busy-waiting is used, which will increase CPU usage. Due to
 //! function call overhead, typical jitter is expected to be up to 10 µs on debug builds, and up to
-//! 2 µs on release builds.
+//! 2 µs on release builds. [`OutputPin::pwm_jitter_stats`] reports the measured period error
+//! of an active PWM signal.
+//!
+//! [`SoftPwmGroup`] emulates PWM signals on multiple pins using a single thread. All
+//! signals share the same period, while the duty cycle and phase offset can be configured
+//! for each pin individually.
+//!
+//! ## Logging and dry runs
+//!
+//! When the optional `log` feature is enabled, every output level, mode and bias change
+//! made through [`Pin`], [`InputPin`], [`OutputPin`] and [`IoPin`] emits a `trace` level
+//! log entry with the BCM GPIO number and the new state, using the [`log`] crate.
+//!
+//! [`Gpio::set_dry_run`] skips the register writes for those same changes, which can be
+//! combined with logging to validate a sequence of GPIO operations without affecting any
+//! connected hardware.
+//!
+//! [`log`]: https://crates.io/crates/log
+//! [`Gpio::set_dry_run`]: struct.Gpio.html#method.set_dry_run
 //!
 //! ## Examples
 //!
@@ -99,7 +137,10 @@
 //! [raspberrypi/linux#2289]: https://github.com/raspberrypi/linux/issues/2289
 //! [`Gpio`]: struct.Gpio.html
 //! [`Gpio::get`]: struct.Gpio.html#method.get
+//! [`BcmPin`]: struct.BcmPin.html
+//! [`PhysicalPin`]: struct.PhysicalPin.html
 //! [`Gpio::poll_interrupts`]: struct.Gpio.html#method.poll_interrupts
+//! [`Gpio::poll_any`]: struct.Gpio.html#method.poll_any
 //! [`Pin`]: struct.Pin.html
 //! [`InputPin`]: struct.InputPin.html
 //! [`InputPin::set_reset_on_drop(false)`]: struct.InputPin.html#method.set_reset_on_drop
@@ -109,7 +150,13 @@
 //! [`OutputPin`]: struct.OutputPin.html
 //! [`OutputPin::set_reset_on_drop(false)`]: struct.OutputPin.html#method.set_reset_on_drop
 //! [`IoPin`]: struct.IoPin.html
+//! [`AltPin`]: struct.AltPin.html
+//! [`Pin::into_alt`]: struct.Pin.html#method.into_alt
 //! [`IoPin::set_reset_on_drop(false)`]: struct.IoPin.html#method.set_reset_on_drop
+//! [`Gpio::set_reset_on_drop(false)`]: struct.Gpio.html#method.set_reset_on_drop
+//! [`SoftPwmGroup`]: struct.SoftPwmGroup.html
+//! [`OutputPin::pwm_jitter_stats`]: struct.OutputPin.html#method.pwm_jitter_stats
+//! [`OutputPin::play_pattern`]: struct.OutputPin.html#method.play_pattern
 //! [`Pwm`]: ../pwm/struct.Pwm.html
 
 use std::error;
@@ -117,12 +164,16 @@ use std::fmt;
 use std::io;
 use std::mem::MaybeUninit;
 use std::ops::Not;
-use std::os::unix::io::AsRawFd;
+use std::os::unix::io::{AsRawFd, RawFd};
+use std::ptr;
 use std::result;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex, Once, Weak};
 use std::time::Duration;
 
+mod clock;
+mod dht;
+mod encoder;
 mod epoll;
 mod gpiomem;
 #[cfg(any(
@@ -133,15 +184,35 @@ mod gpiomem;
 mod hal;
 #[cfg(feature = "hal-unproven")]
 mod hal_unproven;
+mod hc_sr04;
 mod interrupt;
 mod ioctl;
+mod pads;
 mod pin;
+mod pin_group;
+mod rgb_led;
+mod shift_register;
 mod soft_pwm;
+mod soft_pwm_group;
+mod stepper;
 
 use crate::system;
 use crate::system::DeviceInfo;
 
-pub use self::pin::{InputPin, IoPin, OutputPin, Pin};
+pub use self::dht::{Dht, DhtModel, DhtReading};
+pub use self::encoder::Encoder;
+pub use self::hc_sr04::HcSr04;
+pub use self::interrupt::InterruptThreadSettings;
+pub use self::pin::{AltPin, InputPin, InputPinBuilder, IoPin, OutputPin, Pin};
+pub use self::pin_group::PinGroup;
+pub use self::rgb_led::{RgbLed, RgbLedChannel, RgbLedPolarity};
+pub use self::shift_register::ShiftRegister;
+pub use self::soft_pwm::JitterStats;
+pub use self::soft_pwm_group::SoftPwmGroup;
+pub use self::stepper::{StepMode, Stepper};
+
+// PCM/I2S pins (PCM_CLK, PCM_FS, PCM_DIN, PCM_DOUT)
+const PCM_PINS: [u8; 4] = [18, 19, 20, 21];
 
 /// Errors that can occur when accessing the GPIO peripheral.
 #[derive(Debug)]
@@ -174,6 +245,14 @@ pub enum Error {
     /// addressed by their BCM GPIO numbers, rather than their physical location on the GPIO
     /// header.
     PinNotAvailable(u8),
+    /// Pin hasn't been retrieved.
+    ///
+    /// Methods that access multiple pins directly through their registers require each
+    /// affected pin to be retrieved through [`Gpio::get`] first, to prevent accidentally
+    /// changing the state of pins that are in use elsewhere.
+    ///
+    /// [`Gpio::get`]: struct.Gpio.html#method.get
+    PinNotOwned(u8),
     /// Permission denied when opening `/dev/gpiomem`, `/dev/mem` or `/dev/gpiochipN` for
     /// read/write access.
     ///
@@ -185,6 +264,14 @@ pub enum Error {
     Io(io::Error),
     /// Thread panicked.
     ThreadPanic,
+    /// Timed out.
+    ///
+    /// A connected device didn't respond within the expected time.
+    Timeout,
+    /// Checksum mismatch.
+    ///
+    /// The data received from a connected device failed the checksum verification.
+    ChecksumMismatch,
 }
 
 impl fmt::Display for Error {
@@ -193,14 +280,24 @@ impl fmt::Display for Error {
             Error::UnknownModel => write!(f, "Unknown Raspberry Pi model"),
             Error::PinUsed(pin) => write!(f, "Pin {} is already in use", pin),
             Error::PinNotAvailable(pin) => write!(f, "Pin {} is not available", pin),
+            Error::PinNotOwned(pin) => write!(f, "Pin {} hasn't been retrieved", pin),
             Error::PermissionDenied(ref path) => write!(f, "Permission denied: {}", path),
             Error::Io(ref err) => write!(f, "I/O error: {}", err),
             Error::ThreadPanic => write!(f, "Thread panicked"),
+            Error::Timeout => write!(f, "Timed out"),
+            Error::ChecksumMismatch => write!(f, "Checksum mismatch"),
         }
     }
 }
 
-impl error::Error for Error {}
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            Error::Io(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
 
 impl From<io::Error> for Error {
     fn from(err: io::Error) -> Error {
@@ -320,6 +417,220 @@ impl fmt::Display for Bias {
     }
 }
 
+/// Device nodes used to access the GPIO registers.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum MemSource {
+    /// `/dev/gpiomem`, or `/dev/gpiomem0` on the Raspberry Pi 5. Provides access to
+    /// the GPIO registers without superuser privileges.
+    GpioMem,
+    /// `/dev/mem`. Provides access to all physical memory, which requires superuser
+    /// privileges.
+    DevMem,
+}
+
+impl fmt::Display for MemSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            MemSource::GpioMem => write!(f, "GpioMem"),
+            MemSource::DevMem => write!(f, "DevMem"),
+        }
+    }
+}
+
+/// Output slew rates.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum SlewRate {
+    /// Slew rate limited.
+    Slow,
+    /// Slew rate not limited.
+    Fast,
+}
+
+impl fmt::Display for SlewRate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            SlewRate::Slow => write!(f, "Slow"),
+            SlewRate::Fast => write!(f, "Fast"),
+        }
+    }
+}
+
+/// Line directions reported by the `gpiochip` character device.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum LineDirection {
+    /// The line is configured as an input.
+    Input,
+    /// The line is configured as an output.
+    Output,
+}
+
+impl fmt::Display for LineDirection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            LineDirection::Input => write!(f, "Input"),
+            LineDirection::Output => write!(f, "Output"),
+        }
+    }
+}
+
+/// Line information reported by the `gpiochip` character device.
+///
+/// Returned by [`Gpio::line_info`].
+///
+/// [`Gpio::line_info`]: struct.Gpio.html#method.line_info
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct LineInfo {
+    name: Option<String>,
+    consumer: Option<String>,
+    used: bool,
+    direction: LineDirection,
+    active_low: bool,
+    open_drain: bool,
+    open_source: bool,
+}
+
+impl LineInfo {
+    /// Returns the line name, or `None` if the line isn't named.
+    ///
+    /// Line names are assigned by the device tree, and usually match the pin labels
+    /// on the GPIO header, such as `GPIO17` or `ID_SDA`.
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Returns the label of the line's current consumer, or `None` if the consumer
+    /// didn't provide a label.
+    ///
+    /// Lines requested by kernel drivers are usually labeled after the driver or the
+    /// device tree node, while lines requested by `rppal` are labeled `RPPAL`.
+    pub fn consumer(&self) -> Option<&str> {
+        self.consumer.as_deref()
+    }
+
+    /// Returns `true` if the line is in use by a kernel driver, or has been requested
+    /// through the `gpiochip` character device by any process.
+    pub fn is_used(&self) -> bool {
+        self.used
+    }
+
+    /// Returns the line direction.
+    pub fn direction(&self) -> LineDirection {
+        self.direction
+    }
+
+    /// Returns `true` if the line is configured as active-low.
+    pub fn is_active_low(&self) -> bool {
+        self.active_low
+    }
+
+    /// Returns `true` if the line is configured as an open-drain output.
+    pub fn is_open_drain(&self) -> bool {
+        self.open_drain
+    }
+
+    /// Returns `true` if the line is configured as an open-source output.
+    pub fn is_open_source(&self) -> bool {
+        self.open_source
+    }
+}
+
+/// BCM GPIO number.
+///
+/// `BcmPin` identifies a pin by its BCM GPIO number, as used by [`Gpio::get`] and the
+/// SoC's datasheet. Methods that accept `impl Into<BcmPin>` can also be called with a
+/// plain `u8`, which is interpreted as a BCM GPIO number.
+///
+/// [`Gpio::get`]: struct.Gpio.html#method.get
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Copy, Clone)]
+pub struct BcmPin(pub u8);
+
+impl BcmPin {
+    /// Returns the physical pin number on the GPIO header.
+    ///
+    /// If the pin isn't available on the GPIO header, `to_physical` returns
+    /// `Err(`[`Error::PinNotAvailable`]`)`. If the Raspberry Pi model can't be identified,
+    /// `to_physical` returns `Err(`[`Error::UnknownModel`]`)`.
+    ///
+    /// [`Error::PinNotAvailable`]: enum.Error.html#variant.PinNotAvailable
+    /// [`Error::UnknownModel`]: enum.Error.html#variant.UnknownModel
+    pub fn to_physical(self) -> Result<PhysicalPin> {
+        DeviceInfo::new()
+            .map_err(|_| Error::UnknownModel)?
+            .physical_pin(self.0)
+            .map(PhysicalPin)
+            .ok_or(Error::PinNotAvailable(self.0))
+    }
+}
+
+impl From<u8> for BcmPin {
+    fn from(pin: u8) -> BcmPin {
+        BcmPin(pin)
+    }
+}
+
+impl From<BcmPin> for u8 {
+    fn from(pin: BcmPin) -> u8 {
+        pin.0
+    }
+}
+
+impl fmt::Display for BcmPin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "GPIO{}", self.0)
+    }
+}
+
+/// Physical pin number on the GPIO header.
+///
+/// Physical pins are numbered starting at 1, with odd numbers on the inner row of the
+/// header, and even numbers on the outer row. Use [`to_bcm`] to look up the
+/// corresponding BCM GPIO number.
+///
+/// [`to_bcm`]: #method.to_bcm
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Copy, Clone)]
+pub struct PhysicalPin(pub u8);
+
+impl PhysicalPin {
+    /// Returns the BCM GPIO number of the pin.
+    ///
+    /// If the pin isn't a GPIO pin (for instance, a power or ground pin), or the
+    /// Raspberry Pi model doesn't have a GPIO header pin with the specified number,
+    /// `to_bcm` returns `Err(`[`Error::PinNotAvailable`]`)`. If the Raspberry Pi model
+    /// can't be identified, `to_bcm` returns `Err(`[`Error::UnknownModel`]`)`.
+    ///
+    /// [`Error::PinNotAvailable`]: enum.Error.html#variant.PinNotAvailable
+    /// [`Error::UnknownModel`]: enum.Error.html#variant.UnknownModel
+    pub fn to_bcm(self) -> Result<BcmPin> {
+        let device_info = DeviceInfo::new().map_err(|_| Error::UnknownModel)?;
+
+        match device_info
+            .header_pins()
+            .get((self.0 as usize).wrapping_sub(1))
+        {
+            Some(&system::HeaderPin::Gpio(pin)) => Ok(BcmPin(pin)),
+            _ => Err(Error::PinNotAvailable(self.0)),
+        }
+    }
+}
+
+impl From<u8> for PhysicalPin {
+    fn from(pin: u8) -> PhysicalPin {
+        PhysicalPin(pin)
+    }
+}
+
+impl From<PhysicalPin> for u8 {
+    fn from(pin: PhysicalPin) -> u8 {
+        pin.0
+    }
+}
+
+impl fmt::Display for PhysicalPin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Pin {}", self.0)
+    }
+}
+
 /// Interrupt trigger conditions.
 #[derive(Debug, PartialEq, Eq, Copy, Clone)]
 pub enum Trigger {
@@ -348,6 +659,10 @@ pub(crate) struct GpioState {
     sync_interrupts: Mutex<interrupt::EventLoop>,
     pins_taken: [AtomicBool; u8::MAX as usize],
     gpio_lines: u8,
+    interrupt_thread_settings: Mutex<InterruptThreadSettings>,
+    dry_run: AtomicBool,
+    reset_on_drop: AtomicBool,
+    soft_pwm_scheduler: soft_pwm::Scheduler,
 }
 
 impl fmt::Debug for GpioState {
@@ -358,6 +673,10 @@ impl fmt::Debug for GpioState {
             .field("sync_interrupts", &self.sync_interrupts)
             .field("pins_taken", &format_args!("{{ .. }}"))
             .field("gpio_lines", &self.gpio_lines)
+            .field("interrupt_thread_settings", &self.interrupt_thread_settings)
+            .field("dry_run", &self.dry_run)
+            .field("reset_on_drop", &self.reset_on_drop)
+            .field("soft_pwm_scheduler", &self.soft_pwm_scheduler)
             .finish()
     }
 }
@@ -370,6 +689,24 @@ pub struct Gpio {
 
 impl Gpio {
     /// Constructs a new `Gpio`.
+    ///
+    /// `Gpio` instances are reference-counted handles to a single shared state, which
+    /// is initialized by the first call to `new`. Any further calls return a handle to
+    /// the existing state, rather than an error, so independent components of an
+    /// application can each construct their own `Gpio`. Cloning a `Gpio` has the same
+    /// effect. The shared state is released after all `Gpio` and pin instances have
+    /// gone out of scope.
+    ///
+    /// Pin ownership is tracked across all `Gpio` instances. A pin retrieved by one
+    /// component remains unavailable to all other components until it goes out of
+    /// scope, and [`get`] returns `Err(`[`Error::PinUsed`]`)` in the meantime. Settings
+    /// such as [`set_dry_run`] and [`set_reset_on_drop`] are shared as well, and affect
+    /// all `Gpio` instances.
+    ///
+    /// [`get`]: #method.get
+    /// [`Error::PinUsed`]: enum.Error.html#variant.PinUsed
+    /// [`set_dry_run`]: #method.set_dry_run
+    /// [`set_reset_on_drop`]: #method.set_reset_on_drop
     pub fn new() -> Result<Gpio> {
         // Replace this when std::sync::SyncLazy is stabilized. https://github.com/rust-lang/rust/issues/74465
 
@@ -380,14 +717,20 @@ impl Gpio {
         static ONCE: Once = Once::new();
 
         // call_once is thread-safe, guaranteed to be called only once, and memory writes performed
-        // by the closure can be observed by other threads after execution completes.
+        // by the closure can be observed by other threads after execution completes. GPIO_STATE
+        // is written through a raw pointer inside call_once, so no reference exists while it's
+        // being initialized. Afterwards, we only create shared references to it, which is sound
+        // because it's never written to again.
         let mut weak_state = unsafe {
             ONCE.call_once(|| {
-                GPIO_STATE.write(Mutex::new(Weak::new()));
+                (*ptr::addr_of_mut!(GPIO_STATE)).write(Mutex::new(Weak::new()));
             });
 
-            // GPIO_STATE will always be initialized at this point.
-            GPIO_STATE.assume_init_ref().lock().unwrap()
+            // GPIO_STATE will always be initialized at this point, and is never written to again.
+            (*ptr::addr_of!(GPIO_STATE))
+                .assume_init_ref()
+                .lock()
+                .unwrap()
         };
 
         // Clone a strong reference if a GpioState instance already exists, otherwise
@@ -404,7 +747,8 @@ impl Gpio {
                 system::GpioInterface::Rp1 => Box::new(gpiomem::rp1::GpioMem::open()?),
             };
 
-            let cdev = ioctl::find_gpiochip()?;
+            let cdev = ioctl::find_gpiochip(device_info.gpiochip_label())?;
+            ioctl::detect_v2(cdev.as_raw_fd());
             let sync_interrupts = Mutex::new(interrupt::EventLoop::new(
                 cdev.as_raw_fd(),
                 u8::MAX as usize,
@@ -418,6 +762,10 @@ impl Gpio {
                 sync_interrupts,
                 pins_taken,
                 gpio_lines,
+                interrupt_thread_settings: Mutex::new(InterruptThreadSettings::default()),
+                dry_run: AtomicBool::new(false),
+                reset_on_drop: AtomicBool::new(true),
+                soft_pwm_scheduler: soft_pwm::Scheduler::new(),
             });
 
             // Store a weak reference to our state. This gets dropped when
@@ -435,12 +783,31 @@ impl Gpio {
     /// After a [`Pin`] (or a derived [`InputPin`], [`OutputPin`] or [`IoPin`]) goes out
     /// of scope, it can be retrieved again through another `get` call.
     ///
+    /// The range of available pins depends on the SoC, rather than the GPIO header.
+    /// BCM GPIO 0-53 are available on the BCM283x, BCM GPIO 0-57 on the BCM2711, and
+    /// BCM GPIO 0-27 on the RP1 (Raspberry Pi 5). This includes the additional pins
+    /// routed out on Compute Module carrier boards. If the SoC doesn't have a pin with
+    /// the specified number, `get` returns `Err(`[`Error::PinNotAvailable`]`)`.
+    ///
+    /// Pins that aren't routed to the GPIO header are often used internally, for instance
+    /// for the SD card, Ethernet, Wi-Fi or Bluetooth. Changing their mode or state may
+    /// disrupt the Raspberry Pi's operation.
+    ///
+    /// `pin` can be either a [`BcmPin`] or a `u8`. To retrieve a pin by its location on
+    /// the GPIO header, convert a [`PhysicalPin`] with [`PhysicalPin::to_bcm`] first.
+    ///
     /// [`Pin`]: struct.Pin.html
     /// [`InputPin`]: struct.InputPin.html
     /// [`OutputPin`]: struct.OutputPin.html
     /// [`IoPin`]: struct.IoPin.html
     /// [`Error::PinUsed`]: enum.Error.html#variant.PinUsed
-    pub fn get(&self, pin: u8) -> Result<Pin> {
+    /// [`Error::PinNotAvailable`]: enum.Error.html#variant.PinNotAvailable
+    /// [`BcmPin`]: struct.BcmPin.html
+    /// [`PhysicalPin`]: struct.PhysicalPin.html
+    /// [`PhysicalPin::to_bcm`]: struct.PhysicalPin.html#method.to_bcm
+    pub fn get<P: Into<BcmPin>>(&self, pin: P) -> Result<Pin> {
+        let pin = pin.into().0;
+
         if pin >= self.inner.gpio_lines {
             return Err(Error::PinNotAvailable(pin));
         }
@@ -458,6 +825,722 @@ impl Gpio {
         }
     }
 
+    /// Returns an [`OutputPin`] for the specified pin that's driving `off_level`, and
+    /// keeps driving `off_level` when it goes out of scope.
+    ///
+    /// `get_safe_output` is intended for relays, motor drivers and other external
+    /// hardware that must stay in a safe state. The output latch is set to `off_level`
+    /// before the mode is changed to [`Mode::Output`], so the pin never briefly drives
+    /// any other level. The pin's drop state is set to [`Mode::Output`] and `off_level`,
+    /// and [`reset_on_drop`] is set to `true` regardless of the default configured with
+    /// [`set_reset_on_drop`], which ensures the pin returns to `off_level` when the
+    /// [`OutputPin`] is dropped, rather than floating.
+    ///
+    /// This is equivalent to calling [`get`], followed by [`Pin::into_output_low`] or
+    /// [`Pin::into_output_high`], [`OutputPin::set_reset_on_drop`] and
+    /// [`OutputPin::set_drop_state`]. Any errors are identical to those returned by [`get`].
+    ///
+    /// [`OutputPin`]: struct.OutputPin.html
+    /// [`Mode::Output`]: enum.Mode.html#variant.Output
+    /// [`reset_on_drop`]: struct.OutputPin.html#method.set_reset_on_drop
+    /// [`set_reset_on_drop`]: #method.set_reset_on_drop
+    /// [`get`]: #method.get
+    /// [`Pin::into_output_low`]: struct.Pin.html#method.into_output_low
+    /// [`Pin::into_output_high`]: struct.Pin.html#method.into_output_high
+    /// [`OutputPin::set_reset_on_drop`]: struct.OutputPin.html#method.set_reset_on_drop
+    /// [`OutputPin::set_drop_state`]: struct.OutputPin.html#method.set_drop_state
+    pub fn get_safe_output<P: Into<BcmPin>>(&self, pin: P, off_level: Level) -> Result<OutputPin> {
+        let pin = self.get(pin)?;
+
+        let mut output = match off_level {
+            Level::Low => pin.into_output_low(),
+            Level::High => pin.into_output_high(),
+        };
+
+        output.set_reset_on_drop(true);
+        output.set_drop_state(Mode::Output, off_level);
+
+        Ok(output)
+    }
+
+    /// Marks the specified pin as available, so it can be retrieved again through [`get`].
+    ///
+    /// Pins are automatically made available when their [`Pin`] (or a derived
+    /// [`InputPin`], [`OutputPin`] or [`IoPin`]) goes out of scope. `release` is an escape
+    /// hatch for situations where that isn't possible, for instance when an instance
+    /// was leaked using [`mem::forget`]. The pin's mode and state aren't changed.
+    ///
+    /// If the SoC doesn't have a pin with the specified number, `release` returns
+    /// `Err(`[`Error::PinNotAvailable`]`)`.
+    ///
+    /// ## Note
+    ///
+    /// `release` doesn't invalidate any existing instances for the specified pin.
+    /// Retrieving the pin again while another instance is still in use allows
+    /// both instances to simultaneously change the pin's configuration.
+    ///
+    /// [`get`]: #method.get
+    /// [`Pin`]: struct.Pin.html
+    /// [`InputPin`]: struct.InputPin.html
+    /// [`OutputPin`]: struct.OutputPin.html
+    /// [`IoPin`]: struct.IoPin.html
+    /// [`mem::forget`]: https://doc.rust-lang.org/std/mem/fn.forget.html
+    /// [`Error::PinNotAvailable`]: enum.Error.html#variant.PinNotAvailable
+    pub fn release(&self, pin: u8) -> Result<()> {
+        if pin >= self.inner.gpio_lines {
+            return Err(Error::PinNotAvailable(pin));
+        }
+
+        self.inner.pins_taken[pin as usize].store(false, Ordering::SeqCst);
+
+        Ok(())
+    }
+
+    /// Returns the scheduling settings for the threads that handle asynchronous interrupts.
+    pub fn interrupt_thread_settings(&self) -> InterruptThreadSettings {
+        *self.inner.interrupt_thread_settings.lock().unwrap()
+    }
+
+    /// Sets the scheduling settings for the threads that handle asynchronous interrupts.
+    ///
+    /// Each asynchronous interrupt configured with [`InputPin::set_async_interrupt`]
+    /// runs on a separate polling thread. The settings are applied to interrupts that
+    /// are configured after `set_interrupt_thread_settings` is called. Existing
+    /// interrupts aren't affected.
+    ///
+    /// The settings are shared between all `Gpio` instances. If the settings can't be
+    /// applied, for instance because the current user isn't allowed to set a real-time
+    /// priority, [`InputPin::set_async_interrupt`] returns `Err(`[`Error::Io`]`)`.
+    ///
+    /// [`InputPin::set_async_interrupt`]: struct.InputPin.html#method.set_async_interrupt
+    /// [`Error::Io`]: enum.Error.html#variant.Io
+    pub fn set_interrupt_thread_settings(&self, settings: InterruptThreadSettings) {
+        *self.inner.interrupt_thread_settings.lock().unwrap() = settings;
+    }
+
+    /// Returns the default value of `reset_on_drop` for newly configured pins.
+    pub fn reset_on_drop(&self) -> bool {
+        self.inner.reset_on_drop.load(Ordering::SeqCst)
+    }
+
+    /// Sets the default value of `reset_on_drop` for pins that are configured afterwards.
+    ///
+    /// When a [`Pin`] is converted to an [`InputPin`], [`OutputPin`], [`IoPin`] or
+    /// [`AltPin`], or it's configured as part of a [`PinGroup`], the converted pin's
+    /// `reset_on_drop` value is initialized to the default configured through
+    /// `set_reset_on_drop`. Individual pins can still override the default by calling
+    /// their own `set_reset_on_drop` method. Pins that have already been configured
+    /// aren't affected.
+    ///
+    /// The default is shared between all `Gpio` instances, and is set to `true` until
+    /// it's changed. When set to `false`, pins keep their mode, output level and bias
+    /// after they go out of scope, and after the process exits. Resetting the pins to
+    /// a safe state when they're no longer needed is then up to your application.
+    ///
+    /// [`Pin`]: struct.Pin.html
+    /// [`InputPin`]: struct.InputPin.html
+    /// [`OutputPin`]: struct.OutputPin.html
+    /// [`IoPin`]: struct.IoPin.html
+    /// [`AltPin`]: struct.AltPin.html
+    /// [`PinGroup`]: struct.PinGroup.html
+    pub fn set_reset_on_drop(&self, reset_on_drop: bool) {
+        self.inner
+            .reset_on_drop
+            .store(reset_on_drop, Ordering::SeqCst);
+    }
+
+    /// Returns the device node that was used to access the GPIO registers.
+    ///
+    /// RPPAL tries `/dev/gpiomem` first, and falls back to `/dev/mem` if `/dev/gpiomem`
+    /// can't be opened. `/dev/mem` requires superuser privileges. More information on
+    /// permission issues can be found [here].
+    ///
+    /// [here]: index.html#permission-denied
+    pub fn mem_source(&self) -> MemSource {
+        self.inner.gpio_mem.mem_source()
+    }
+
+    /// Returns the output drive strength in milliamperes (mA) configured in the pad
+    /// control registers for the specified BCM GPIO number.
+    ///
+    /// `drive_strength` reads the current configuration without taking ownership of
+    /// the pin, so it can be used for any pin, including pins that are in use. On the
+    /// Raspberry Pi 5, Raspberry Pi 500 and Compute Module 5, the drive strength is
+    /// configured per pin, and is set to 2, 4, 8 or 12 mA.
+    ///
+    /// On earlier models, the BCM283x and BCM2711 pad control registers configure the
+    /// drive strength for a group of pins at once, and `drive_strength` returns the
+    /// value of the group the pin belongs to (GPIO 0-27, GPIO 28-45 or GPIO 46-53),
+    /// which is set to a multiple of 2 mA between 2 and 16 mA. The pad control registers
+    /// aren't accessible through `/dev/gpiomem`, so reading them requires superuser
+    /// privileges to access `/dev/mem`. Without access, `drive_strength` returns
+    /// `Err(`[`Error::PermissionDenied`]`)`.
+    ///
+    /// If the GPIO peripheral doesn't expose a pin with the specified number,
+    /// `drive_strength` returns `Err(`[`Error::PinNotAvailable`]`)`.
+    ///
+    /// [`Error::PermissionDenied`]: enum.Error.html#variant.PermissionDenied
+    /// [`Error::PinNotAvailable`]: enum.Error.html#variant.PinNotAvailable
+    pub fn drive_strength<P: Into<BcmPin>>(&self, pin: P) -> Result<u8> {
+        let pin = pin.into().0;
+
+        if pin >= self.inner.gpio_lines {
+            return Err(Error::PinNotAvailable(pin));
+        }
+
+        match self.inner.gpio_mem.drive_strength(pin) {
+            Some(drive_strength) => Ok(drive_strength),
+            None => Ok(pads::PadControl::open()?.drive_strength(pin)),
+        }
+    }
+
+    /// Returns the output slew rate configured in the pad control registers for the
+    /// specified BCM GPIO number.
+    ///
+    /// `slew_rate` behaves similarly to [`drive_strength`]. On the BCM283x and BCM2711,
+    /// the slew rate is configured for a group of pins at once, and `slew_rate`
+    /// returns the value of the group the pin belongs to.
+    ///
+    /// [`drive_strength`]: #method.drive_strength
+    pub fn slew_rate<P: Into<BcmPin>>(&self, pin: P) -> Result<SlewRate> {
+        let pin = pin.into().0;
+
+        if pin >= self.inner.gpio_lines {
+            return Err(Error::PinNotAvailable(pin));
+        }
+
+        match self.inner.gpio_mem.slew_rate(pin) {
+            Some(slew_rate) => Ok(slew_rate),
+            None => Ok(pads::PadControl::open()?.slew_rate(pin)),
+        }
+    }
+
+    /// Returns the I/O voltage in volts (V) selected for the specified bank of pads,
+    /// or `None` if the information isn't available.
+    ///
+    /// On the Raspberry Pi 5, Raspberry Pi 500 and Compute Module 5, the RP1 reports
+    /// whether each of its 3 banks of pads is configured for 3.3 V or 1.8 V. Bank 0
+    /// contains GPIO 0-27, which are available on the GPIO header. Banks 1 and 2
+    /// contain GPIO 28-33 and GPIO 34-53, which are used internally.
+    ///
+    /// The BCM283x and BCM2711 don't provide a voltage selection that can be read by
+    /// software, so `bank_voltage` always returns `None` on earlier models, even on
+    /// Compute Module carrier boards that set a different bank voltage in hardware.
+    /// `bank_voltage` also returns `None` for banks that don't exist.
+    pub fn bank_voltage(&self, bank: u8) -> Option<f32> {
+        self.inner.gpio_mem.bank_voltage(bank)
+    }
+
+    /// Returns the line information the `gpiochip` character device reports for the
+    /// specified BCM GPIO number.
+    ///
+    /// `line_info` can be used to check whether a pin is claimed by a kernel driver,
+    /// such as the I2C or 1-Wire driver, or by another process, before configuring it.
+    /// Pins retrieved through [`get`] are controlled through the GPIO registers
+    /// directly, and only show up as used while they have an interrupt configured.
+    /// Likewise, pins claimed by other processes through `/dev/gpiomem` or `/dev/mem`
+    /// aren't reported as used.
+    ///
+    /// The line information is read-only, and reflects the pin's state at the time of
+    /// the call.
+    ///
+    /// If the GPIO peripheral doesn't expose a pin with the specified number,
+    /// `line_info` returns `Err(`[`Error::PinNotAvailable`]`)`.
+    ///
+    /// [`get`]: #method.get
+    /// [`Error::PinNotAvailable`]: enum.Error.html#variant.PinNotAvailable
+    pub fn line_info<P: Into<BcmPin>>(&self, pin: P) -> Result<LineInfo> {
+        let pin = pin.into().0;
+
+        if pin >= self.inner.gpio_lines {
+            return Err(Error::PinNotAvailable(pin));
+        }
+
+        let line_info = ioctl::line_info(self.inner.cdev.as_raw_fd(), u32::from(pin))?;
+        let name = line_info.name();
+        let consumer = line_info.consumer();
+
+        Ok(LineInfo {
+            name: if name.is_empty() { None } else { Some(name) },
+            consumer: if consumer.is_empty() {
+                None
+            } else {
+                Some(consumer)
+            },
+            used: line_info.used(),
+            direction: if line_info.output() {
+                LineDirection::Output
+            } else {
+                LineDirection::Input
+            },
+            active_low: line_info.active_low(),
+            open_drain: line_info.open_drain(),
+            open_source: line_info.open_source(),
+        })
+    }
+
+    /// Returns `true` if dry-run mode is enabled.
+    pub fn dry_run(&self) -> bool {
+        self.inner.dry_run.load(Ordering::SeqCst)
+    }
+
+    /// When enabled, output level, mode and bias changes made through [`Pin`],
+    /// [`InputPin`], [`OutputPin`] and [`IoPin`] aren't written to the GPIO registers.
+    ///
+    /// Dry-run mode is shared between all `Gpio` instances, and is disabled by default.
+    /// Combined with the optional `log` feature, this can be used to trace a sequence
+    /// of GPIO operations without affecting any connected hardware. More information
+    /// can be found [here].
+    ///
+    /// ## Note
+    ///
+    /// Reading a pin's logic level or mode still accesses the GPIO registers. While
+    /// dry-run mode is enabled, [`OutputPin::is_set_high`] and [`OutputPin::is_set_low`]
+    /// return the intended output state. Software-based PWM, [`PinGroup`],
+    /// [`write_bank`], [`write_register`], [`set_clock`], [`get_pcm`] and interrupts
+    /// aren't affected by dry-run mode.
+    ///
+    /// [`Pin`]: struct.Pin.html
+    /// [`InputPin`]: struct.InputPin.html
+    /// [`OutputPin`]: struct.OutputPin.html
+    /// [`IoPin`]: struct.IoPin.html
+    /// [`OutputPin::is_set_high`]: struct.OutputPin.html#method.is_set_high
+    /// [`OutputPin::is_set_low`]: struct.OutputPin.html#method.is_set_low
+    /// [`PinGroup`]: struct.PinGroup.html
+    /// [`write_bank`]: #method.write_bank
+    /// [`write_register`]: #method.write_register
+    /// [`set_clock`]: #method.set_clock
+    /// [`get_pcm`]: #method.get_pcm
+    /// [here]: index.html#logging-and-dry-runs
+    pub fn set_dry_run(&self, dry_run: bool) {
+        self.inner.dry_run.store(dry_run, Ordering::SeqCst);
+    }
+
+    /// Returns the BCM GPIO numbers of all pins that are currently in use.
+    ///
+    /// A pin is in use from the moment it's retrieved through [`get`] (or as part of a
+    /// [`PinGroup`]), until its [`Pin`] (or a derived [`InputPin`], [`OutputPin`] or
+    /// [`IoPin`]) goes out of scope. `taken_pins` only reports pins in use by the current
+    /// process. Pins used by other processes or kernel drivers aren't included.
+    ///
+    /// [`get`]: #method.get
+    /// [`PinGroup`]: struct.PinGroup.html
+    /// [`Pin`]: struct.Pin.html
+    /// [`InputPin`]: struct.InputPin.html
+    /// [`OutputPin`]: struct.OutputPin.html
+    /// [`IoPin`]: struct.IoPin.html
+    pub fn taken_pins(&self) -> Vec<u8> {
+        (0..self.inner.gpio_lines)
+            .filter(|&pin| self.inner.pins_taken[pin as usize].load(Ordering::SeqCst))
+            .collect()
+    }
+
+    /// Resets all pins on the GPIO header to [`Input`] mode with the built-in
+    /// pull-up/pull-down resistors disabled, and returns the BCM GPIO numbers of the
+    /// pins that were reset.
+    ///
+    /// `reset_all` is intended to restore a known state after another application exited
+    /// without cleaning up. Pins that are currently in use by this application are skipped.
+    /// If `keep_alt_functions` is set to `true`, pins that are configured for one of the
+    /// alternate functions (for instance, I2C, SPI or UART pins) are skipped as well.
+    ///
+    /// Compute Modules don't have a GPIO header, so `reset_all` resets BCM GPIO 0-27
+    /// instead.
+    ///
+    /// ## Note
+    ///
+    /// `reset_all` changes the state of pins that may be in use by other applications
+    /// or kernel drivers, which can interrupt their operation, or damage connected
+    /// hardware that relies on a pin being driven high or low. Only call `reset_all` when
+    /// you know none of the pins on the GPIO header are in use elsewhere, or set
+    /// `keep_alt_functions` to `true` to leave any pins used by peripherals untouched.
+    ///
+    /// [`Input`]: enum.Mode.html#variant.Input
+    pub fn reset_all(&self, keep_alt_functions: bool) -> Result<Vec<u8>> {
+        let device_info = DeviceInfo::new().map_err(|_| Error::UnknownModel)?;
+        let mut pins: Vec<u8> = device_info
+            .header_pins()
+            .iter()
+            .filter_map(|&header_pin| match header_pin {
+                system::HeaderPin::Gpio(pin) => Some(pin),
+                _ => None,
+            })
+            .collect();
+
+        if pins.is_empty() {
+            pins = (0..28).collect();
+        }
+
+        pins.sort_unstable();
+        pins.retain(|&pin| {
+            if pin >= self.inner.gpio_lines
+                || self.inner.pins_taken[pin as usize].load(Ordering::SeqCst)
+            {
+                return false;
+            }
+
+            !keep_alt_functions
+                || matches!(
+                    self.inner.gpio_mem.mode(pin),
+                    Mode::Input | Mode::Output | Mode::Null
+                )
+        });
+
+        if !self.dry_run() {
+            for &pin in &pins {
+                self.inner.gpio_mem.set_mode(pin, Mode::Input);
+                self.inner.gpio_mem.set_bias(pin, Bias::Off);
+            }
+        }
+
+        Ok(pins)
+    }
+
+    /// Returns a [`PinGroup`] for the specified BCM GPIO numbers.
+    ///
+    /// `pins` lists the BCM GPIO numbers in bit order, starting with the least
+    /// significant bit. Each pin's mode is set to `mode`.
+    ///
+    /// If any of the pins is already in use, `get_group` returns `Err(`[`Error::PinUsed`]`)`.
+    /// A [`PinGroup`] can contain up to 32 pins. If more pins are specified,
+    /// `get_group` returns `Err(`[`Error::PinNotAvailable`]`)` for the first pin
+    /// that doesn't fit.
+    ///
+    /// [`PinGroup`]: struct.PinGroup.html
+    /// [`Error::PinUsed`]: enum.Error.html#variant.PinUsed
+    /// [`Error::PinNotAvailable`]: enum.Error.html#variant.PinNotAvailable
+    pub fn get_group(&self, pins: &[u8], mode: Mode) -> Result<PinGroup> {
+        if pins.len() > 32 {
+            return Err(Error::PinNotAvailable(pins[32]));
+        }
+
+        let mut group = Vec::with_capacity(pins.len());
+        for &pin in pins {
+            group.push(self.get(pin)?.into_io(mode));
+        }
+
+        Ok(PinGroup::new(group))
+    }
+
+    /// Reads the logic levels of 32 consecutive pins at once.
+    ///
+    /// Each bit in the returned value represents a single pin. Bank 0 covers BCM GPIO 0-31,
+    /// where bit 0 corresponds to BCM GPIO 0 and bit 31 to BCM GPIO 31. Bank 1 covers BCM
+    /// GPIO 32-53 (BCM283x) or 32-57 (BCM2711), where bit 0 corresponds to BCM GPIO 32.
+    /// Bits that fall outside the available pins are undefined. The RP1 (Raspberry Pi 5)
+    /// only has bank 0, which covers BCM GPIO 0-27.
+    ///
+    /// `read_bank` reads the levels directly from the GPIO registers, and doesn't require
+    /// the pins to be retrieved first. If the bank doesn't exist, `read_bank` returns
+    /// `Err(`[`Error::PinNotAvailable`]`)` for the bank's first pin.
+    ///
+    /// [`Error::PinNotAvailable`]: enum.Error.html#variant.PinNotAvailable
+    pub fn read_bank(&self, bank: u8) -> Result<u32> {
+        self.check_bank(bank)?;
+
+        Ok(self.inner.gpio_mem.bank_levels(bank))
+    }
+
+    /// Sets the output state of multiple pins in a single bank at once.
+    ///
+    /// Pins that correspond to bits set in `set_mask` are set to [`High`], and pins that
+    /// correspond to bits set in `clear_mask` are set to [`Low`]. If a bit is set in both
+    /// masks, the pin is set to [`Low`]. All other pins are left unchanged. The mapping
+    /// between banks, bits and pins is described in the documentation for [`read_bank`].
+    ///
+    /// On the BCM283x and BCM2711, pins set to [`High`] and pins set to [`Low`] are
+    /// changed by two consecutive register writes. On the RP1 (Raspberry Pi 5), all
+    /// pins change state simultaneously.
+    ///
+    /// Each affected pin needs to be retrieved through [`get`] before calling `write_bank`,
+    /// and should be configured as an output. If an affected pin hasn't been retrieved,
+    /// `write_bank` returns `Err(`[`Error::PinNotOwned`]`)`. If the bank or any of the
+    /// affected pins don't exist, `write_bank` returns `Err(`[`Error::PinNotAvailable`]`)`.
+    ///
+    /// [`High`]: enum.Level.html#variant.High
+    /// [`Low`]: enum.Level.html#variant.Low
+    /// [`read_bank`]: #method.read_bank
+    /// [`get`]: #method.get
+    /// [`Error::PinNotOwned`]: enum.Error.html#variant.PinNotOwned
+    /// [`Error::PinNotAvailable`]: enum.Error.html#variant.PinNotAvailable
+    pub fn write_bank(&self, bank: u8, set_mask: u32, clear_mask: u32) -> Result<()> {
+        self.check_bank(bank)?;
+
+        let mask = set_mask | clear_mask;
+        for bit in 0..32u8 {
+            if mask & (1 << bit) == 0 {
+                continue;
+            }
+
+            let pin = bank * 32 + bit;
+            if pin >= self.inner.gpio_lines {
+                return Err(Error::PinNotAvailable(pin));
+            }
+
+            if !self.inner.pins_taken[pin as usize].load(Ordering::SeqCst) {
+                return Err(Error::PinNotOwned(pin));
+            }
+        }
+
+        self.inner
+            .gpio_mem
+            .write_bank(bank, mask, set_mask & !clear_mask);
+
+        Ok(())
+    }
+
+    /// Samples the logic levels of the specified BCM GPIO numbers at a fixed interval.
+    ///
+    /// `capture` reads the GPIO level registers `samples` times, waiting `sample_interval`
+    /// in between samples, and returns the packed samples. `pins` lists the BCM GPIO
+    /// numbers in bit order, so bit 0 of each sample contains the level of the first pin.
+    /// Up to 32 pins can be sampled at once.
+    ///
+    /// Similar to [`read_bank`], `capture` reads the levels directly from the GPIO
+    /// registers, and doesn't require the pins to be retrieved first. This makes it
+    /// possible to monitor pins that are in use by other peripherals, like a basic logic
+    /// analyzer. The buffers are allocated before the capture starts, and the samples are
+    /// timed by busy-waiting the same way as [`delay_us`], relative to the start of the
+    /// capture, so timing errors don't add up over time.
+    ///
+    /// If more than 32 pins are specified, `capture` returns
+    /// `Err(`[`Error::PinNotAvailable`]`)` for the first pin that doesn't fit. If the GPIO
+    /// peripheral doesn't expose one of the pins, `capture` returns
+    /// `Err(`[`Error::PinNotAvailable`]`)` for that pin.
+    ///
+    /// ## Note
+    ///
+    /// `capture` keeps a CPU core fully occupied for the entire duration. The calling
+    /// thread can still be preempted, which delays the affected samples. Running the
+    /// thread with a real-time scheduling policy reduces the chance of that happening.
+    /// Sampling pins in both banks requires two register reads per sample, which slightly
+    /// increases the minimum sample interval.
+    ///
+    /// [`read_bank`]: #method.read_bank
+    /// [`delay_us`]: fn.delay_us.html
+    /// [`Error::PinNotAvailable`]: enum.Error.html#variant.PinNotAvailable
+    pub fn capture(
+        &self,
+        pins: &[u8],
+        samples: usize,
+        sample_interval: Duration,
+    ) -> Result<Vec<u32>> {
+        if pins.len() > 32 {
+            return Err(Error::PinNotAvailable(pins[32]));
+        }
+
+        if let Some(&pin) = pins.iter().find(|&&pin| pin >= self.inner.gpio_lines) {
+            return Err(Error::PinNotAvailable(pin));
+        }
+
+        let gpio_mem = &self.inner.gpio_mem;
+        let read_bank1 = pins.iter().any(|&pin| pin >= 32);
+        let mut bank0 = vec![0u32; samples];
+        let mut bank1 = vec![0u32; if read_bank1 { samples } else { 0 }];
+        let interval_ns = sample_interval.as_nanos().min(i64::MAX as u128) as i64;
+
+        // Calibrate before the start time is sampled, so the first sample isn't delayed
+        soft_pwm::time_overhead_ns();
+
+        let start_ns = soft_pwm::get_time_ns();
+        for sample in 0..samples {
+            soft_pwm::busy_wait_until_ns(
+                start_ns.saturating_add(interval_ns.saturating_mul(sample as i64)),
+            );
+
+            bank0[sample] = gpio_mem.bank_levels(0);
+            if read_bank1 {
+                bank1[sample] = gpio_mem.bank_levels(1);
+            }
+        }
+
+        // Pack the samples in place, now that timing no longer matters
+        for sample in 0..samples {
+            let mut packed = 0;
+            for (bit, &pin) in pins.iter().enumerate() {
+                let levels = if pin >= 32 {
+                    bank1[sample]
+                } else {
+                    bank0[sample]
+                };
+                if levels & (1 << (pin % 32)) > 0 {
+                    packed |= 1 << bit;
+                }
+            }
+
+            bank0[sample] = packed;
+        }
+
+        Ok(bank0)
+    }
+
+    fn check_bank(&self, bank: u8) -> Result<()> {
+        if u16::from(bank) * 32 >= u16::from(self.inner.gpio_lines) {
+            return Err(Error::PinNotAvailable(bank.saturating_mul(32)));
+        }
+
+        Ok(())
+    }
+
+    /// Returns a [`PinGroup`] containing the PCM/I2S pins, configured for their
+    /// PCM/I2S alternate function.
+    ///
+    /// `get_pcm` is a convenience method that retrieves the PCM/I2S pins in the
+    /// following order, and changes their mode to [`Alt0`] on the BCM283x and BCM2711,
+    /// or [`Alt2`] on the RP1 (Raspberry Pi 5).
+    ///
+    /// * PCM_CLK (bit clock): BCM GPIO 18 (physical pin 12)
+    /// * PCM_FS (frame sync/word select): BCM GPIO 19 (physical pin 35)
+    /// * PCM_DIN (data in): BCM GPIO 20 (physical pin 38)
+    /// * PCM_DOUT (data out): BCM GPIO 21 (physical pin 40)
+    ///
+    /// RPPAL doesn't provide an interface for the PCM/I2S peripheral itself. The pins are
+    /// typically used by a kernel driver, or by an external audio codec whose control
+    /// interface is managed separately, for instance through [`I2c`].
+    ///
+    /// If any of the pins is already in use, `get_pcm` returns `Err(`[`Error::PinUsed`]`)`.
+    /// When the [`PinGroup`] goes out of scope, the pins are reset to their original
+    /// mode, unless [`PinGroup::set_reset_on_drop`] is set to `false`.
+    ///
+    /// [`PinGroup`]: struct.PinGroup.html
+    /// [`PinGroup::set_reset_on_drop`]: struct.PinGroup.html#method.set_reset_on_drop
+    /// [`Alt0`]: enum.Mode.html#variant.Alt0
+    /// [`Alt2`]: enum.Mode.html#variant.Alt2
+    /// [`I2c`]: ../i2c/struct.I2c.html
+    /// [`Error::PinUsed`]: enum.Error.html#variant.PinUsed
+    pub fn get_pcm(&self) -> Result<PinGroup> {
+        let mode = match DeviceInfo::new()
+            .map_err(|_| Error::UnknownModel)?
+            .gpio_interface()
+        {
+            system::GpioInterface::Bcm => Mode::Alt0,
+            system::GpioInterface::Rp1 => Mode::Alt2,
+        };
+
+        self.get_group(&PCM_PINS, mode)
+    }
+
+    /// Outputs a hardware-generated clock signal on the specified pin, and returns the
+    /// effective frequency.
+    ///
+    /// The BCM283x and BCM2711 SoCs have three general purpose clocks (GPCLK0-2),
+    /// which can be output on the following pins.
+    ///
+    /// * GPCLK0: BCM GPIO 4 (physical pin 7), 20 (physical pin 38), 32, 34
+    /// * GPCLK1: BCM GPIO 5 (physical pin 29), 21 (physical pin 40), 42, 44
+    /// * GPCLK2: BCM GPIO 6 (physical pin 31), 43
+    ///
+    /// `frequency` is specified in hertz (Hz). The clock is derived from either the
+    /// oscillator (19.2 MHz on the BCM283x, 54 MHz on the BCM2711) or PLLD (500 MHz on
+    /// the BCM283x, 750 MHz on the BCM2711), using an integer divider between 2 and 4095.
+    /// `set_clock` selects the clock source that results in the frequency closest to the
+    /// requested one. Because the requested frequency is quantized, make sure to check
+    /// the returned value.
+    ///
+    /// Setting `frequency` to `0.0` stops the clock, and changes the pin's mode to
+    /// [`Input`]. The clock keeps running after `Gpio` goes out of scope.
+    ///
+    /// If the pin is already in use, `set_clock` returns `Err(`[`Error::PinUsed`]`)`.
+    /// If the pin doesn't have a GPCLK output, `set_clock` returns `Err(`[`Error::Io`]`)`
+    /// with an `InvalidInput` error kind.
+    ///
+    /// ## Note
+    ///
+    /// The clock manager isn't accessible through `/dev/gpiomem`, which means `set_clock`
+    /// requires superuser privileges to access `/dev/mem`. GPCLK outputs aren't supported
+    /// on the Raspberry Pi 5.
+    ///
+    /// Some GPCLK outputs may already be in use by other peripherals. Changing their
+    /// settings could disrupt the Raspberry Pi's operation.
+    ///
+    /// [`Input`]: enum.Mode.html#variant.Input
+    /// [`Error::PinUsed`]: enum.Error.html#variant.PinUsed
+    /// [`Error::Io`]: enum.Error.html#variant.Io
+    pub fn set_clock(&self, pin: u8, frequency: f64) -> Result<f64> {
+        let (clock, mode) = match clock::gpclk(pin) {
+            Some(gpclk) if pin < self.inner.gpio_lines => gpclk,
+            _ => return Err(Error::Io(io::Error::from(io::ErrorKind::InvalidInput))),
+        };
+
+        if self.inner.pins_taken[pin as usize].load(Ordering::SeqCst) {
+            return Err(Error::PinUsed(pin));
+        }
+
+        let clock_manager = clock::ClockManager::open()?;
+
+        if frequency <= 0.0 {
+            clock_manager.disable(clock);
+            self.inner.gpio_mem.set_mode(pin, Mode::Input);
+
+            return Ok(0.0);
+        }
+
+        let frequency = clock_manager.enable(clock, frequency);
+        self.inner.gpio_mem.set_mode(pin, mode);
+
+        Ok(frequency)
+    }
+
+    /// Reads the raw value of a memory-mapped GPIO register.
+    ///
+    /// `offset` is specified in bytes, relative to the start of the memory-mapped GPIO
+    /// register area. On the BCM283x and BCM2711, this is the GPIO peripheral's base address
+    /// (for instance, `0x40` for `GPEDS0`). On the RP1 (Raspberry Pi 5), this is the start
+    /// of `IO_BANK0`, followed by `SYS_RIO0` at `0x10000` and `PADS_BANK0` at `0x20000`.
+    ///
+    /// Returns `Err(`[`Error::Io`]`)` with an `InvalidInput` error kind if `offset` isn't
+    /// aligned to a 32-bit register, or falls outside the memory-mapped area.
+    ///
+    /// ## Safety
+    ///
+    /// Reading some registers has side effects, and may interfere with other processes or
+    /// with the pin state tracked by RPPAL. Refer to the SoC's datasheet before accessing
+    /// any registers directly.
+    ///
+    /// [`Error::Io`]: enum.Error.html#variant.Io
+    pub unsafe fn read_register(&self, offset: usize) -> Result<u32> {
+        self.check_register_offset(offset)?;
+
+        Ok(self.inner.gpio_mem.read_register(offset))
+    }
+
+    /// Writes a raw value to a memory-mapped GPIO register.
+    ///
+    /// `offset` is specified in bytes, relative to the start of the memory-mapped GPIO
+    /// register area. More information can be found at [`read_register`].
+    ///
+    /// Returns `Err(`[`Error::Io`]`)` with an `InvalidInput` error kind if `offset` isn't
+    /// aligned to a 32-bit register, or falls outside the memory-mapped area.
+    ///
+    /// ## Safety
+    ///
+    /// Writing to a register can change the state of any pin, including pins that are in use
+    /// by the kernel (for instance, SD card or Ethernet pins) or other processes. This can
+    /// result in undefined behavior, data loss or damage to connected hardware. Register
+    /// writes aren't synchronized with any other RPPAL methods.
+    ///
+    /// [`read_register`]: #method.read_register
+    /// [`Error::Io`]: enum.Error.html#variant.Io
+    pub unsafe fn write_register(&self, offset: usize, value: u32) -> Result<()> {
+        self.check_register_offset(offset)?;
+
+        self.inner.gpio_mem.write_register(offset, value);
+
+        Ok(())
+    }
+
+    fn check_register_offset(&self, offset: usize) -> Result<()> {
+        if offset % std::mem::size_of::<u32>() != 0
+            || offset
+                .checked_add(std::mem::size_of::<u32>())
+                .map_or(true, |end| end > self.inner.gpio_mem.mem_size())
+        {
+            return Err(Error::Io(io::Error::from(io::ErrorKind::InvalidInput)));
+        }
+
+        Ok(())
+    }
+
     /// Blocks until an interrupt is triggered on any of the specified pins, or until a timeout occurs.
     ///
     /// Only pins that have been previously configured for synchronous interrupts using [`InputPin::set_interrupt`]
@@ -479,6 +1562,10 @@ impl Gpio {
     /// at the same time, only the first one is returned. The remaining events are cached and will be returned
     /// the next time [`InputPin::poll_interrupt`] or `poll_interrupts` is called.
     ///
+    /// The returned [`Level`] is based on the type of edge reported by the kernel, rather
+    /// than on reading the pin after the event was received, so it indicates the pin's
+    /// logic level at the time of the edge, even if the level has changed again since.
+    ///
     /// [`InputPin::set_interrupt`]: struct.InputPin.html#method.set_interrupt
     /// [`InputPin::poll_interrupt`]: struct.InputPin.html#method.poll_interrupt
     /// [`InputPin::set_async_interrupt`]: struct.InputPin.html#method.set_async_interrupt
@@ -492,4 +1579,116 @@ impl Gpio {
     ) -> Result<Option<(&'a InputPin, Level)>> {
         (*self.inner.sync_interrupts.lock().unwrap()).poll(pins, reset, timeout)
     }
+
+    /// Blocks until one or more interrupts are triggered on any of the specified pins,
+    /// or until a timeout occurs, and returns all queued trigger events.
+    ///
+    /// `poll_interrupts_all` behaves similarly to [`poll_interrupts`], but rather than returning
+    /// a single event, it keeps reading until all trigger events queued by the kernel for the
+    /// specified pins have been retrieved. This is more efficient for signals that trigger
+    /// multiple interrupts in quick succession.
+    ///
+    /// Each event is returned as `(&`[`InputPin`]`, `[`Level`]`, timestamp)`, sorted by their
+    /// timestamps. The timestamp is provided by the kernel, and represents the time elapsed
+    /// since an unspecified starting point (`CLOCK_MONOTONIC`). Timestamps can be compared to
+    /// calculate the time between events. If the timeout occurs before any interrupts are
+    /// triggered, `poll_interrupts_all` returns an empty `Vec`.
+    ///
+    /// The kernel queues up to 16 events per pin. Any additional events are discarded.
+    ///
+    /// [`poll_interrupts`]: #method.poll_interrupts
+    /// [`InputPin`]: struct.InputPin.html
+    /// [`Level`]: enum.Level.html
+    pub fn poll_interrupts_all<'a>(
+        &self,
+        pins: &[&'a InputPin],
+        reset: bool,
+        timeout: Option<Duration>,
+    ) -> Result<Vec<(&'a InputPin, Level, Duration)>> {
+        (*self.inner.sync_interrupts.lock().unwrap()).poll_all(pins, reset, timeout)
+    }
+
+    /// Blocks until an interrupt is triggered on any pin that's currently configured for
+    /// synchronous interrupts, or until a timeout occurs.
+    ///
+    /// `poll_any` behaves similarly to [`poll_interrupts`], but rather than requiring a
+    /// slice of [`InputPin`] references, it waits for all pins that have a synchronous
+    /// interrupt configured through [`InputPin::set_interrupt`], which saves you from
+    /// keeping track of the configured pins yourself.
+    ///
+    /// The triggered event is returned as `(pin, `[`Level`]`, timestamp)`, where `pin` is the
+    /// BCM GPIO number. The timestamp is provided by the kernel, and represents the time
+    /// elapsed since an unspecified starting point (`CLOCK_MONOTONIC`). If multiple
+    /// interrupts are triggered at the same time, the pin with the lowest BCM GPIO number
+    /// is returned first. Any remaining events are returned during subsequent calls.
+    ///
+    /// Setting `reset` to `true` clears any cached trigger events for all pins with a
+    /// synchronous interrupt.
+    ///
+    /// `timeout` can be set to `None` to wait indefinitely. If no pins are configured for
+    /// synchronous interrupts and `timeout` is set to `None`, `poll_any` blocks forever.
+    ///
+    /// Like `poll_interrupts`, `poll_any` holds the lock on the synchronous interrupts for
+    /// as long as it blocks. Any calls to [`InputPin::set_interrupt`], [`InputPin::clear_interrupt`]
+    /// or [`InputPin::poll_interrupt`] made on other threads in the meantime wait until
+    /// `poll_any` returns. If other threads add or remove pins while you're polling, use a
+    /// finite `timeout`, or those calls may never return.
+    ///
+    /// [`poll_interrupts`]: #method.poll_interrupts
+    /// [`InputPin`]: struct.InputPin.html
+    /// [`InputPin::set_interrupt`]: struct.InputPin.html#method.set_interrupt
+    /// [`InputPin::clear_interrupt`]: struct.InputPin.html#method.clear_interrupt
+    /// [`InputPin::poll_interrupt`]: struct.InputPin.html#method.poll_interrupt
+    /// [`Level`]: enum.Level.html
+    pub fn poll_any(
+        &self,
+        reset: bool,
+        timeout: Option<Duration>,
+    ) -> Result<Option<(u8, Level, Duration)>> {
+        (*self.inner.sync_interrupts.lock().unwrap()).poll_any(reset, timeout)
+    }
+}
+
+fn pad_control_unsupported() -> Error {
+    Error::Io(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "pad control isn't supported on this model",
+    ))
+}
+
+/// Busy-waits for the specified number of microseconds.
+///
+/// `delay_us` is intended for bit-banged protocols that need accurate sub-millisecond
+/// delays, which `thread::sleep` can't reliably provide due to timer slack and
+/// scheduling latency. Instead of sleeping, `delay_us` repeatedly reads the monotonic
+/// clock (`CLOCK_MONOTONIC`) until the delay has elapsed. The time it takes to read the
+/// clock is measured during the first call, and subtracted from the delay to avoid
+/// overshooting.
+///
+/// ## Note
+///
+/// `delay_us` keeps a CPU core fully occupied for the entire duration. For delays of
+/// more than a few milliseconds, use `thread::sleep` instead. The calling thread can
+/// still be preempted, which may extend the delay. Running the thread with a real-time
+/// scheduling policy reduces the chance of that happening.
+pub fn delay_us(micros: u64) {
+    // Calibrate before the start time is sampled, so the first delay doesn't overshoot
+    soft_pwm::time_overhead_ns();
+
+    let target_ns = soft_pwm::get_time_ns()
+        .saturating_add(micros.saturating_mul(1000).min(i64::MAX as u64) as i64);
+
+    soft_pwm::busy_wait_until_ns(target_ns);
+}
+
+impl AsRawFd for Gpio {
+    /// Returns the raw file descriptor for the `gpiochip` character device used
+    /// to configure interrupts.
+    ///
+    /// The file descriptor remains owned by `Gpio`, and is shared between all `Gpio`
+    /// and pin instances. It shouldn't be closed, and shouldn't be used after all
+    /// instances have gone out of scope.
+    fn as_raw_fd(&self) -> RawFd {
+        self.inner.cdev.as_raw_fd()
+    }
 }
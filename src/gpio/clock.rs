@@ -0,0 +1,187 @@
+use std::fs::OpenOptions;
+use std::io;
+use std::os::unix::fs::OpenOptionsExt;
+use std::os::unix::io::AsRawFd;
+use std::ptr;
+use std::thread;
+use std::time::Duration;
+
+use libc::{self, c_void, size_t, MAP_FAILED, MAP_SHARED, O_SYNC, PROT_READ, PROT_WRITE};
+
+use crate::gpio::{Error, Mode, Result};
+use crate::system::{self, DeviceInfo, SoC};
+
+const PATH_DEV_MEM: &str = "/dev/mem";
+// The GPCLK registers end at CM_GP2DIV (0x84)
+const CM_MEM_SIZE: usize = 0x88;
+const CM_GP0CTL: usize = 0x70 / std::mem::size_of::<u32>();
+
+// All clock manager writes need to include the password in the MSB
+const CM_PASSWD: u32 = 0x5a << 24;
+const CM_CTL_ENAB: u32 = 1 << 4;
+const CM_CTL_KILL: u32 = 1 << 5;
+const CM_CTL_BUSY: u32 = 1 << 7;
+const CM_CTL_MASK: u32 = 0x00ff_ffff;
+const CM_DIVI_SHIFT: u32 = 12;
+
+const CM_SRC_OSC: u32 = 1;
+const CM_SRC_PLLD: u32 = 6;
+
+// The integer divider is 12 bits wide. Dividing by 1 would output the unmodified
+// source clock, which exceeds the maximum GPIO output frequency.
+const DIVI_MIN: u32 = 2;
+const DIVI_MAX: u32 = 4095;
+
+// Maximum number of times we check the BUSY flag after disabling a clock
+const BUSY_RETRIES: u32 = 100;
+
+// Returns the GPCLK # and the alternate function mode for pins with a GPCLK output
+pub(crate) fn gpclk(pin: u8) -> Option<(usize, Mode)> {
+    match pin {
+        4 | 32 | 34 => Some((0, Mode::Alt0)),
+        5 | 42 | 44 => Some((1, Mode::Alt0)),
+        6 | 43 => Some((2, Mode::Alt0)),
+        20 => Some((0, Mode::Alt5)),
+        21 => Some((1, Mode::Alt5)),
+        _ => None,
+    }
+}
+
+// Memory-mapped clock manager registers, used to configure the GPCLK outputs
+pub(crate) struct ClockManager {
+    mem_ptr: *mut u32,
+    soc: SoC,
+}
+
+impl ClockManager {
+    pub(crate) fn open() -> Result<ClockManager> {
+        let device_info = DeviceInfo::new().map_err(|_| Error::UnknownModel)?;
+
+        let cm_address = device_info
+            .peripheral_addresses()
+            .clock_manager
+            .ok_or_else(|| {
+                Error::Io(io::Error::new(
+                    io::ErrorKind::Unsupported,
+                    "GPCLK outputs are only supported on BCM283x and BCM2711",
+                ))
+            })?;
+
+        // The clock manager isn't accessible through /dev/gpiomem
+        let mem_file = match OpenOptions::new()
+            .read(true)
+            .write(true)
+            .custom_flags(O_SYNC)
+            .open(PATH_DEV_MEM)
+        {
+            Ok(file) => file,
+            Err(ref e) if e.kind() == io::ErrorKind::PermissionDenied => {
+                return Err(Error::PermissionDenied(String::from(PATH_DEV_MEM)))
+            }
+            Err(e) => return Err(Error::Io(e)),
+        };
+
+        let offset = system::devmem_offset(cm_address)?;
+
+        let mem_ptr = unsafe {
+            libc::mmap(
+                ptr::null_mut(),
+                CM_MEM_SIZE,
+                PROT_READ | PROT_WRITE,
+                MAP_SHARED,
+                mem_file.as_raw_fd(),
+                offset,
+            )
+        };
+
+        if mem_ptr == MAP_FAILED {
+            return Err(Error::Io(io::Error::last_os_error()));
+        }
+
+        Ok(ClockManager {
+            mem_ptr: mem_ptr as *mut u32,
+            soc: device_info.soc(),
+        })
+    }
+
+    // Configures and enables the specified GPCLK, and returns the effective frequency
+    pub(crate) fn enable(&self, clock: usize, frequency: f64) -> f64 {
+        // Select the clock source that gets us closest to the requested frequency
+        let mut source = CM_SRC_OSC;
+        let mut divi = DIVI_MAX;
+        let mut effective = 0.0;
+        for &(src, src_frequency) in self.sources().iter() {
+            let src_divi = ((src_frequency / frequency).round() as u32).clamp(DIVI_MIN, DIVI_MAX);
+            let src_effective = src_frequency / f64::from(src_divi);
+
+            if effective == 0.0 || (src_effective - frequency).abs() < (effective - frequency).abs()
+            {
+                source = src;
+                divi = src_divi;
+                effective = src_effective;
+            }
+        }
+
+        self.disable(clock);
+
+        let ctl = CM_GP0CTL + (clock * 2);
+        self.write(ctl + 1, CM_PASSWD | (divi << CM_DIVI_SHIFT));
+        // The clock source needs to be set before the clock is enabled
+        self.write(ctl, CM_PASSWD | source);
+        self.write(ctl, CM_PASSWD | source | CM_CTL_ENAB);
+
+        effective
+    }
+
+    // Disables the specified GPCLK, and waits until it has stopped
+    pub(crate) fn disable(&self, clock: usize) {
+        let ctl = CM_GP0CTL + (clock * 2);
+
+        self.write(
+            ctl,
+            CM_PASSWD | (self.read(ctl) & CM_CTL_MASK & !CM_CTL_ENAB),
+        );
+
+        for _ in 0..BUSY_RETRIES {
+            if self.read(ctl) & CM_CTL_BUSY == 0 {
+                return;
+            }
+
+            thread::sleep(Duration::from_micros(10));
+        }
+
+        // The clock didn't stop in time. Killing the clock generator may cause a glitch
+        // on the output pin, but guarantees we can safely change the divider.
+        let source = self.read(ctl) & 0x0f;
+        self.write(ctl, CM_PASSWD | source | CM_CTL_KILL);
+        self.write(ctl, CM_PASSWD | source);
+    }
+
+    // Available clock sources and their frequencies
+    fn sources(&self) -> [(u32, f64); 2] {
+        match self.soc {
+            SoC::Bcm2711 => [(CM_SRC_OSC, 54_000_000.0), (CM_SRC_PLLD, 750_000_000.0)],
+            _ => [(CM_SRC_OSC, 19_200_000.0), (CM_SRC_PLLD, 500_000_000.0)],
+        }
+    }
+
+    #[inline(always)]
+    fn read(&self, offset: usize) -> u32 {
+        unsafe { ptr::read_volatile(self.mem_ptr.add(offset)) }
+    }
+
+    #[inline(always)]
+    fn write(&self, offset: usize, value: u32) {
+        unsafe {
+            ptr::write_volatile(self.mem_ptr.add(offset), value);
+        }
+    }
+}
+
+impl Drop for ClockManager {
+    fn drop(&mut self) {
+        unsafe {
+            libc::munmap(self.mem_ptr as *mut c_void, CM_MEM_SIZE as size_t);
+        }
+    }
+}
@@ -0,0 +1,262 @@
+use std::fmt;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use super::soft_pwm::get_time_ns;
+use super::{Bias, Error, IoPin, Level, Mode, Result};
+
+// Maximum time we'll wait for the sensor to change the data line's level
+const TIMEOUT_NS: i64 = 1_000_000;
+// High pulses longer than this represent a 1 bit. 0 bits are 26-28 µs, 1 bits are 70 µs.
+const BIT_THRESHOLD_NS: i64 = 48_000;
+// Number of data bits sent by the sensor
+const DATA_BITS: usize = 40;
+// Default number of retries after a failed read
+const DEFAULT_RETRIES: u32 = 3;
+
+/// DHT sensor models.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum DhtModel {
+    /// DHT11. Integer resolution, 0-50 °C, 20-90% relative humidity.
+    Dht11,
+    /// DHT22 (AM2302). 0.1 resolution, -40-80 °C, 0-100% relative humidity.
+    Dht22,
+}
+
+impl DhtModel {
+    // How long the data line is held low to request a reading
+    fn start_signal(self) -> Duration {
+        match self {
+            DhtModel::Dht11 => Duration::from_millis(20),
+            DhtModel::Dht22 => Duration::from_millis(2),
+        }
+    }
+
+    // Minimum time between two readings
+    fn sampling_interval(self) -> Duration {
+        match self {
+            DhtModel::Dht11 => Duration::from_secs(1),
+            DhtModel::Dht22 => Duration::from_secs(2),
+        }
+    }
+}
+
+impl fmt::Display for DhtModel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            DhtModel::Dht11 => write!(f, "DHT11"),
+            DhtModel::Dht22 => write!(f, "DHT22"),
+        }
+    }
+}
+
+/// Temperature and humidity reading from a DHT sensor.
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct DhtReading {
+    temperature: f32,
+    humidity: f32,
+}
+
+impl DhtReading {
+    /// Returns the temperature in degrees Celsius (°C).
+    pub fn temperature(&self) -> f32 {
+        self.temperature
+    }
+
+    /// Returns the relative humidity in percent (%).
+    pub fn humidity(&self) -> f32 {
+        self.humidity
+    }
+}
+
+/// DHT11/DHT22 temperature and humidity sensor.
+///
+/// The DHT sensors use a proprietary single-wire protocol. To request a reading,
+/// the data line is held low for a short period. The sensor then responds with
+/// 40 data bits, where each bit's value is encoded in the width of a high pulse.
+///
+/// Because the pulse widths are measured by continuously reading the pin's logic level
+/// on the current thread, the measurements are susceptible to scheduling/preemption.
+/// Failed readings are automatically retried. The number of retries can be changed
+/// with [`set_retries`].
+///
+/// The data line requires a pull-up resistor. The pin's built-in pull-up resistor is
+/// enabled while reading, but most sensor modules include an external pull-up resistor
+/// as well.
+///
+/// [`set_retries`]: #method.set_retries
+#[derive(Debug)]
+pub struct Dht {
+    pin: IoPin,
+    model: DhtModel,
+    retries: u32,
+    last_read: Option<Instant>,
+}
+
+impl Dht {
+    /// Constructs a new `Dht` for the specified pin and sensor model.
+    pub fn new(mut pin: IoPin, model: DhtModel) -> Dht {
+        pin.set_bias(Bias::PullUp);
+        pin.set_mode(Mode::Input);
+
+        Dht {
+            pin,
+            model,
+            retries: DEFAULT_RETRIES,
+            last_read: None,
+        }
+    }
+
+    /// Returns the sensor model.
+    pub fn model(&self) -> DhtModel {
+        self.model
+    }
+
+    /// Returns the number of retries after a failed reading.
+    pub fn retries(&self) -> u32 {
+        self.retries
+    }
+
+    /// Sets the number of retries after a failed reading.
+    ///
+    /// By default, `retries` is set to `3`.
+    pub fn set_retries(&mut self, retries: u32) {
+        self.retries = retries;
+    }
+
+    /// Reads the temperature and relative humidity.
+    ///
+    /// The sensors can't be read more often than once per second (DHT11) or once
+    /// every two seconds (DHT22). If needed, `read` blocks until enough time has
+    /// passed since the previous reading.
+    ///
+    /// If the sensor doesn't respond, `read` returns `Err(`[`Error::Timeout`]`)`. If the
+    /// received data is invalid, `read` returns `Err(`[`Error::ChecksumMismatch`]`)`. Both
+    /// are returned only after all retries failed.
+    ///
+    /// [`Error::Timeout`]: enum.Error.html#variant.Timeout
+    /// [`Error::ChecksumMismatch`]: enum.Error.html#variant.ChecksumMismatch
+    pub fn read(&mut self) -> Result<DhtReading> {
+        let mut attempt = 0;
+
+        loop {
+            match self.read_once() {
+                Ok(reading) => return Ok(reading),
+                Err(e @ Error::Timeout) | Err(e @ Error::ChecksumMismatch) => {
+                    if attempt >= self.retries {
+                        return Err(e);
+                    }
+                }
+                Err(e) => return Err(e),
+            }
+
+            attempt += 1;
+        }
+    }
+
+    /// Consumes the `Dht` and returns the [`IoPin`].
+    ///
+    /// [`IoPin`]: struct.IoPin.html
+    pub fn into_pin(self) -> IoPin {
+        self.pin
+    }
+
+    fn read_once(&mut self) -> Result<DhtReading> {
+        if let Some(last_read) = self.last_read {
+            let elapsed = last_read.elapsed();
+            if elapsed < self.model.sampling_interval() {
+                thread::sleep(self.model.sampling_interval() - elapsed);
+            }
+        }
+
+        self.last_read = Some(Instant::now());
+
+        // Send the start signal, and release the data line
+        self.pin.set_low();
+        self.pin.set_mode(Mode::Output);
+        thread::sleep(self.model.start_signal());
+        self.pin.set_mode(Mode::Input);
+
+        let data = self.read_data();
+
+        // Make sure the data line is released, even if we timed out
+        self.pin.set_mode(Mode::Input);
+
+        let data = data?;
+        let checksum = data[0]
+            .wrapping_add(data[1])
+            .wrapping_add(data[2])
+            .wrapping_add(data[3]);
+
+        if checksum != data[4] {
+            return Err(Error::ChecksumMismatch);
+        }
+
+        Ok(match self.model {
+            DhtModel::Dht11 => {
+                let temperature = f32::from(data[2]) + f32::from(data[3] & 0x7f) / 10.0;
+
+                DhtReading {
+                    temperature: if data[3] & 0x80 > 0 {
+                        -temperature
+                    } else {
+                        temperature
+                    },
+                    humidity: f32::from(data[0]) + f32::from(data[1]) / 10.0,
+                }
+            }
+            DhtModel::Dht22 => {
+                let temperature =
+                    f32::from(u16::from(data[2] & 0x7f) << 8 | u16::from(data[3])) / 10.0;
+
+                DhtReading {
+                    temperature: if data[2] & 0x80 > 0 {
+                        -temperature
+                    } else {
+                        temperature
+                    },
+                    humidity: f32::from(u16::from(data[0]) << 8 | u16::from(data[1])) / 10.0,
+                }
+            }
+        })
+    }
+
+    fn read_data(&self) -> Result<[u8; 5]> {
+        // The sensor responds with an 80 µs low pulse, followed by an 80 µs high pulse
+        self.wait_for(Level::Low)?;
+        self.wait_for(Level::High)?;
+        self.wait_for(Level::Low)?;
+
+        let mut data = [0u8; 5];
+        for bit in 0..DATA_BITS {
+            // Each bit starts with a 50 µs low pulse, followed by a high pulse
+            // that determines the bit's value.
+            let start_ns = self.wait_for(Level::High)?;
+            let end_ns = self.wait_for(Level::Low)?;
+
+            if end_ns - start_ns > BIT_THRESHOLD_NS {
+                data[bit / 8] |= 0x80 >> (bit % 8);
+            }
+        }
+
+        Ok(data)
+    }
+
+    // Busy-waits until the pin changes to the specified level, and returns the timestamp.
+    #[inline(always)]
+    fn wait_for(&self, level: Level) -> Result<i64> {
+        let start_ns = get_time_ns();
+
+        loop {
+            let current_ns = get_time_ns();
+
+            if self.pin.read() == level {
+                return Ok(current_ns);
+            }
+
+            if current_ns - start_ns > TIMEOUT_NS {
+                return Err(Error::Timeout);
+            }
+        }
+    }
+}
@@ -0,0 +1,162 @@
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+use super::{GpioState, InputPin, Level, Result, Trigger};
+
+// Position change for each transition, indexed by (previous state << 2) | current state,
+// where each state is (A << 1) | B. Transitions that skip a state are ambiguous, and
+// usually caused by contact bounce, so they don't change the position. The state is
+// still updated, so the next valid transition is decoded against the actual levels.
+const TRANSITIONS: [i8; 16] = [0, -1, 1, 0, 1, 0, 0, -1, -1, 0, 0, 1, 0, 1, -1, 0];
+
+type Callback = Box<dyn FnMut(i64) + Send>;
+
+struct State {
+    position: i64,
+    // Last observed quadrature state
+    previous: u8,
+}
+
+struct Shared {
+    gpio_state: Arc<GpioState>,
+    pin_a: u8,
+    pin_b: u8,
+    state: Mutex<State>,
+    callback: Mutex<Option<Callback>>,
+}
+
+impl Shared {
+    // Processes a level change on one of the pins
+    fn update(&self, pin_a: Option<Level>, pin_b: Option<Level>) {
+        // The level that triggered the interrupt is more reliable than reading it again
+        let a = pin_a.unwrap_or_else(|| self.gpio_state.gpio_mem.level(self.pin_a));
+        let b = pin_b.unwrap_or_else(|| self.gpio_state.gpio_mem.level(self.pin_b));
+        let current = ((a as u8) << 1) | b as u8;
+
+        let position = {
+            let mut state = self.state.lock().unwrap();
+
+            let delta = TRANSITIONS[usize::from((state.previous << 2) | current)];
+            state.previous = current;
+            if delta == 0 {
+                return;
+            }
+
+            state.position += i64::from(delta);
+            state.position
+        };
+
+        if let Some(ref mut callback) = *self.callback.lock().unwrap() {
+            callback(position);
+        }
+    }
+}
+
+/// Quadrature rotary encoder decoder.
+///
+/// `Encoder` decodes the A/B signals of an incremental rotary encoder, and keeps track of
+/// its position. Asynchronous interrupts are configured on both pins, and each level
+/// change is validated against the quadrature state transitions. Transitions that skip a
+/// state are rejected, which filters out most of the noise caused by contact bounce.
+///
+/// The position is counted in quadrature steps. Most mechanical encoders with detents
+/// move 4 steps per detent, although some move 2 or 1. For noisy encoders, configure
+/// a debounce period with [`InputPin::set_debounce`] before constructing the `Encoder`.
+///
+/// [`InputPin::set_debounce`]: struct.InputPin.html#method.set_debounce
+pub struct Encoder {
+    pin_a: InputPin,
+    pin_b: InputPin,
+    shared: Arc<Shared>,
+}
+
+impl Encoder {
+    /// Constructs a new `Encoder`.
+    ///
+    /// `pin_a` and `pin_b` are connected to the encoder's A and B outputs. The position
+    /// increases when A leads B, which is usually clockwise rotation. Mechanical
+    /// encoders typically connect A and B to ground through the contacts, and need
+    /// pull-up resistors, which can be enabled with [`Pin::into_input_pullup`].
+    ///
+    /// Any interrupts previously configured on `pin_a` and `pin_b` are replaced.
+    ///
+    /// [`Pin::into_input_pullup`]: struct.Pin.html#method.into_input_pullup
+    pub fn new(mut pin_a: InputPin, mut pin_b: InputPin) -> Result<Encoder> {
+        let a = pin_a.read();
+        let b = pin_b.read();
+
+        let shared = Arc::new(Shared {
+            gpio_state: pin_a.pin.gpio_state.clone(),
+            pin_a: pin_a.pin(),
+            pin_b: pin_b.pin(),
+            state: Mutex::new(State {
+                position: 0,
+                previous: ((a as u8) << 1) | b as u8,
+            }),
+            callback: Mutex::new(None),
+        });
+
+        let shared_a = shared.clone();
+        pin_a.set_async_interrupt(Trigger::Both, move |level| {
+            shared_a.update(Some(level), None);
+        })?;
+
+        let shared_b = shared.clone();
+        pin_b.set_async_interrupt(Trigger::Both, move |level| {
+            shared_b.update(None, Some(level));
+        })?;
+
+        Ok(Encoder {
+            pin_a,
+            pin_b,
+            shared,
+        })
+    }
+
+    /// Returns the current position in quadrature steps.
+    pub fn position(&self) -> i64 {
+        self.shared.state.lock().unwrap().position
+    }
+
+    /// Sets the current position in quadrature steps.
+    pub fn set_position(&mut self, position: i64) {
+        self.shared.state.lock().unwrap().position = position;
+    }
+
+    /// Configures a callback that's called whenever the position changes.
+    ///
+    /// The callback receives the updated position, and is executed on one of the
+    /// interrupt polling threads. Any previously configured callback is replaced.
+    pub fn set_callback<C>(&mut self, callback: C)
+    where
+        C: FnMut(i64) + Send + 'static,
+    {
+        *self.shared.callback.lock().unwrap() = Some(Box::new(callback));
+    }
+
+    /// Removes a previously configured callback.
+    pub fn clear_callback(&mut self) {
+        *self.shared.callback.lock().unwrap() = None;
+    }
+
+    /// Consumes the `Encoder`, removes the interrupts, and returns the [`InputPin`]s
+    /// for A and B.
+    ///
+    /// [`InputPin`]: struct.InputPin.html
+    pub fn into_pins(mut self) -> Result<(InputPin, InputPin)> {
+        self.pin_a.clear_async_interrupt()?;
+        self.pin_b.clear_async_interrupt()?;
+
+        Ok((self.pin_a, self.pin_b))
+    }
+}
+
+impl fmt::Debug for Encoder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Encoder")
+            .field("pin_a", &self.pin_a)
+            .field("pin_b", &self.pin_b)
+            .field("position", &self.position())
+            .finish()
+    }
+}
@@ -1,4 +1,4 @@
-use crate::gpio::{Bias, Level, Mode};
+use crate::gpio::{Bias, Level, MemSource, Mode, SlewRate, Trigger};
 
 pub mod bcm;
 pub mod rp1;
@@ -7,7 +7,41 @@ pub(crate) trait GpioRegisters: std::fmt::Debug + Sync + Send {
     fn set_high(&self, pin: u8);
     fn set_low(&self, pin: u8);
     fn level(&self, pin: u8) -> Level;
+    // Flips the output state of the specified pin.
+    fn toggle(&self, pin: u8);
     fn mode(&self, pin: u8) -> Mode;
     fn set_mode(&self, pin: u8, mode: Mode);
     fn set_bias(&self, pin: u8, bias: Bias);
+    // Enables or disables the Schmitt trigger input. Returns false if the
+    // pad configuration isn't accessible.
+    fn set_hysteresis(&self, pin: u8, enabled: bool) -> bool;
+    // Returns the output drive strength in mA, or None if the pad configuration
+    // isn't accessible.
+    fn drive_strength(&self, pin: u8) -> Option<u8>;
+    // Returns the output slew rate, or None if the pad configuration isn't accessible.
+    fn slew_rate(&self, pin: u8) -> Option<SlewRate>;
+    // Returns the I/O voltage in volts selected for the specified bank of pads, or
+    // None if the voltage selection isn't accessible.
+    fn bank_voltage(&self, bank: u8) -> Option<f32>;
+    // Reads the levels of 32 consecutive pins, starting at pin (bank * 32).
+    fn bank_levels(&self, bank: u8) -> u32;
+    // Sets the output state for the pins in mask to the corresponding bits in
+    // value. Pins that aren't part of mask are left unchanged.
+    fn write_bank(&self, bank: u8, mask: u32, value: u32);
+    // Configures the hardware edge detection for the specified pin, and clears
+    // any pending edge events.
+    fn set_edge_detect(&self, pin: u8, trigger: Trigger);
+    // Returns true if an edge matching trigger was detected since the last call,
+    // and clears the pending event.
+    fn take_edge_event(&self, pin: u8, trigger: Trigger) -> bool;
+    // Device node the registers were memory-mapped from.
+    fn mem_source(&self) -> MemSource;
+    // Size of the memory-mapped register area in bytes.
+    fn mem_size(&self) -> usize;
+    // Reads the register at the specified offset in bytes. The caller is
+    // responsible for checking the offset against mem_size().
+    unsafe fn read_register(&self, offset: usize) -> u32;
+    // Writes to the register at the specified offset in bytes. The caller is
+    // responsible for checking the offset against mem_size().
+    unsafe fn write_register(&self, offset: usize, value: u32);
 }
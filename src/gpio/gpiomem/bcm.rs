@@ -8,11 +8,11 @@ use std::sync::atomic::{AtomicBool, Ordering};
 use std::thread;
 use std::time::Duration;
 
-use libc::{self, c_void, off_t, size_t, MAP_FAILED, MAP_SHARED, O_SYNC, PROT_READ, PROT_WRITE};
+use libc::{self, c_void, size_t, MAP_FAILED, MAP_SHARED, O_SYNC, PROT_READ, PROT_WRITE};
 
 use crate::gpio::gpiomem::GpioRegisters;
-use crate::gpio::{Bias, Error, Level, Mode, Result};
-use crate::system::{DeviceInfo, SoC};
+use crate::gpio::{Bias, Error, Level, MemSource, Mode, Result, SlewRate, Trigger};
+use crate::system::{self, DeviceInfo, SoC};
 
 const PATH_DEV_GPIOMEM: &str = "/dev/gpiomem";
 const PATH_DEV_MEM: &str = "/dev/mem";
@@ -24,6 +24,9 @@ const GPFSEL0: usize = 0x00;
 const GPSET0: usize = 0x1c / std::mem::size_of::<u32>();
 const GPCLR0: usize = 0x28 / std::mem::size_of::<u32>();
 const GPLEV0: usize = 0x34 / std::mem::size_of::<u32>();
+const GPEDS0: usize = 0x40 / std::mem::size_of::<u32>();
+const GPREN0: usize = 0x4c / std::mem::size_of::<u32>();
+const GPFEN0: usize = 0x58 / std::mem::size_of::<u32>();
 const GPPUD: usize = 0x94 / std::mem::size_of::<u32>();
 const GPPUDCLK0: usize = 0x98 / std::mem::size_of::<u32>();
 // Only available on BCM2711 (RPi4)
@@ -42,6 +45,7 @@ pub struct GpioMem {
     mem_ptr: *mut u32,
     locks: [AtomicBool; GPIO_MEM_REGISTERS],
     soc: SoC,
+    mem_source: MemSource,
 }
 
 impl fmt::Debug for GpioMem {
@@ -50,6 +54,7 @@ impl fmt::Debug for GpioMem {
             .field("mem_ptr", &self.mem_ptr)
             .field("locks", &format_args!("{{ .. }}"))
             .field("soc", &self.soc)
+            .field("mem_source", &self.mem_source)
             .finish()
     }
 }
@@ -58,10 +63,10 @@ impl GpioMem {
     pub fn open() -> Result<GpioMem> {
         // Try /dev/gpiomem first. If that fails, try /dev/mem instead. If neither works,
         // report back the error that's the most relevant.
-        let mem_ptr = match Self::map_devgpiomem() {
-            Ok(ptr) => ptr,
+        let (mem_ptr, mem_source) = match Self::map_devgpiomem() {
+            Ok(ptr) => (ptr, MemSource::GpioMem),
             Err(gpiomem_err) => match Self::map_devmem() {
-                Ok(ptr) => ptr,
+                Ok(ptr) => (ptr, MemSource::DevMem),
                 Err(Error::Io(ref e)) if e.kind() == io::ErrorKind::PermissionDenied => {
                     // Did /dev/gpiomem also give us a Permission Denied error? If so, return
                     // that path instead of /dev/mem. Solving /dev/gpiomem issues should be
@@ -87,6 +92,7 @@ impl GpioMem {
             mem_ptr,
             locks,
             soc,
+            mem_source,
         })
     }
 
@@ -124,6 +130,10 @@ impl GpioMem {
         // Identify which SoC we're using, so we know what offset to start at
         let device_info = DeviceInfo::new().map_err(|_| Error::UnknownModel)?;
 
+        // A 32-bit kernel can't map peripherals located above 4 GiB, which happens
+        // when the BCM2711 is configured for high peripheral mode.
+        let offset = system::devmem_offset(device_info.peripheral_addresses().gpio)?;
+
         let mem_file = OpenOptions::new()
             .read(true)
             .write(true)
@@ -138,7 +148,7 @@ impl GpioMem {
                 PROT_READ | PROT_WRITE,
                 MAP_SHARED,
                 mem_file.as_raw_fd(),
-                (device_info.peripheral_base() + device_info.gpio_offset()) as off_t,
+                offset,
             )
         };
 
@@ -196,6 +206,98 @@ impl GpioRegisters for GpioMem {
         unsafe { std::mem::transmute((reg_value >> shift) as u8 & 0b1) }
     }
 
+    #[inline(always)]
+    fn toggle(&self, pin: u8) {
+        // The BCM283x/BCM2711 don't have a toggle register, so we check the current
+        // level, and write to GPSET or GPCLR, which only affects the selected pin.
+        match self.level(pin) {
+            Level::Low => self.set_high(pin),
+            Level::High => self.set_low(pin),
+        }
+    }
+
+    #[inline(always)]
+    fn bank_levels(&self, bank: u8) -> u32 {
+        self.read(GPLEV0 + bank as usize)
+    }
+
+    #[inline(always)]
+    fn write_bank(&self, bank: u8, mask: u32, value: u32) {
+        // Set and clear are handled by separate registers, so all pins that are
+        // driven high change together, followed by all pins that are driven low.
+        if value & mask > 0 {
+            self.write(GPSET0 + bank as usize, value & mask);
+        }
+
+        if !value & mask > 0 {
+            self.write(GPCLR0 + bank as usize, !value & mask);
+        }
+    }
+
+    fn set_edge_detect(&self, pin: u8, trigger: Trigger) {
+        let shift = pin % 32;
+        let (rising, falling) = match trigger {
+            Trigger::Disabled => (false, false),
+            Trigger::RisingEdge => (true, false),
+            Trigger::FallingEdge => (false, true),
+            Trigger::Both => (true, true),
+        };
+
+        for &(register, enabled) in &[(GPREN0, rising), (GPFEN0, falling)] {
+            let offset = register + pin as usize / 32;
+
+            loop {
+                if self.locks[offset]
+                    .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
+                    .is_ok()
+                {
+                    break;
+                }
+            }
+
+            let reg_value = self.read(offset);
+            self.write(
+                offset,
+                (reg_value & !(1 << shift)) | ((enabled as u32) << shift),
+            );
+
+            self.locks[offset].store(false, Ordering::SeqCst);
+        }
+
+        // GPEDS is write-1-to-clear, so this doesn't affect any other pins
+        self.write(GPEDS0 + pin as usize / 32, 1 << shift);
+    }
+
+    #[inline(always)]
+    fn take_edge_event(&self, pin: u8, _trigger: Trigger) -> bool {
+        let offset = GPEDS0 + pin as usize / 32;
+        let shift = pin % 32;
+
+        if self.read(offset) & (1 << shift) == 0 {
+            return false;
+        }
+
+        self.write(offset, 1 << shift);
+
+        true
+    }
+
+    fn mem_source(&self) -> MemSource {
+        self.mem_source
+    }
+
+    fn mem_size(&self) -> usize {
+        GPIO_MEM_SIZE
+    }
+
+    unsafe fn read_register(&self, offset: usize) -> u32 {
+        self.read(offset / std::mem::size_of::<u32>())
+    }
+
+    unsafe fn write_register(&self, offset: usize, value: u32) {
+        self.write(offset / std::mem::size_of::<u32>(), value);
+    }
+
     fn mode(&self, pin: u8) -> Mode {
         let offset = GPFSEL0 + pin as usize / 10;
         let shift = (pin % 10) * 3;
@@ -326,6 +428,28 @@ impl GpioRegisters for GpioMem {
             self.locks[GPPUD].store(false, Ordering::SeqCst);
         }
     }
+
+    fn set_hysteresis(&self, _pin: u8, _enabled: bool) -> bool {
+        // Hysteresis is configured per group of pins through the PADS registers.
+        // Changing it for a single pin would affect the entire group, so this
+        // isn't supported.
+        false
+    }
+
+    fn drive_strength(&self, _pin: u8) -> Option<u8> {
+        // The PADS registers are located outside of the GPIO register area, and are
+        // read through gpio::pads instead.
+        None
+    }
+
+    fn slew_rate(&self, _pin: u8) -> Option<SlewRate> {
+        None
+    }
+
+    fn bank_voltage(&self, _bank: u8) -> Option<f32> {
+        // The BCM283x and BCM2711 don't provide a software-readable voltage selection
+        None
+    }
 }
 
 // Required because of the raw pointer to our memory-mapped file
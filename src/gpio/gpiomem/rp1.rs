@@ -9,7 +9,7 @@ use std::ptr;
 
 use libc::{self, c_void, size_t, MAP_FAILED, MAP_SHARED, O_SYNC, PROT_READ, PROT_WRITE};
 
-use crate::gpio::{Bias, Error, Level, Mode, Result};
+use crate::gpio::{Bias, Error, Level, MemSource, Mode, Result, SlewRate, Trigger};
 use crate::system::{DeviceInfo, SoC};
 
 use super::GpioRegisters;
@@ -26,6 +26,10 @@ const MEM_SIZE: usize = 0x30000;
 const IO_BANK0_OFFSET: usize = 0x00000;
 const SYS_RIO0_OFFSET: usize = 0x10000;
 const PADS_BANK0_OFFSET: usize = 0x20000;
+// Offset to the next PADS_BANK. PADS_BANK1 and PADS_BANK2 are only used for the
+// voltage selection.
+const PADS_BANK_OFFSET: usize = 0x4000;
+const PADS_BANKS: u8 = 3;
 
 // Atomic register access (datasheet @ 2.4)
 const RW_OFFSET: usize = 0x0000;
@@ -45,6 +49,12 @@ const CTRL_OUTOVER_MASK: u32 = 0x3000;
 const CTRL_OUTOVER_LSB: u32 = 12;
 const CTRL_OEOVER_MASK: u32 = 0xc000;
 const CTRL_OEOVER_LSB: u32 = 14;
+// Clears all latched interrupt events for the pin
+const CTRL_IRQRESET: u32 = 1 << 28;
+
+// Latched raw edge events (datasheet @ 3.1.4)
+const STATUS_EVENT_EDGE_LOW: u32 = 1 << 20;
+const STATUS_EVENT_EDGE_HIGH: u32 = 1 << 21;
 
 // Drive output from peripheral signal selected by FUNCSEL
 const OUTOVER_PERI: u32 = 0;
@@ -63,11 +73,20 @@ const FSEL_ALT7: u8 = 7;
 const FSEL_ALT8: u8 = 8;
 const FSEL_NULL: u8 = 31;
 
+// Voltage select offset for the PADS_BANK registers (datasheet @ 3.1.4)
+const PADS_VOLTAGE_SELECT: usize = 0x00;
+// Set for 1.8 V, cleared for 3.3 V
+const PADS_VOLTAGE_SELECT_1V8: u32 = 0x01;
+
 // GPIO offset for the PADS_BANK registers (datasheet @ 3.1.4)
 const PADS_GPIO: usize = 0x04;
 // Offset to the next GPIO for the PADS_BANK registers (datasheet @ 3.1.4)
 const PADS_OFFSET: usize = 4;
 
+const PADS_SLEWFAST_MASK: u32 = 0x01;
+const PADS_SCHMITT_MASK: u32 = 0x02;
+const PADS_DRIVE_MASK: u32 = 0x30;
+const PADS_DRIVE_LSB: u32 = 4;
 const PADS_IN_ENABLE_MASK: u32 = 0x40;
 const PADS_OUT_DISABLE_MASK: u32 = 0x80;
 
@@ -223,6 +242,86 @@ impl GpioRegisters for GpioMem {
         unsafe { std::mem::transmute((reg_value >> pin) as u8 & 0b1) }
     }
 
+    #[inline(always)]
+    fn toggle(&self, pin: u8) {
+        let offset = (SYS_RIO0_OFFSET + RIO_OUT + XOR_OFFSET) / REG_SIZE;
+
+        self.write(offset, 1 << pin);
+    }
+
+    #[inline(always)]
+    fn bank_levels(&self, bank: u8) -> u32 {
+        // Only bank 0 is accessible
+        if bank > 0 {
+            return 0;
+        }
+
+        self.read((SYS_RIO0_OFFSET + RIO_IN) / REG_SIZE)
+    }
+
+    #[inline(always)]
+    fn write_bank(&self, bank: u8, mask: u32, value: u32) {
+        // Only bank 0 is accessible
+        if bank > 0 {
+            return;
+        }
+
+        // Flip the bits that differ through the atomic XOR alias, so all
+        // pins change state with a single write.
+        let reg_value = self.read((SYS_RIO0_OFFSET + RIO_OUT) / REG_SIZE);
+        let toggle = (reg_value ^ value) & mask;
+
+        if toggle > 0 {
+            self.write((SYS_RIO0_OFFSET + RIO_OUT + XOR_OFFSET) / REG_SIZE, toggle);
+        }
+    }
+
+    fn set_edge_detect(&self, pin: u8, _trigger: Trigger) {
+        // Edge events are always latched in the STATUS register, so we only need
+        // to clear any pending events. The IRQ masks are left unchanged, since
+        // those are managed by the kernel.
+        let offset =
+            (IO_BANK0_OFFSET + GPIO_CTRL + (pin as usize * GPIO_OFFSET) + SET_OFFSET) / REG_SIZE;
+
+        self.write(offset, CTRL_IRQRESET);
+    }
+
+    #[inline(always)]
+    fn take_edge_event(&self, pin: u8, trigger: Trigger) -> bool {
+        let mask = match trigger {
+            Trigger::Disabled => return false,
+            Trigger::RisingEdge => STATUS_EVENT_EDGE_HIGH,
+            Trigger::FallingEdge => STATUS_EVENT_EDGE_LOW,
+            Trigger::Both => STATUS_EVENT_EDGE_HIGH | STATUS_EVENT_EDGE_LOW,
+        };
+
+        let offset =
+            (IO_BANK0_OFFSET + GPIO_STATUS + (pin as usize * GPIO_OFFSET) + RW_OFFSET) / REG_SIZE;
+        if self.read(offset) & mask == 0 {
+            return false;
+        }
+
+        self.set_edge_detect(pin, trigger);
+
+        true
+    }
+
+    fn mem_source(&self) -> MemSource {
+        MemSource::GpioMem
+    }
+
+    fn mem_size(&self) -> usize {
+        MEM_SIZE
+    }
+
+    unsafe fn read_register(&self, offset: usize) -> u32 {
+        self.read(offset / std::mem::size_of::<u32>())
+    }
+
+    unsafe fn write_register(&self, offset: usize, value: u32) {
+        self.write(offset / std::mem::size_of::<u32>(), value);
+    }
+
     fn mode(&self, pin: u8) -> Mode {
         let offset =
             (IO_BANK0_OFFSET + GPIO_CTRL + (pin as usize * GPIO_OFFSET) + RW_OFFSET) / REG_SIZE;
@@ -296,6 +395,63 @@ impl GpioRegisters for GpioMem {
 
         self.write(offset, reg_value);
     }
+
+    fn set_hysteresis(&self, pin: u8, enabled: bool) -> bool {
+        let offset =
+            (PADS_BANK0_OFFSET + PADS_GPIO + (pin as usize * PADS_OFFSET) + RW_OFFSET) / REG_SIZE;
+        let reg_value = self.read(offset);
+
+        if enabled {
+            self.write(offset, reg_value | PADS_SCHMITT_MASK);
+        } else {
+            self.write(offset, reg_value & !PADS_SCHMITT_MASK);
+        }
+
+        true
+    }
+
+    fn drive_strength(&self, pin: u8) -> Option<u8> {
+        let offset =
+            (PADS_BANK0_OFFSET + PADS_GPIO + (pin as usize * PADS_OFFSET) + RW_OFFSET) / REG_SIZE;
+
+        Some(
+            match (self.read(offset) & PADS_DRIVE_MASK) >> PADS_DRIVE_LSB {
+                0 => 2,
+                1 => 4,
+                2 => 8,
+                _ => 12,
+            },
+        )
+    }
+
+    fn slew_rate(&self, pin: u8) -> Option<SlewRate> {
+        let offset =
+            (PADS_BANK0_OFFSET + PADS_GPIO + (pin as usize * PADS_OFFSET) + RW_OFFSET) / REG_SIZE;
+
+        if self.read(offset) & PADS_SLEWFAST_MASK > 0 {
+            Some(SlewRate::Fast)
+        } else {
+            Some(SlewRate::Slow)
+        }
+    }
+
+    fn bank_voltage(&self, bank: u8) -> Option<f32> {
+        if bank >= PADS_BANKS {
+            return None;
+        }
+
+        let offset = (PADS_BANK0_OFFSET
+            + (bank as usize * PADS_BANK_OFFSET)
+            + PADS_VOLTAGE_SELECT
+            + RW_OFFSET)
+            / REG_SIZE;
+
+        if self.read(offset) & PADS_VOLTAGE_SELECT_1V8 > 0 {
+            Some(1.8)
+        } else {
+            Some(3.3)
+        }
+    }
 }
 
 impl Drop for GpioMem {
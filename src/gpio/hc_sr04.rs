@@ -0,0 +1,151 @@
+use std::io;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use super::{Error, InputPin, Level, OutputPin, Result, Trigger};
+
+// Width of the trigger pulse. The datasheet specifies a minimum of 10 µs.
+const TRIGGER_PULSE: Duration = Duration::from_micros(10);
+// The sensor keeps the echo pin high for about 38 ms when no obstacle is detected,
+// which is well beyond its maximum range of 4 m (~23 ms).
+const DEFAULT_TIMEOUT: Duration = Duration::from_millis(40);
+// Minimum time between two measurements, so echoes from the previous measurement
+// have died down.
+const MEASUREMENT_INTERVAL: Duration = Duration::from_millis(60);
+// Default air temperature in degrees Celsius (°C)
+const DEFAULT_TEMPERATURE: f64 = 20.0;
+
+/// HC-SR04 ultrasonic distance sensor.
+///
+/// A measurement is started by sending a 10 µs pulse on the trigger pin. The sensor then
+/// emits an ultrasonic burst, and sets the echo pin high until the echo is received. The
+/// distance is calculated from the width of the echo pulse, which is measured using the
+/// kernel timestamps of the echo pin's rising and falling edges, and the speed of sound
+/// at the configured air temperature.
+///
+/// The HC-SR04 is a 5 V device. Its echo output needs a voltage divider or level shifter
+/// before it's connected to a GPIO pin, since the Raspberry Pi's GPIO pins aren't 5 V
+/// tolerant.
+#[derive(Debug)]
+pub struct HcSr04 {
+    trigger: OutputPin,
+    echo: InputPin,
+    timeout: Duration,
+    temperature: f64,
+    last_measurement: Option<Instant>,
+}
+
+impl HcSr04 {
+    /// Constructs a new `HcSr04`.
+    ///
+    /// `trigger` and `echo` are connected to the sensor's Trig and Echo pins. The trigger
+    /// pin is set low, and any interrupts configured on `echo` are cleared.
+    pub fn new(mut trigger: OutputPin, mut echo: InputPin) -> Result<HcSr04> {
+        trigger.set_low();
+        echo.clear_interrupt()?;
+        echo.clear_async_interrupt()?;
+
+        Ok(HcSr04 {
+            trigger,
+            echo,
+            timeout: DEFAULT_TIMEOUT,
+            temperature: DEFAULT_TEMPERATURE,
+            last_measurement: None,
+        })
+    }
+
+    /// Returns the maximum time a measurement will wait for the echo to complete.
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    /// Sets the maximum time a measurement will wait for the echo to complete.
+    ///
+    /// Lowering the timeout limits the maximum distance that can be measured. Each
+    /// millisecond corresponds to roughly 17 cm. By default, `timeout` is set to 40 ms.
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        self.timeout = timeout;
+    }
+
+    /// Returns the air temperature in degrees Celsius (°C) used to calculate the speed
+    /// of sound.
+    pub fn temperature(&self) -> f64 {
+        self.temperature
+    }
+
+    /// Sets the air temperature in degrees Celsius (°C) used to calculate the speed
+    /// of sound.
+    ///
+    /// The speed of sound increases by about 0.6 m/s for each degree Celsius. By
+    /// default, `temperature` is set to 20 °C.
+    ///
+    /// If `temperature` isn't a finite number, `set_temperature` returns
+    /// `Err(`[`Error::Io`]`)` with an `io::ErrorKind::InvalidInput` error.
+    ///
+    /// [`Error::Io`]: enum.Error.html#variant.Io
+    pub fn set_temperature(&mut self, temperature: f64) -> Result<()> {
+        if !temperature.is_finite() {
+            return Err(Error::Io(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "temperature must be a finite number",
+            )));
+        }
+
+        self.temperature = temperature;
+
+        Ok(())
+    }
+
+    /// Measures the distance to the nearest obstacle in centimeters (cm).
+    ///
+    /// The sensor can't be triggered more often than once every 60 ms. If needed,
+    /// `distance` blocks until enough time has passed since the previous measurement.
+    ///
+    /// If the echo doesn't complete before the timeout period elapses, which usually
+    /// means the obstacle is out of range, `distance` returns `Ok(None)`.
+    pub fn distance(&mut self) -> Result<Option<f64>> {
+        Ok(self
+            .echo_width()?
+            .map(|width| width.as_secs_f64() * self.speed_of_sound() * 100.0 / 2.0))
+    }
+
+    /// Consumes the `HcSr04`, and returns the trigger [`OutputPin`] and echo [`InputPin`].
+    ///
+    /// [`OutputPin`]: struct.OutputPin.html
+    /// [`InputPin`]: struct.InputPin.html
+    pub fn into_pins(self) -> (OutputPin, InputPin) {
+        (self.trigger, self.echo)
+    }
+
+    // Speed of sound in m/s at the configured temperature
+    fn speed_of_sound(&self) -> f64 {
+        331.3 + 0.606 * self.temperature
+    }
+
+    fn echo_width(&mut self) -> Result<Option<Duration>> {
+        if let Some(last_measurement) = self.last_measurement {
+            let elapsed = last_measurement.elapsed();
+            if elapsed < MEASUREMENT_INTERVAL {
+                thread::sleep(MEASUREMENT_INTERVAL - elapsed);
+            }
+        }
+
+        // Request the line before triggering, so we can't miss the rising edge
+        let mut reader = self.echo.edge_reader(Trigger::Both)?;
+
+        self.last_measurement = Some(Instant::now());
+        self.trigger.pulse(Level::High, TRIGGER_PULSE);
+
+        let deadline = Instant::now().checked_add(self.timeout);
+        let mut start = None;
+        while let Some((level, timestamp)) = reader.next(deadline)? {
+            if level == Level::High {
+                start = Some(timestamp);
+            } else if let Some(start) = start {
+                return Ok(Some(timestamp.saturating_sub(start)));
+            }
+        }
+
+        Ok(None)
+    }
+}
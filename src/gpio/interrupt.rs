@@ -1,29 +1,122 @@
 #![allow(dead_code)]
 
 use std::fmt;
+use std::io;
+use std::mem;
+use std::os::unix::io::AsRawFd;
+use std::os::unix::thread::JoinHandleExt;
+use std::result;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::{Duration, Instant};
 
 use crate::gpio::epoll::{epoll_event, Epoll, EventFd, EPOLLERR, EPOLLET, EPOLLIN, EPOLLPRI};
 use crate::gpio::ioctl;
 use crate::gpio::pin::InputPin;
-use crate::gpio::{Error, Level, Result, Trigger};
+use crate::gpio::{Bias, Error, GpioState, Level, Result, Trigger};
+
+// Line configuration applied to v2 line requests, in addition to the trigger
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct LineSettings {
+    pub bias: Bias,
+    pub debounce: Option<Duration>,
+    // Number of events the kernel queues for the line request. 0 selects the kernel's
+    // default of 16 events.
+    pub event_buffer_size: u32,
+}
+
+// Line event request using either the v1 or v2 GPIO character device uAPI
+#[derive(Debug)]
+enum EventRequest {
+    V1(ioctl::EventRequest),
+    V2(Box<ioctl::LineRequest>),
+}
+
+impl EventRequest {
+    fn new(
+        cdev_fd: i32,
+        pin: u8,
+        trigger: Trigger,
+        settings: LineSettings,
+    ) -> Result<EventRequest> {
+        // The v2 uAPI was added in Linux 5.10. On older kernels, we fall back to a v1
+        // request. The line settings are only supported by the v2 uAPI, and are ignored
+        // for v1 requests.
+        if ioctl::v2_supported() {
+            return Ok(EventRequest::V2(Box::new(ioctl::LineRequest::new_event(
+                cdev_fd,
+                &[u32::from(pin)],
+                trigger,
+                settings.bias,
+                settings.debounce,
+                settings.event_buffer_size,
+            )?)));
+        }
+
+        Ok(EventRequest::V1(ioctl::EventRequest::new(
+            cdev_fd, pin, trigger,
+        )?))
+    }
+
+    fn fd(&self) -> i32 {
+        match self {
+            EventRequest::V1(event_request) => event_request.fd,
+            EventRequest::V2(line_request) => line_request.fd,
+        }
+    }
+
+    // Returns the level, kernel timestamp and line sequence number of the next event.
+    // Sequence numbers are only available for v2 requests.
+    fn event(&self) -> Result<(Level, Duration, Option<u32>)> {
+        match self {
+            EventRequest::V1(event_request) => {
+                let event = ioctl::get_event(event_request.fd)?;
+                Ok((event.level(), event.timestamp(), None))
+            }
+            EventRequest::V2(line_request) => {
+                let event = ioctl::LineEvent::new(line_request.fd)?;
+                Ok((event.level(), event.timestamp(), Some(event.line_seqno)))
+            }
+        }
+    }
+
+    fn close(&mut self) {
+        match self {
+            EventRequest::V1(event_request) => event_request.close(),
+            EventRequest::V2(line_request) => line_request.close(),
+        }
+    }
+}
 
 #[derive(Debug)]
 struct Interrupt {
     pin: u8,
     trigger: Trigger,
+    settings: LineSettings,
     cdev_fd: i32,
-    event_request: ioctl::EventRequest,
+    event_request: EventRequest,
+    // Sequence number of the last event read from the line request
+    line_seqno: u32,
+    dropped_events: Arc<AtomicU64>,
 }
 
 impl Interrupt {
-    fn new(cdev_fd: i32, pin: u8, trigger: Trigger) -> Result<Interrupt> {
+    fn new(
+        cdev_fd: i32,
+        pin: u8,
+        trigger: Trigger,
+        settings: LineSettings,
+        dropped_events: Arc<AtomicU64>,
+    ) -> Result<Interrupt> {
         Ok(Interrupt {
             pin,
             trigger,
+            settings,
             cdev_fd,
-            event_request: ioctl::EventRequest::new(cdev_fd, pin, trigger)?,
+            event_request: EventRequest::new(cdev_fd, pin, trigger, settings)?,
+            line_seqno: 0,
+            dropped_events,
         })
     }
 
@@ -32,38 +125,150 @@ impl Interrupt {
     }
 
     fn fd(&self) -> i32 {
-        self.event_request.fd
+        self.event_request.fd()
     }
 
     fn pin(&self) -> u8 {
         self.pin
     }
 
-    fn set_trigger(&mut self, trigger: Trigger) -> Result<()> {
-        self.trigger = trigger;
+    fn is_open(&self) -> bool {
+        self.fd() > 0
+    }
 
-        self.reset()
+    // Changes the trigger and line settings without replacing the event request, so
+    // the fd stays the same. Returns false if that isn't supported, which is the case
+    // for v1 requests, and for changes to the event buffer size.
+    fn reconfigure_in_place(&mut self, trigger: Trigger, settings: LineSettings) -> Result<bool> {
+        match self.event_request {
+            EventRequest::V2(ref line_request)
+                if settings.event_buffer_size == self.settings.event_buffer_size =>
+            {
+                line_request.set_event_config(trigger, settings.bias, settings.debounce)?;
+                self.trigger = trigger;
+                self.settings = settings;
+
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
     }
 
-    fn event(&mut self) -> Result<ioctl::Event> {
+    // Replaces the event request with one that uses the new trigger and line settings.
+    // If the new request fails, the previous configuration is requested again.
+    fn reopen(&mut self, trigger: Trigger, settings: LineSettings) -> Result<()> {
+        // A line can't be requested twice, so the old request has to be closed first
+        self.event_request.close();
+        self.line_seqno = 0;
+
+        match EventRequest::new(self.cdev_fd, self.pin, trigger, settings) {
+            Ok(event_request) => {
+                self.event_request = event_request;
+                self.trigger = trigger;
+                self.settings = settings;
+
+                Ok(())
+            }
+            Err(e) => {
+                if let Ok(event_request) =
+                    EventRequest::new(self.cdev_fd, self.pin, self.trigger, self.settings)
+                {
+                    self.event_request = event_request;
+                }
+
+                Err(e)
+            }
+        }
+    }
+
+    fn event(&mut self) -> Result<(Level, Duration)> {
         // This might block if there are no events waiting
-        ioctl::get_event(self.event_request.fd)
+        let (level, timestamp, line_seqno) = self.event_request.event()?;
+
+        // The kernel discards the oldest event when the buffer is full. Each line request
+        // numbers its events starting at 1, so any gaps indicate dropped events.
+        if let Some(line_seqno) = line_seqno {
+            let dropped = line_seqno.wrapping_sub(self.line_seqno).wrapping_sub(1);
+            if dropped > 0 {
+                self.dropped_events
+                    .fetch_add(u64::from(dropped), Ordering::Relaxed);
+
+                #[cfg(feature = "log")]
+                log::warn!("GPIO {}: dropped {} interrupt events", self.pin, dropped);
+            }
+
+            self.line_seqno = line_seqno;
+        }
+
+        Ok((level, timestamp))
+    }
+
+    fn dropped_events(&self) -> u64 {
+        self.dropped_events.load(Ordering::Relaxed)
     }
 
+    // Discards any pending events by replacing the event request
     fn reset(&mut self) -> Result<()> {
-        // Close the old event fd before opening a new one
-        self.event_request.close();
-        self.event_request = ioctl::EventRequest::new(self.cdev_fd, self.pin, self.trigger)?;
+        self.reopen(self.trigger, self.settings)
+    }
+}
 
-        Ok(())
+// Reads timestamped trigger events for a single pin, independently of the EventLoop.
+#[derive(Debug)]
+pub struct EdgeReader {
+    poll: Epoll,
+    interrupt: Interrupt,
+}
+
+impl EdgeReader {
+    pub fn new(
+        cdev_fd: i32,
+        pin: u8,
+        trigger: Trigger,
+        settings: LineSettings,
+    ) -> Result<EdgeReader> {
+        let poll = Epoll::new()?;
+        let interrupt =
+            Interrupt::new(cdev_fd, pin, trigger, settings, Arc::new(AtomicU64::new(0)))?;
+        poll.add(interrupt.fd(), u64::from(pin), EPOLLIN | EPOLLPRI)?;
+
+        Ok(EdgeReader { poll, interrupt })
+    }
+
+    // Returns the next trigger event's level and kernel timestamp, or None if the
+    // deadline passes first.
+    pub fn next(&mut self, deadline: Option<Instant>) -> Result<Option<(Level, Duration)>> {
+        let mut events = [epoll_event { events: 0, u64: 0 }; 1];
+
+        let timeout = match deadline {
+            Some(deadline) => {
+                let now = Instant::now();
+                if now >= deadline {
+                    return Ok(None);
+                }
+
+                Some(deadline - now)
+            }
+            None => None,
+        };
+
+        if self.poll.wait(&mut events, timeout)? == 0 {
+            return Ok(None);
+        }
+
+        Ok(Some(self.interrupt.event()?))
     }
 }
 
+// The kernel queues up to 16 events for each line by default
+const MAX_QUEUED_EVENTS: usize = 16;
+
 #[derive(Debug)]
 struct TriggerStatus {
     interrupt: Option<Interrupt>,
     triggered: bool,
     level: Level,
+    timestamp: Duration,
 }
 
 pub struct EventLoop {
@@ -94,6 +299,7 @@ impl EventLoop {
                 interrupt: None,
                 triggered: false,
                 level: Level::Low,
+                timestamp: Duration::from_secs(0),
             });
         }
 
@@ -153,7 +359,9 @@ impl EventLoop {
                 let trigger_status = &mut self.trigger_status[pin];
 
                 if let Some(ref mut interrupt) = trigger_status.interrupt {
-                    trigger_status.level = interrupt.event()?.level();
+                    let (level, timestamp) = interrupt.event()?;
+                    trigger_status.level = level;
+                    trigger_status.timestamp = timestamp;
                     trigger_status.triggered = true;
                 };
             }
@@ -181,26 +389,189 @@ impl EventLoop {
         }
     }
 
-    pub fn set_interrupt(&mut self, pin: u8, trigger: Trigger) -> Result<()> {
-        let trigger_status = &mut self.trigger_status[pin as usize];
+    pub fn poll_any(
+        &mut self,
+        reset: bool,
+        timeout: Option<Duration>,
+    ) -> Result<Option<(u8, Level, Duration)>> {
+        for trigger_status in self.trigger_status.iter_mut() {
+            if let Some(ref mut interrupt) = trigger_status.interrupt {
+                if reset {
+                    // Discard cached and pending trigger events
+                    trigger_status.triggered = false;
 
-        trigger_status.triggered = false;
+                    self.poll.delete(interrupt.fd())?;
+                    interrupt.reset()?;
+                    self.poll.add(
+                        interrupt.fd(),
+                        u64::from(interrupt.pin()),
+                        EPOLLIN | EPOLLPRI,
+                    )?;
+                } else if trigger_status.triggered {
+                    // Return any trigger events cached during a previous poll
+                    trigger_status.triggered = false;
 
-        // Interrupt already exists. We just need to change the trigger.
-        if let Some(ref mut interrupt) = trigger_status.interrupt {
-            if interrupt.trigger != trigger {
-                // This requires a new event request, so the fd might change
-                self.poll.delete(interrupt.fd())?;
-                interrupt.set_trigger(trigger)?;
-                self.poll
-                    .add(interrupt.fd(), u64::from(pin), EPOLLIN | EPOLLPRI)?;
+                    return Ok(Some((
+                        interrupt.pin(),
+                        trigger_status.level,
+                        trigger_status.timestamp,
+                    )));
+                }
             }
+        }
 
-            return Ok(());
+        let num_events = self.poll.wait(&mut self.events, timeout)?;
+
+        // No events means a timeout occurred
+        if num_events == 0 {
+            return Ok(None);
+        }
+
+        for event in &self.events[0..num_events] {
+            let trigger_status = &mut self.trigger_status[event.u64 as usize];
+
+            if let Some(ref mut interrupt) = trigger_status.interrupt {
+                let (level, timestamp) = interrupt.event()?;
+                trigger_status.level = level;
+                trigger_status.timestamp = timestamp;
+                trigger_status.triggered = true;
+            };
+        }
+
+        // Return the first triggered pin. The rest will be saved for the next poll.
+        for trigger_status in self.trigger_status.iter_mut() {
+            if let Some(ref interrupt) = trigger_status.interrupt {
+                if trigger_status.triggered {
+                    trigger_status.triggered = false;
+
+                    return Ok(Some((
+                        interrupt.pin(),
+                        trigger_status.level,
+                        trigger_status.timestamp,
+                    )));
+                }
+            }
+        }
+
+        Ok(None)
+    }
+
+    pub fn poll_all<'a>(
+        &mut self,
+        pins: &[&'a InputPin],
+        reset: bool,
+        timeout: Option<Duration>,
+    ) -> Result<Vec<(&'a InputPin, Level, Duration)>> {
+        let mut triggered = Vec::new();
+
+        for pin in pins {
+            let trigger_status = &mut self.trigger_status[pin.pin() as usize];
+
+            // Did we cache any trigger events during a previous poll?
+            if trigger_status.triggered {
+                trigger_status.triggered = false;
+
+                if !reset {
+                    triggered.push((*pin, trigger_status.level, trigger_status.timestamp));
+                }
+            }
+
+            // Reset any pending trigger events
+            if let Some(ref mut interrupt) = trigger_status.interrupt {
+                if reset {
+                    self.poll.delete(interrupt.fd())?;
+                    interrupt.reset()?;
+                    self.poll.add(
+                        interrupt.fd(),
+                        u64::from(interrupt.pin()),
+                        EPOLLIN | EPOLLPRI,
+                    )?;
+                }
+            }
         }
 
+        // Block until we get any of the events we're waiting for, and then keep reading
+        // without blocking until all queued events have been drained.
+        let now = Instant::now();
+        let mut drain_rounds = 0;
+        loop {
+            let wait = if triggered.is_empty() {
+                timeout.map(|t| t.checked_sub(now.elapsed()).unwrap_or_default())
+            } else {
+                Some(Duration::from_secs(0))
+            };
+
+            let num_events = self.poll.wait(&mut self.events, wait)?;
+            let mut found = false;
+
+            for event in &self.events[0..num_events] {
+                let pin = event.u64 as usize;
+
+                let trigger_status = &mut self.trigger_status[pin];
+
+                if let Some(ref mut interrupt) = trigger_status.interrupt {
+                    let (level, timestamp) = interrupt.event()?;
+
+                    if let Some(input_pin) = pins.iter().find(|p| p.pin() as usize == pin) {
+                        triggered.push((*input_pin, level, timestamp));
+                        found = true;
+                    } else {
+                        // Save events for pins we're not waiting for
+                        trigger_status.level = level;
+                        trigger_status.timestamp = timestamp;
+                        trigger_status.triggered = true;
+                    }
+                };
+            }
+
+            if !triggered.is_empty() {
+                drain_rounds += 1;
+
+                if !found || drain_rounds > MAX_QUEUED_EVENTS {
+                    break;
+                }
+            } else if num_events == 0 {
+                // No events means a timeout occurred
+                break;
+            } else if let Some(t) = timeout {
+                // Make sure a pin we're not waiting for isn't keeping us from
+                // returning within the requested timeout.
+                if now.elapsed() > t {
+                    break;
+                }
+            }
+        }
+
+        // Events for different pins could have been read out of order
+        triggered.sort_by_key(|event| event.2);
+
+        Ok(triggered)
+    }
+
+    pub fn set_interrupt(
+        &mut self,
+        pin: u8,
+        trigger: Trigger,
+        settings: LineSettings,
+    ) -> Result<()> {
+        // Interrupt already exists. We just need to change the trigger and line settings.
+        if self.trigger_status[pin as usize].interrupt.is_some() {
+            self.set_settings(pin, settings)?;
+            return self.set_trigger(pin, trigger);
+        }
+
+        let trigger_status = &mut self.trigger_status[pin as usize];
+
+        trigger_status.triggered = false;
+
         // Register a new interrupt
-        let interrupt = Interrupt::new(self.cdev_fd, pin, trigger)?;
+        let interrupt = Interrupt::new(
+            self.cdev_fd,
+            pin,
+            trigger,
+            settings,
+            Arc::new(AtomicU64::new(0)),
+        )?;
         self.poll
             .add(interrupt.fd(), u64::from(pin), EPOLLIN | EPOLLPRI)?;
         trigger_status.interrupt = Some(interrupt);
@@ -208,6 +579,56 @@ impl EventLoop {
         Ok(())
     }
 
+    pub fn set_trigger(&mut self, pin: u8, trigger: Trigger) -> Result<()> {
+        let trigger_status = &mut self.trigger_status[pin as usize];
+
+        trigger_status.triggered = false;
+
+        if let Some(ref mut interrupt) = trigger_status.interrupt {
+            if interrupt.trigger != trigger {
+                let settings = interrupt.settings;
+                reconfigure(&self.poll, interrupt, trigger, settings)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn set_settings(&mut self, pin: u8, settings: LineSettings) -> Result<()> {
+        let trigger_status = &mut self.trigger_status[pin as usize];
+
+        if let Some(ref mut interrupt) = trigger_status.interrupt {
+            if interrupt.settings != settings {
+                trigger_status.triggered = false;
+                let trigger = interrupt.trigger;
+                reconfigure(&self.poll, interrupt, trigger, settings)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn trigger(&self, pin: u8) -> Option<Trigger> {
+        self.trigger_status[pin as usize]
+            .interrupt
+            .as_ref()
+            .map(|interrupt| interrupt.trigger())
+    }
+
+    pub fn dropped_events(&self, pin: u8) -> Option<u64> {
+        self.trigger_status[pin as usize]
+            .interrupt
+            .as_ref()
+            .map(|interrupt| interrupt.dropped_events())
+    }
+
+    pub fn interrupt_fd(&self, pin: u8) -> Option<i32> {
+        self.trigger_status[pin as usize]
+            .interrupt
+            .as_ref()
+            .map(|interrupt| interrupt.fd())
+    }
+
     pub fn clear_interrupt(&mut self, pin: u8) -> Result<()> {
         let trigger_status = &mut self.trigger_status[pin as usize];
 
@@ -221,68 +642,349 @@ impl EventLoop {
     }
 }
 
-#[derive(Debug)]
+// Applies a new trigger and line settings to an interrupt registered with poll. If the
+// event request has to be replaced, the new fd is registered instead of the old one.
+fn reconfigure(
+    poll: &Epoll,
+    interrupt: &mut Interrupt,
+    trigger: Trigger,
+    settings: LineSettings,
+) -> Result<()> {
+    if interrupt.reconfigure_in_place(trigger, settings)? {
+        return Ok(());
+    }
+
+    poll.delete(interrupt.fd())?;
+    let result = interrupt.reopen(trigger, settings);
+    if interrupt.is_open() {
+        poll.add(
+            interrupt.fd(),
+            u64::from(interrupt.pin()),
+            EPOLLIN | EPOLLPRI,
+        )?;
+    }
+
+    result
+}
+
+/// Scheduling settings for the threads that handle asynchronous interrupts.
+///
+/// By default, the polling threads inherit the scheduling policy and CPU affinity of the
+/// thread that configures the interrupt. Latency-sensitive applications can pin the
+/// polling threads to a specific CPU core, and run them with a real-time priority to
+/// reduce jitter.
+///
+/// Use [`Gpio::set_interrupt_thread_settings`] to apply the settings to all
+/// asynchronous interrupts configured afterwards.
+///
+/// [`Gpio::set_interrupt_thread_settings`]: struct.Gpio.html#method.set_interrupt_thread_settings
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Default)]
+pub struct InterruptThreadSettings {
+    /// Restricts the thread to the specified CPU core.
+    pub cpu: Option<usize>,
+    /// Runs the thread with the `SCHED_FIFO` scheduling policy at the specified
+    /// priority (`1`-`99`).
+    ///
+    /// Real-time priorities require superuser privileges, or the `CAP_SYS_NICE` capability.
+    pub realtime_priority: Option<u8>,
+}
+
+impl InterruptThreadSettings {
+    // Applies the settings to the specified thread
+    fn apply(&self, thread: libc::pthread_t) -> Result<()> {
+        if let Some(cpu) = self.cpu {
+            if cpu >= libc::CPU_SETSIZE as usize {
+                return Err(Error::Io(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "CPU core out of range",
+                )));
+            }
+
+            let result = unsafe {
+                let mut cpu_set: libc::cpu_set_t = mem::zeroed();
+                libc::CPU_SET(cpu, &mut cpu_set);
+
+                libc::pthread_setaffinity_np(thread, mem::size_of::<libc::cpu_set_t>(), &cpu_set)
+            };
+
+            if result != 0 {
+                return Err(Error::Io(io::Error::from_raw_os_error(result)));
+            }
+        }
+
+        if let Some(priority) = self.realtime_priority {
+            let param = libc::sched_param {
+                sched_priority: i32::from(priority),
+            };
+
+            let result = unsafe { libc::pthread_setschedparam(thread, libc::SCHED_FIFO, &param) };
+
+            if result != 0 {
+                return Err(Error::Io(io::Error::from_raw_os_error(result)));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+type Callback = Box<dyn FnMut(Level) + Send>;
+
 pub struct AsyncInterrupt {
-    poll_thread: Option<thread::JoinHandle<Result<()>>>,
+    // The poll thread returns the callback when it exits, even if an error occurred.
+    // None once the thread has been stopped, or if reconfigure couldn't restart it.
+    poll_thread: Option<thread::JoinHandle<(Callback, Result<()>)>>,
     tx: EventFd,
+    // Shared with the poll thread, so the line request can be reconfigured in place
+    interrupt: Arc<Mutex<Interrupt>>,
+    // Keeps the gpiochip cdev open for as long as the poll thread is running, even
+    // after the InputPin and all Gpio instances have been dropped.
+    gpio_state: Arc<GpioState>,
+    pin: u8,
+    trigger: Trigger,
+    settings: LineSettings,
+    thread_settings: InterruptThreadSettings,
+    dropped_events: Arc<AtomicU64>,
+}
+
+impl fmt::Debug for AsyncInterrupt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AsyncInterrupt")
+            .field("poll_thread", &self.poll_thread)
+            .field("tx", &self.tx)
+            .field("interrupt", &self.interrupt)
+            .field("cdev_fd", &self.gpio_state.cdev.as_raw_fd())
+            .field("pin", &self.pin)
+            .field("trigger", &self.trigger)
+            .field("settings", &self.settings)
+            .field("thread_settings", &self.thread_settings)
+            .field("dropped_events", &self.dropped_events)
+            .finish()
+    }
 }
 
 impl AsyncInterrupt {
-    pub fn new<C>(fd: i32, pin: u8, trigger: Trigger, mut callback: C) -> Result<AsyncInterrupt>
+    pub fn new<C>(
+        gpio_state: Arc<GpioState>,
+        pin: u8,
+        trigger: Trigger,
+        settings: LineSettings,
+        thread_settings: InterruptThreadSettings,
+        callback: C,
+    ) -> Result<AsyncInterrupt>
     where
         C: FnMut(Level) + Send + 'static,
     {
-        let tx = EventFd::new()?;
+        Self::spawn(
+            gpio_state,
+            pin,
+            trigger,
+            settings,
+            thread_settings,
+            Arc::new(AtomicU64::new(0)),
+            Box::new(callback),
+        )
+        .map_err(|(e, _)| e)
+    }
+
+    // Requests the line and starts the poll thread. If an error occurs, the callback
+    // is returned along with the error, so the caller can restore the previous state.
+    fn spawn(
+        gpio_state: Arc<GpioState>,
+        pin: u8,
+        trigger: Trigger,
+        settings: LineSettings,
+        thread_settings: InterruptThreadSettings,
+        dropped_events: Arc<AtomicU64>,
+        mut callback: Callback,
+    ) -> result::Result<AsyncInterrupt, (Error, Option<Callback>)> {
+        let tx = match EventFd::new() {
+            Ok(tx) => tx,
+            Err(e) => return Err((Error::Io(e), Some(callback))),
+        };
         let rx = tx.fd();
 
-        let poll_thread = thread::spawn(move || -> Result<()> {
-            let poll = Epoll::new()?;
-
-            // rx becomes readable when the main thread calls notify()
-            poll.add(rx, rx as u64, EPOLLERR | EPOLLET | EPOLLIN)?;
-
-            let mut interrupt = Interrupt::new(fd, pin, trigger)?;
-            poll.add(interrupt.fd(), interrupt.fd() as u64, EPOLLIN | EPOLLPRI)?;
-
-            let mut previous_level = Level::Low; // for now
-
-            let mut events = [epoll_event { events: 0, u64: 0 }; 2];
-            loop {
-                let num_events = poll.wait(&mut events, None)?;
-                if num_events > 0 {
-                    for event in &events[0..num_events] {
-                        let fd = event.u64 as i32;
-                        if fd == rx {
-                            return Ok(()); // The main thread asked us to stop
-                        } else if fd == interrupt.fd() {
-                            let level = interrupt.event()?.level();
-                            if level != previous_level {
-                                callback(level);
-                                previous_level = level;
-                            }
-                        }
-                    }
-                }
-            }
+        // The line is requested on the calling thread, so any errors are returned
+        // before the callback is moved to the poll thread.
+        let interrupt = match Interrupt::new(
+            gpio_state.cdev.as_raw_fd(),
+            pin,
+            trigger,
+            settings,
+            dropped_events.clone(),
+        ) {
+            Ok(interrupt) => Arc::new(Mutex::new(interrupt)),
+            Err(e) => return Err((e, Some(callback))),
+        };
+
+        let thread_interrupt = interrupt.clone();
+        let poll_thread = thread::spawn(move || {
+            let result = poll_events(rx, &thread_interrupt, &mut callback);
+
+            (callback, result)
         });
 
-        Ok(AsyncInterrupt {
+        let thread = poll_thread.as_pthread_t();
+
+        let mut async_interrupt = AsyncInterrupt {
             poll_thread: Some(poll_thread),
             tx,
-        })
+            interrupt,
+            gpio_state,
+            pin,
+            trigger,
+            settings,
+            thread_settings,
+            dropped_events,
+        };
+
+        if let Err(e) = thread_settings.apply(thread) {
+            let (callback, _) = async_interrupt.stop_thread();
+            return Err((e, callback));
+        }
+
+        Ok(async_interrupt)
+    }
+
+    pub fn trigger(&self) -> Trigger {
+        self.trigger
+    }
+
+    pub fn set_trigger(&mut self, trigger: Trigger) -> Result<()> {
+        if trigger == self.trigger && self.poll_thread.is_some() {
+            return Ok(());
+        }
+
+        self.reconfigure(trigger, self.settings)
+    }
+
+    pub fn set_settings(&mut self, settings: LineSettings) -> Result<()> {
+        if settings == self.settings && self.poll_thread.is_some() {
+            return Ok(());
+        }
+
+        self.reconfigure(self.trigger, settings)
+    }
+
+    pub fn dropped_events(&self) -> u64 {
+        self.dropped_events.load(Ordering::Relaxed)
     }
 
     pub fn stop(&mut self) -> Result<()> {
-        self.tx.notify()?;
+        self.stop_thread().1
+    }
+
+    // Applies a new trigger and line settings. If an error occurs, the previous
+    // configuration remains active.
+    fn reconfigure(&mut self, trigger: Trigger, settings: LineSettings) -> Result<()> {
+        // Without a poll thread, there's no callback left to restart it with
+        if self.poll_thread.is_none() {
+            return Err(Error::Io(io::Error::new(
+                io::ErrorKind::NotConnected,
+                "asynchronous interrupt thread isn't running",
+            )));
+        }
+
+        // v2 line requests can be changed in place while the poll thread keeps running
+        if self
+            .interrupt
+            .lock()
+            .unwrap()
+            .reconfigure_in_place(trigger, settings)?
+        {
+            self.trigger = trigger;
+            self.settings = settings;
+
+            return Ok(());
+        }
+
+        // Otherwise, we stop the poll thread, and start a new one with a new line request
+        // and the same callback.
+        let callback = match self.stop_thread() {
+            (Some(callback), _) => callback,
+            (None, result) => return result,
+        };
+
+        // A line can't be requested twice, so the old request has to be closed first
+        self.interrupt.lock().unwrap().event_request.close();
+
+        match Self::spawn(
+            self.gpio_state.clone(),
+            self.pin,
+            trigger,
+            settings,
+            self.thread_settings,
+            self.dropped_events.clone(),
+            callback,
+        ) {
+            Ok(async_interrupt) => {
+                *self = async_interrupt;
+
+                Ok(())
+            }
+            Err((e, Some(callback))) => {
+                // Restore the previous configuration
+                if let Ok(async_interrupt) = Self::spawn(
+                    self.gpio_state.clone(),
+                    self.pin,
+                    self.trigger,
+                    self.settings,
+                    self.thread_settings,
+                    self.dropped_events.clone(),
+                    callback,
+                ) {
+                    *self = async_interrupt;
+                }
+
+                Err(e)
+            }
+            Err((e, None)) => Err(e),
+        }
+    }
+
+    fn stop_thread(&mut self) -> (Option<Callback>, Result<()>) {
+        if let Err(e) = self.tx.notify() {
+            return (None, Err(Error::Io(e)));
+        }
 
         if let Some(poll_thread) = self.poll_thread.take() {
             match poll_thread.join() {
-                Ok(r) => return r,
-                Err(_) => return Err(Error::ThreadPanic),
+                Ok((callback, result)) => return (Some(callback), result),
+                Err(_) => return (None, Err(Error::ThreadPanic)),
             }
         }
 
-        Ok(())
+        (None, Ok(()))
+    }
+}
+
+// Waits for interrupt events, and executes the callback for each event, until rx
+// becomes readable.
+fn poll_events(rx: i32, interrupt: &Mutex<Interrupt>, callback: &mut Callback) -> Result<()> {
+    let poll = Epoll::new()?;
+
+    // rx becomes readable when the main thread calls notify()
+    poll.add(rx, rx as u64, EPOLLERR | EPOLLET | EPOLLIN)?;
+
+    let interrupt_fd = interrupt.lock().unwrap().fd();
+    poll.add(interrupt_fd, interrupt_fd as u64, EPOLLIN | EPOLLPRI)?;
+
+    // Every event is dispatched. The level is derived from the edge reported by
+    // the kernel, so consecutive events with the same level are separate edges,
+    // for instance repeated rising edges with Trigger::RisingEdge, or a
+    // falling edge on a line that's initially high.
+    let mut events = [epoll_event { events: 0, u64: 0 }; 2];
+    loop {
+        let num_events = poll.wait(&mut events, None)?;
+        for event in &events[0..num_events] {
+            let fd = event.u64 as i32;
+            if fd == rx {
+                return Ok(()); // The main thread asked us to stop
+            } else if fd == interrupt_fd {
+                let (level, _) = interrupt.lock().unwrap().event()?;
+                callback(level);
+            }
+        }
     }
 }
 
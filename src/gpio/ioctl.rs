@@ -3,3 +3,43 @@ mod v2;
 
 pub use v1::*;
 // pub use v2::*;
+// Interrupts use the v2 line requests, and fall back to the v1 event requests
+// on kernels that don't support the v2 uAPI.
+pub use v2::{LineEvent, LineRequest};
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::gpio::Error;
+
+// Set when the kernel doesn't support the v2 uAPI
+static V2_UNSUPPORTED: AtomicBool = AtomicBool::new(false);
+
+// Checks whether the kernel supports the v2 uAPI, which was added in Linux 5.10, by
+// requesting the line info for the first line. gpiolib-cdev returns EINVAL for
+// unknown ioctls, while other character devices return ENOTTY. Any other result
+// means the v2 ioctl was recognized.
+pub fn detect_v2(cdev_fd: i32) {
+    let unsupported = match v2::LineInfo::new(cdev_fd, 0) {
+        Err(Error::Io(ref e)) => {
+            matches!(e.raw_os_error(), Some(libc::EINVAL) | Some(libc::ENOTTY))
+        }
+        _ => false,
+    };
+
+    V2_UNSUPPORTED.store(unsupported, Ordering::Relaxed);
+}
+
+// Returns false if detect_v2() found the kernel doesn't support the v2 uAPI
+pub fn v2_supported() -> bool {
+    !V2_UNSUPPORTED.load(Ordering::Relaxed)
+}
+
+// Retrieves the line info through the v2 uAPI, and falls back to the v1 uAPI on
+// kernels that don't support v2, or that were built without CONFIG_GPIO_CDEV_V1.
+pub fn line_info(cdev_fd: i32, offset: u32) -> Result<LineInfo, Error> {
+    if v2_supported() {
+        v2::LineInfo::new(cdev_fd, offset).map(LineInfo::from)
+    } else {
+        LineInfo::new(cdev_fd, offset)
+    }
+}
@@ -11,6 +11,8 @@ use std::mem;
 use std::os::unix::io::AsRawFd;
 use std::time::Duration;
 
+use super::v2;
+
 #[cfg(target_env = "gnu")]
 type IoctlLong = libc::c_ulong;
 #[cfg(target_env = "musl")]
@@ -113,12 +115,70 @@ pub struct LineInfo {
 }
 
 impl LineInfo {
-    pub fn new() -> LineInfo {
-        LineInfo {
-            line_offset: 0,
+    pub fn new(cdev_fd: c_int, offset: u32) -> Result<LineInfo> {
+        let mut line_info = LineInfo {
+            line_offset: offset,
             flags: 0,
             name: [0u8; NAME_BUFSIZE],
             consumer: [0u8; LABEL_BUFSIZE],
+        };
+
+        parse_retval!(unsafe { libc::ioctl(cdev_fd, REQ_GET_LINE_INFO, &mut line_info) })?;
+
+        Ok(line_info)
+    }
+
+    pub fn name(&self) -> String {
+        cbuf_to_string(&self.name)
+    }
+
+    pub fn consumer(&self) -> String {
+        cbuf_to_string(&self.consumer)
+    }
+
+    pub fn used(&self) -> bool {
+        (self.flags & LINE_FLAG_KERNEL) > 0
+    }
+
+    pub fn output(&self) -> bool {
+        (self.flags & LINE_FLAG_IS_OUT) > 0
+    }
+
+    pub fn active_low(&self) -> bool {
+        (self.flags & LINE_FLAG_ACTIVE_LOW) > 0
+    }
+
+    pub fn open_drain(&self) -> bool {
+        (self.flags & LINE_FLAG_OPEN_DRAIN) > 0
+    }
+
+    pub fn open_source(&self) -> bool {
+        (self.flags & LINE_FLAG_OPEN_SOURCE) > 0
+    }
+}
+
+impl From<v2::LineInfo> for LineInfo {
+    fn from(info: v2::LineInfo) -> LineInfo {
+        let line_flags = info.flags();
+        let mut flags = 0;
+
+        for &(set, flag) in &[
+            (line_flags.used(), LINE_FLAG_KERNEL),
+            (line_flags.output(), LINE_FLAG_IS_OUT),
+            (line_flags.active_low(), LINE_FLAG_ACTIVE_LOW),
+            (line_flags.open_drain(), LINE_FLAG_OPEN_DRAIN),
+            (line_flags.open_source(), LINE_FLAG_OPEN_SOURCE),
+        ] {
+            if set {
+                flags |= flag;
+            }
+        }
+
+        LineInfo {
+            line_offset: info.offset,
+            flags,
+            name: info.name,
+            consumer: info.consumer,
         }
     }
 }
@@ -396,6 +456,10 @@ impl Event {
         self.trigger
     }
 
+    pub fn timestamp(&self) -> Duration {
+        self.timestamp
+    }
+
     pub fn level(&self) -> Level {
         match self.trigger {
             Trigger::RisingEdge => Level::High,
@@ -414,8 +478,12 @@ pub fn get_event(event_fd: c_int) -> Result<Event> {
     Ok(Event::from_event_data(event_data))
 }
 
-// Find the correct gpiochip device based on its label
-pub fn find_gpiochip() -> Result<File> {
+// Find the gpiochip with the specified label. If none of the available gpiochips match,
+// fall back to the first gpiochip with any of the known driver names, for kernels that
+// use a different label than expected for the current SoC.
+pub fn find_gpiochip(label: &str) -> Result<File> {
+    let mut fallback = None;
+
     for id in 0..=255 {
         let gpiochip = match OpenOptions::new()
             .read(true)
@@ -426,20 +494,27 @@ pub fn find_gpiochip() -> Result<File> {
             Err(ref e) if e.kind() == io::ErrorKind::PermissionDenied => {
                 return Err(Error::PermissionDenied(format!("{}{}", PATH_GPIOCHIP, id)));
             }
+            // The gpiochip numbering isn't necessarily contiguous
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => continue,
             Err(e) => return Err(Error::from(e)),
         };
 
         let chip_info = ChipInfo::new(gpiochip.as_raw_fd())?;
-        if chip_info.label[0..DRIVER_NAME.len()] == DRIVER_NAME[..]
-            || chip_info.label[0..DRIVER_NAME_BCM2711.len()] == DRIVER_NAME_BCM2711[..]
-            || chip_info.label[0..DRIVER_NAME_BCM2712.len()] == DRIVER_NAME_BCM2712[..]
-        {
+        if cbuf_to_cstring(&chip_info.label).as_bytes() == label.as_bytes() {
             return Ok(gpiochip);
         }
+
+        if fallback.is_none()
+            && (chip_info.label[0..DRIVER_NAME.len()] == DRIVER_NAME[..]
+                || chip_info.label[0..DRIVER_NAME_BCM2711.len()] == DRIVER_NAME_BCM2711[..]
+                || chip_info.label[0..DRIVER_NAME_BCM2712.len()] == DRIVER_NAME_BCM2712[..])
+        {
+            fallback = Some(gpiochip);
+        }
     }
 
     // File Not Found I/O error
-    Err(Error::Io(io::Error::from_raw_os_error(ENOENT)))
+    fallback.ok_or_else(|| Error::Io(io::Error::from_raw_os_error(ENOENT)))
 }
 
 // Create a CString from a C-style NUL-terminated char array. This workaround
@@ -453,3 +528,7 @@ fn cbuf_to_cstring(buf: &[u8]) -> CString {
     })
     .unwrap_or_default()
 }
+
+fn cbuf_to_string(buf: &[u8]) -> String {
+    cbuf_to_cstring(buf).into_string().unwrap_or_default()
+}
@@ -1,7 +1,7 @@
 #![allow(clippy::unnecessary_cast)]
 #![allow(dead_code)]
 
-use crate::gpio::{Error, Level, Result, Trigger};
+use crate::gpio::{Bias, Error, Level, Result, Trigger};
 use libc::{self, c_int, c_void, ENOENT};
 use std::ffi::CString;
 use std::fmt;
@@ -258,7 +258,7 @@ impl LineFlags {
     }
 
     pub fn bias_disabled(&self) -> bool {
-        (self.flags & LINE_FLAG_EDGE_FALLING) > 0
+        (self.flags & LINE_FLAG_BIAS_DISABLED) > 0
     }
 
     pub fn event_clock_realtime(&self) -> bool {
@@ -450,8 +450,64 @@ impl LineRequest {
         }
     }
 
+    // Request one or more lines as inputs with edge detection and the specified bias.
+    // If debounce is set, the kernel filters out any level changes that don't remain
+    // stable for the specified period, using either the hardware debounce filter or a
+    // software implementation. An event_buffer_size of 0 selects the kernel's default
+    // buffer size. All lines are configured atomically, and share a single fd. Each
+    // event identifies its line by offset.
+    pub fn new_event(
+        cdev_fd: c_int,
+        offsets: &[u32],
+        trigger: Trigger,
+        bias: Bias,
+        debounce: Option<Duration>,
+        event_buffer_size: u32,
+    ) -> Result<LineRequest> {
+        if offsets.is_empty() || offsets.len() > LINES_MAX {
+            return Err(Error::Io(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid number of lines",
+            )));
+        }
+
+        let mut line_request = LineRequest::default();
+        line_request.offsets[..offsets.len()].copy_from_slice(offsets);
+        line_request.num_lines = offsets.len() as u32;
+        line_request.event_buffer_size = event_buffer_size;
+        line_request.config = event_config(line_request.num_lines, trigger, bias, debounce);
+
+        // Set consumer label, so other processes know we're monitoring this event
+        line_request.consumer[0..CONSUMER_LABEL.len()].copy_from_slice(CONSUMER_LABEL.as_bytes());
+
+        parse_retval!(unsafe { libc::ioctl(cdev_fd, GPIO_V2_GET_LINE_IOCTL, &mut line_request) })?;
+
+        // If the fd is zero or negative, an error occurred
+        if line_request.fd <= 0 {
+            Err(Error::Io(std::io::Error::last_os_error()))
+        } else {
+            Ok(line_request)
+        }
+    }
+
+    // Change the edge detection, bias and debounce settings of an event request in
+    // place. The fd and any queued events are preserved, and the event buffer size
+    // can't be changed.
+    pub fn set_event_config(
+        &self,
+        trigger: Trigger,
+        bias: Bias,
+        debounce: Option<Duration>,
+    ) -> Result<()> {
+        let mut config = event_config(self.num_lines, trigger, bias, debounce);
+
+        parse_retval!(unsafe { libc::ioctl(self.fd, GPIO_V2_LINE_SET_CONFIG_IOCTL, &mut config) })?;
+
+        Ok(())
+    }
+
     pub fn levels(&self) -> Result<LineValues> {
-        let mut line_values = LineValues::new(0, 0x01);
+        let mut line_values = LineValues::new(0, line_mask(self.num_lines));
 
         parse_retval!(unsafe {
             libc::ioctl(self.fd, GPIO_V2_LINE_GET_VALUES_IOCTL, &mut line_values)
@@ -471,6 +527,54 @@ impl LineRequest {
     }
 }
 
+// Line configuration for input lines with edge detection
+fn event_config(
+    num_lines: u32,
+    trigger: Trigger,
+    bias: Bias,
+    debounce: Option<Duration>,
+) -> LineConfig {
+    let mut config = LineConfig {
+        flags: LINE_FLAG_INPUT
+            | match trigger {
+                Trigger::Disabled => 0,
+                Trigger::RisingEdge => LINE_FLAG_EDGE_RISING,
+                Trigger::FallingEdge => LINE_FLAG_EDGE_FALLING,
+                Trigger::Both => LINE_FLAG_EDGE_RISING | LINE_FLAG_EDGE_FALLING,
+            }
+            | match bias {
+                Bias::Off => LINE_FLAG_BIAS_DISABLED,
+                Bias::PullDown => LINE_FLAG_BIAS_PULL_DOWN,
+                Bias::PullUp => LINE_FLAG_BIAS_PULL_UP,
+            },
+        ..Default::default()
+    };
+
+    if let Some(debounce) = debounce {
+        // debounce_period_us shares its storage with the 64-bit values field
+        config.num_attrs = 1;
+        config.attrs[0] = LineConfigAttribute {
+            attr: LineAttribute {
+                id: LINE_ATTR_ID_DEBOUNCE,
+                padding: 0,
+                values: debounce.as_micros().min(u128::from(u32::MAX)) as u64,
+            },
+            mask: line_mask(num_lines),
+        };
+    }
+
+    config
+}
+
+// Bitmask that selects the first num_lines lines of a line request
+fn line_mask(num_lines: u32) -> u64 {
+    if num_lines >= 64 {
+        u64::MAX
+    } else {
+        (1 << num_lines) - 1
+    }
+}
+
 impl Drop for LineRequest {
     fn drop(&mut self) {
         self.close();
@@ -499,7 +603,7 @@ impl LineValues {
     }
 }
 
-#[derive(Copy, Clone)]
+#[derive(Debug, Copy, Clone, Default)]
 #[repr(C)]
 pub struct LineEvent {
     pub timestamp_ns: u64,
@@ -510,6 +614,43 @@ pub struct LineEvent {
     pub padding: [u32; 6],
 }
 
+impl LineEvent {
+    // Read the next edge event for a line request. This blocks if no events are queued.
+    pub fn new(line_fd: c_int) -> Result<LineEvent> {
+        let mut line_event = LineEvent::default();
+
+        let bytes_read = parse_retval!(unsafe {
+            libc::read(
+                line_fd,
+                &mut line_event as *mut LineEvent as *mut c_void,
+                mem::size_of::<LineEvent>(),
+            )
+        })?;
+
+        if bytes_read < mem::size_of::<LineEvent>() as isize {
+            Err(std::io::Error::new(
+                std::io::ErrorKind::UnexpectedEof,
+                "failed to fill whole buffer",
+            )
+            .into())
+        } else {
+            Ok(line_event)
+        }
+    }
+
+    pub fn level(&self) -> Level {
+        if self.id == LINE_EVENT_RISING_EDGE {
+            Level::High
+        } else {
+            Level::Low
+        }
+    }
+
+    pub fn timestamp(&self) -> Duration {
+        Duration::from_nanos(self.timestamp_ns)
+    }
+}
+
 // Find the correct gpiochip device based on its label
 pub fn find_gpiochip() -> Result<File> {
     for id in 0..=255 {
@@ -0,0 +1,112 @@
+use std::fs::OpenOptions;
+use std::io;
+use std::os::unix::fs::OpenOptionsExt;
+use std::os::unix::io::AsRawFd;
+use std::ptr;
+
+use libc::{self, c_void, size_t, MAP_FAILED, MAP_SHARED, O_SYNC, PROT_READ};
+
+use crate::gpio::{Error, Result, SlewRate};
+use crate::system::{self, DeviceInfo};
+
+const PATH_DEV_MEM: &str = "/dev/mem";
+// mmap offsets need to be page-aligned, so we map the entire page containing the
+// pad control registers
+const PAGE_SIZE: u64 = 4096;
+
+// Each pad control register configures a group of pins: GPIO 0-27, GPIO 28-45 and
+// GPIO 46-53 (GPIO pads control whitepaper)
+const PADS_GROUP1_START: u8 = 28;
+const PADS_GROUP2_START: u8 = 46;
+
+const PADS_DRIVE_MASK: u32 = 0x07;
+const PADS_SLEW_MASK: u32 = 0x10;
+
+// Read-only mapping of the BCM283x and BCM2711 pad control registers
+pub(crate) struct PadControl {
+    mem_ptr: *const u32,
+    // Register offset within the mapped page
+    base: usize,
+}
+
+impl PadControl {
+    pub(crate) fn open() -> Result<PadControl> {
+        let device_info = DeviceInfo::new().map_err(|_| Error::UnknownModel)?;
+
+        let pads_address = device_info
+            .peripheral_addresses()
+            .pads
+            .ok_or_else(super::pad_control_unsupported)?;
+
+        // The pad control registers aren't accessible through /dev/gpiomem
+        let mem_file = match OpenOptions::new()
+            .read(true)
+            .custom_flags(O_SYNC)
+            .open(PATH_DEV_MEM)
+        {
+            Ok(file) => file,
+            Err(ref e) if e.kind() == io::ErrorKind::PermissionDenied => {
+                return Err(Error::PermissionDenied(String::from(PATH_DEV_MEM)))
+            }
+            Err(e) => return Err(Error::Io(e)),
+        };
+
+        let page_offset = system::devmem_offset(pads_address & !(PAGE_SIZE - 1))?;
+
+        let mem_ptr = unsafe {
+            libc::mmap(
+                ptr::null_mut(),
+                PAGE_SIZE as size_t,
+                PROT_READ,
+                MAP_SHARED,
+                mem_file.as_raw_fd(),
+                page_offset,
+            )
+        };
+
+        if mem_ptr == MAP_FAILED {
+            return Err(Error::Io(io::Error::last_os_error()));
+        }
+
+        Ok(PadControl {
+            mem_ptr: mem_ptr as *const u32,
+            base: (pads_address & (PAGE_SIZE - 1)) as usize / std::mem::size_of::<u32>(),
+        })
+    }
+
+    // Returns the drive strength in mA configured for the pin's group
+    pub(crate) fn drive_strength(&self, pin: u8) -> u8 {
+        // DRIVE selects 2 to 16 mA in steps of 2 mA
+        ((self.read(pin) & PADS_DRIVE_MASK) as u8 + 1) * 2
+    }
+
+    // Returns the slew rate configured for the pin's group
+    pub(crate) fn slew_rate(&self, pin: u8) -> SlewRate {
+        if self.read(pin) & PADS_SLEW_MASK > 0 {
+            SlewRate::Fast
+        } else {
+            SlewRate::Slow
+        }
+    }
+
+    #[inline(always)]
+    fn read(&self, pin: u8) -> u32 {
+        let group = if pin < PADS_GROUP1_START {
+            0
+        } else if pin < PADS_GROUP2_START {
+            1
+        } else {
+            2
+        };
+
+        unsafe { ptr::read_volatile(self.mem_ptr.add(self.base + group)) }
+    }
+}
+
+impl Drop for PadControl {
+    fn drop(&mut self) {
+        unsafe {
+            libc::munmap(self.mem_ptr as *mut c_void, PAGE_SIZE as size_t);
+        }
+    }
+}
@@ -1,10 +1,21 @@
-use std::os::unix::io::AsRawFd;
+use std::io;
+use std::os::unix::io::{AsRawFd, RawFd};
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
-use std::time::Duration;
-
-use super::soft_pwm::SoftPwm;
-use crate::gpio::{interrupt::AsyncInterrupt, Bias, GpioState, Level, Mode, Result, Trigger};
+use std::time::{Duration, Instant};
+
+use super::soft_pwm::{self, JitterStats, SoftPwm};
+use crate::gpio::interrupt::{AsyncInterrupt, EdgeReader, LineSettings};
+use crate::gpio::{Bias, Error, GpioState, Level, Mode, Result, Trigger};
+use crate::system;
+
+// Emits a trace entry for a GPIO register write when the log feature is enabled
+macro_rules! trace {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "log")]
+        log::trace!($($arg)*);
+    };
+}
 
 const NANOS_PER_SEC: f64 = 1_000_000_000.0;
 
@@ -23,6 +34,10 @@ macro_rules! impl_pin {
 macro_rules! impl_input {
     () => {
         /// Reads the pin's logic level.
+        ///
+        /// `read` performs a single volatile read of the GPIO level register. It doesn't
+        /// acquire any locks or make any system calls, which makes it suitable for
+        /// sampling an input in a tight loop.
         #[inline]
         pub fn read(&self) -> Level {
             self.pin.read()
@@ -43,6 +58,37 @@ macro_rules! impl_input {
         pub fn is_high(&self) -> bool {
             self.pin.read() == Level::High
         }
+
+        /// Reads the pin's logic level multiple times, and returns the majority level.
+        ///
+        /// `read_debounced` is a simple software filter for noisy inputs. The pin is
+        /// sampled `samples` times, waiting `interval` between consecutive samples.
+        /// This method blocks until all samples have been taken.
+        ///
+        /// If both levels occur equally often, the pin is read once more to break
+        /// the tie. If `samples` is set to `0`, the pin is read only once.
+        pub fn read_debounced(&self, samples: u32, interval: Duration) -> Level {
+            let mut high = 0;
+
+            for sample in 0..samples {
+                if sample > 0 && interval > Duration::from_secs(0) {
+                    std::thread::sleep(interval);
+                }
+
+                if self.pin.read() == Level::High {
+                    high += 1;
+                }
+            }
+
+            let low = samples - high;
+            if high > low {
+                Level::High
+            } else if low > high {
+                Level::Low
+            } else {
+                self.pin.read()
+            }
+        }
     };
 }
 
@@ -56,7 +102,15 @@ macro_rules! impl_output {
 
         /// Sets the pin's output state to [`Low`].
         ///
+        /// `set_low`, [`set_high`] and [`write`] perform a single volatile write to the
+        /// GPIO output set or clear register. They don't allocate, acquire any locks or
+        /// make any system calls, which makes them suitable for bit-banging. The maximum
+        /// toggle rate depends on the Raspberry Pi model, and can be measured with the
+        /// `gpio_write` benchmark.
+        ///
         /// [`Low`]: enum.Level.html#variant.Low
+        /// [`set_high`]: #method.set_high
+        /// [`write`]: #method.write
         #[inline]
         pub fn set_low(&mut self) {
             self.pin.set_low()
@@ -72,15 +126,52 @@ macro_rules! impl_output {
 
         /// Toggles the pin's output state between [`Low`] and [`High`].
         ///
+        /// If the output state was last set through this instance, `toggle` sets the
+        /// opposite state through the `GPSET` or `GPCLR` register, without reading the
+        /// pin's current logic level.
+        ///
+        /// Otherwise, on the RP1 (Raspberry Pi 5), `toggle` flips the output state with a
+        /// single write to the atomic XOR alias of the `SYS_RIO` output register. The
+        /// BCM283x and BCM2711 don't support atomic toggling, so `toggle` reads the pin's
+        /// current logic level, and sets the opposite output state. In all cases, only
+        /// the selected pin is affected.
+        ///
         /// [`Low`]: enum.Level.html#variant.Low
         /// [`High`]: enum.Level.html#variant.High
         #[inline]
         pub fn toggle(&mut self) {
-            if self.pin.read() == Level::Low {
-                self.set_high();
-            } else {
-                self.set_low();
-            }
+            self.pin.toggle()
+        }
+
+        /// Sets the pin's output state to `level` for the specified `duration`, and then
+        /// restores the previous output state.
+        ///
+        /// `pulse` is intended for short trigger and reset pulses, such as the 10 µs
+        /// trigger pulse used by HC-SR04 ultrasonic sensors. Instead of sleeping, `pulse`
+        /// busy-waits the same way as [`delay_us`], which keeps the current thread
+        /// occupied, but is considerably more accurate than `thread::sleep` for durations
+        /// in the microsecond range. The calling thread can still be preempted, which may
+        /// extend the pulse.
+        ///
+        /// If the output is already set to `level`, the pin remains at `level` for the
+        /// entire duration. Any active software-based PWM signal or pattern should be
+        /// stopped first.
+        ///
+        /// [`delay_us`]: fn.delay_us.html
+        pub fn pulse(&mut self, level: Level, duration: Duration) {
+            let previous = self.pin.output_level.unwrap_or_else(|| self.pin.read());
+
+            // Calibrate before the start time is sampled, so the first pulse isn't
+            // extended by the calibration
+            soft_pwm::time_overhead_ns();
+
+            self.pin.write(level);
+
+            let target_ns = soft_pwm::get_time_ns()
+                .saturating_add(duration.as_nanos().min(i64::MAX as u128) as i64);
+            soft_pwm::busy_wait_until_ns(target_ns);
+
+            self.pin.write(previous);
         }
 
         /// Configures a software-based PWM signal.
@@ -94,12 +185,16 @@ macro_rules! impl_output {
         /// scheduling/preemption. If an accurate or faster PWM signal is required, use the
         /// hardware [`Pwm`] peripheral instead. More information can be found [here].
         ///
-        /// If `set_pwm` is called when a PWM thread is already active, the existing thread
-        /// will be reconfigured at the end of the current cycle.
+        /// The PWM signals for all pins are emulated on a single shared thread, which is
+        /// started when the first signal is configured. If `set_pwm` is called when a PWM
+        /// signal is already active on the pin, the signal will be reconfigured at the end
+        /// of the current cycle.
         ///
         /// [`Pwm`]: ../pwm/struct.Pwm.html
         /// [here]: index.html#software-based-pwm
         pub fn set_pwm(&mut self, period: Duration, pulse_width: Duration) -> Result<()> {
+            self.stop_pattern()?;
+
             if let Some(ref mut soft_pwm) = self.soft_pwm {
                 soft_pwm.reconfigure(period, pulse_width);
             } else {
@@ -159,17 +254,104 @@ macro_rules! impl_output {
             )
         }
 
+        /// Returns timing statistics for the active software-based PWM signal, or `None`
+        /// if no PWM signal is configured.
+        ///
+        /// The PWM thread measures the duration of each cycle, and keeps track of the
+        /// minimum, maximum and mean difference from the configured period. The
+        /// statistics are reset when the PWM signal is reconfigured with [`set_pwm`].
+        /// Use this to decide whether the timing accuracy of software-based PWM is
+        /// acceptable for your application.
+        ///
+        /// [`set_pwm`]: #method.set_pwm
+        pub fn pwm_jitter_stats(&self) -> Option<JitterStats> {
+            self.soft_pwm
+                .as_ref()
+                .filter(|soft_pwm| !soft_pwm.is_pattern())
+                .map(|soft_pwm| soft_pwm.jitter_stats())
+        }
+
         /// Stops a previously configured software-based PWM signal.
         ///
-        /// The thread responsible for emulating the PWM signal is stopped at the end
-        /// of the current cycle.
+        /// The pin is removed from the shared PWM thread, and its output state is set
+        /// to [`Low`]. An active pattern is stopped instead, which leaves the pin at the
+        /// level of the current step.
+        ///
+        /// [`Low`]: enum.Level.html#variant.Low
         pub fn clear_pwm(&mut self) -> Result<()> {
             if let Some(mut soft_pwm) = self.soft_pwm.take() {
+                // The PWM thread may have left the pin in either state
+                self.pin.output_level = None;
                 soft_pwm.stop()?;
             }
 
             Ok(())
         }
+
+        /// Plays a sequence of output levels on a separate thread.
+        ///
+        /// Each step in `steps` sets the output to the specified [`Level`], and holds it
+        /// for the specified duration. Steps are scheduled relative to the start of the
+        /// pattern, so small delays don't add up over time. If `repeat` is set to `true`,
+        /// the pattern is repeated until [`stop_pattern`] is called. Otherwise, the output
+        /// is left at the level of the last step.
+        ///
+        /// The pattern is timed using the same method as software-based PWM, and is subject
+        /// to the same timing inaccuracies. More information can be found [here].
+        ///
+        /// Any active software-based PWM signal or pattern is stopped first. Calling
+        /// [`set_pwm`] stops the pattern.
+        ///
+        /// If `steps` is empty, or `repeat` is set to `true` and the total duration of
+        /// the steps is zero, `play_pattern` returns `Err(`[`Error::Io`]`)` with an
+        /// `io::ErrorKind::InvalidInput` error.
+        ///
+        /// [`Level`]: enum.Level.html
+        /// [`stop_pattern`]: #method.stop_pattern
+        /// [`set_pwm`]: #method.set_pwm
+        /// [`Error::Io`]: enum.Error.html#variant.Io
+        /// [here]: index.html#software-based-pwm
+        pub fn play_pattern(&mut self, steps: &[(Level, Duration)], repeat: bool) -> Result<()> {
+            if steps.is_empty()
+                || (repeat
+                    && steps
+                        .iter()
+                        .all(|&(_, duration)| duration == Duration::default()))
+            {
+                return Err(Error::Io(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "pattern must contain at least one step with a non-zero duration",
+                )));
+            }
+
+            self.clear_pwm()?;
+
+            self.soft_pwm = Some(SoftPwm::with_pattern(
+                self.pin.pin,
+                self.pin.gpio_state.clone(),
+                steps.to_vec(),
+                repeat,
+            ));
+
+            Ok(())
+        }
+
+        /// Stops a pattern started with [`play_pattern`].
+        ///
+        /// The output is left at the level of the current step.
+        ///
+        /// [`play_pattern`]: #method.play_pattern
+        pub fn stop_pattern(&mut self) -> Result<()> {
+            if self
+                .soft_pwm
+                .as_ref()
+                .map_or(false, |soft_pwm| soft_pwm.is_pattern())
+            {
+                self.clear_pwm()?;
+            }
+
+            Ok(())
+        }
     };
 }
 
@@ -197,24 +379,23 @@ macro_rules! impl_reset_on_drop {
     };
 }
 
-macro_rules! impl_drop {
-    ($struct:ident) => {
-        impl Drop for $struct {
-            /// Resets the pin's mode and disables the built-in pull-up/pull-down
-            /// resistors if `reset_on_drop` is set to `true` (default).
-            fn drop(&mut self) {
-                if !self.reset_on_drop {
-                    return;
-                }
-
-                if let Some(prev_mode) = self.prev_mode {
-                    self.pin.set_mode(prev_mode);
-                }
-
-                if self.bias != Bias::Off {
-                    self.pin.set_bias(Bias::Off);
-                }
-            }
+macro_rules! impl_release {
+    () => {
+        /// Consumes the pin, and returns the underlying [`Pin`].
+        ///
+        /// `release` resets the pin the same way as when it goes out of scope, based on
+        /// the value of `reset_on_drop`. The returned [`Pin`] keeps ownership of the
+        /// GPIO pin, and can be converted again, or dropped to make the pin available
+        /// through [`Gpio::get`].
+        ///
+        /// [`Pin`]: struct.Pin.html
+        /// [`Gpio::get`]: struct.Gpio.html#method.get
+        pub fn release(mut self) -> Pin {
+            self.reset();
+            // The pin has already been reset, so make sure dropping self doesn't do it again
+            self.reset_on_drop = false;
+
+            self.pin.take()
         }
     };
 }
@@ -262,13 +443,35 @@ macro_rules! impl_eq {
 #[derive(Debug)]
 pub struct Pin {
     pub(crate) pin: u8,
-    gpio_state: Arc<GpioState>,
+    pub(crate) gpio_state: Arc<GpioState>,
+    // Last output state written through this instance, or None if unknown
+    output_level: Option<Level>,
+    // Cleared when ownership of the pin has been moved to another instance by take()
+    owned: bool,
 }
 
 impl Pin {
     #[inline]
     pub(crate) fn new(pin: u8, gpio_state: Arc<GpioState>) -> Pin {
-        Pin { pin, gpio_state }
+        Pin {
+            pin,
+            gpio_state,
+            output_level: None,
+            owned: true,
+        }
+    }
+
+    // Moves ownership of the pin to a new instance. The pin stays taken, so it can't
+    // be retrieved through Gpio::get in the meantime.
+    fn take(&mut self) -> Pin {
+        self.owned = false;
+
+        Pin {
+            pin: self.pin,
+            gpio_state: self.gpio_state.clone(),
+            output_level: self.output_level,
+            owned: true,
+        }
     }
 
     /// Returns the GPIO pin number.
@@ -291,6 +494,15 @@ impl Pin {
         self.gpio_state.gpio_mem.level(self.pin)
     }
 
+    /// Returns the pin's logic level voltage.
+    ///
+    /// All GPIO pins use 3.3 V logic levels, and aren't 5 V tolerant. Connecting
+    /// a 5 V signal directly to a GPIO pin may permanently damage the SoC.
+    #[inline]
+    pub fn voltage(&self) -> f32 {
+        system::GPIO_VOLTAGE
+    }
+
     /// Consumes the `Pin` and returns an [`InputPin`]. Sets the mode to [`Input`]
     /// and disables the pin's built-in pull-up/pull-down resistors.
     ///
@@ -329,8 +541,25 @@ impl Pin {
         InputPin::new(self, Bias::PullUp)
     }
 
+    /// Consumes the `Pin` and returns an [`InputPinBuilder`], which can be used to
+    /// configure all [`InputPin`] settings at once.
+    ///
+    /// [`InputPinBuilder`]: struct.InputPinBuilder.html
+    /// [`InputPin`]: struct.InputPin.html
+    #[inline]
+    pub fn input_builder(self) -> InputPinBuilder {
+        InputPinBuilder::new(self)
+    }
+
     /// Consumes the `Pin` and returns an [`OutputPin`]. Sets the mode to [`Mode::Output`]
     /// and leaves the logic level unchanged.
+    ///
+    /// The pin immediately starts driving the level that was last written to its output
+    /// latch, which may be left over from a previous process. If the pin's initial state
+    /// matters, use [`into_output_low`] or [`into_output_high`] instead.
+    ///
+    /// [`into_output_low`]: #method.into_output_low
+    /// [`into_output_high`]: #method.into_output_high
     #[inline]
     pub fn into_output(self) -> OutputPin {
         OutputPin::new(self)
@@ -338,6 +567,10 @@ impl Pin {
 
     /// Consumes the `Pin` and returns an [`OutputPin`]. Changes the logic level to
     /// [`Level::Low`] and then sets the mode to [`Mode::Output`].
+    ///
+    /// The output latch is updated before the mode is changed, so once the pin switches
+    /// to output mode, it never drives any other level, even briefly. This avoids glitches on
+    /// connected devices such as relays.
     #[inline]
     pub fn into_output_low(mut self) -> OutputPin {
         self.set_low();
@@ -347,6 +580,10 @@ impl Pin {
 
     /// Consumes the `Pin` and returns an [`OutputPin`]. Changes the logic level to
     /// [`Level::High`] and then sets the mode to [`Mode::Output`].
+    ///
+    /// The output latch is updated before the mode is changed, so once the pin switches
+    /// to output mode, it never drives any other level, even briefly. This avoids glitches on
+    /// connected devices such as relays.
     #[inline]
     pub fn into_output_high(mut self) -> OutputPin {
         self.set_high();
@@ -363,24 +600,131 @@ impl Pin {
         IoPin::new(self, mode)
     }
 
+    /// Consumes the `Pin` and returns an [`AltPin`]. Sets the mode to the specified
+    /// alternate function.
+    ///
+    /// `into_alt` routes the pin to one of its peripheral functions, for peripherals
+    /// that aren't managed by a kernel driver. [`Alt0`] through [`Alt5`] are supported
+    /// on all SoCs. [`Alt6`] through [`Alt8`] are only available on the RP1
+    /// (Raspberry Pi 5).
+    ///
+    /// If `mode` isn't an alternate function supported by the SoC, `into_alt` returns
+    /// `Err(`[`Error::Io`]`)` with an `io::ErrorKind::InvalidInput` error.
+    ///
+    /// [`AltPin`]: struct.AltPin.html
+    /// [`Alt0`]: enum.Mode.html#variant.Alt0
+    /// [`Alt5`]: enum.Mode.html#variant.Alt5
+    /// [`Alt6`]: enum.Mode.html#variant.Alt6
+    /// [`Alt8`]: enum.Mode.html#variant.Alt8
+    /// [`Error::Io`]: enum.Error.html#variant.Io
+    pub fn into_alt(self, mode: Mode) -> Result<AltPin> {
+        let supported = match mode {
+            Mode::Alt0 | Mode::Alt1 | Mode::Alt2 | Mode::Alt3 | Mode::Alt4 | Mode::Alt5 => true,
+            Mode::Alt6 | Mode::Alt7 | Mode::Alt8 => {
+                system::DeviceInfo::new()?.gpio_interface() == system::GpioInterface::Rp1
+            }
+            _ => false,
+        };
+
+        if !supported {
+            return Err(Error::Io(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{} isn't a supported alternate function", mode),
+            )));
+        }
+
+        Ok(AltPin::new(self, mode))
+    }
+
+    // Returns the reset_on_drop value configured through Gpio::set_reset_on_drop
+    #[inline]
+    pub(crate) fn default_reset_on_drop(&self) -> bool {
+        self.gpio_state.reset_on_drop.load(Ordering::SeqCst)
+    }
+
+    #[inline]
+    fn is_dry_run(&self) -> bool {
+        self.gpio_state.dry_run.load(Ordering::Relaxed)
+    }
+
     #[inline]
     pub(crate) fn set_mode(&mut self, mode: Mode) {
-        self.gpio_state.gpio_mem.set_mode(self.pin, mode);
+        trace!("GPIO {}: set mode to {}", self.pin, mode);
+
+        if !self.is_dry_run() {
+            self.gpio_state.gpio_mem.set_mode(self.pin, mode);
+        }
     }
 
     #[inline]
     pub(crate) fn set_bias(&mut self, bias: Bias) {
-        self.gpio_state.gpio_mem.set_bias(self.pin, bias);
+        trace!("GPIO {}: set bias to {}", self.pin, bias);
+
+        if !self.is_dry_run() {
+            self.gpio_state.gpio_mem.set_bias(self.pin, bias);
+        }
+    }
+
+    pub(crate) fn set_hysteresis(&mut self, enabled: bool) -> Result<()> {
+        trace!("GPIO {}: set hysteresis to {}", self.pin, enabled);
+
+        if !self.is_dry_run() && !self.gpio_state.gpio_mem.set_hysteresis(self.pin, enabled) {
+            return Err(Error::Io(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "hysteresis control isn't supported on this model",
+            )));
+        }
+
+        Ok(())
+    }
+
+    #[inline]
+    pub(crate) fn toggle(&mut self) {
+        // Derive the new output state from the tracked level if we have one, so the
+        // pin can't end up in a different state than the one we're tracking
+        match self.output_level {
+            Some(Level::Low) => self.set_high(),
+            Some(Level::High) => self.set_low(),
+            None => {
+                trace!("GPIO {}: toggle", self.pin);
+
+                if !self.is_dry_run() {
+                    self.gpio_state.gpio_mem.toggle(self.pin);
+                }
+            }
+        }
+    }
+
+    #[inline]
+    pub(crate) fn set_edge_detect(&mut self, trigger: Trigger) {
+        self.gpio_state.gpio_mem.set_edge_detect(self.pin, trigger);
+    }
+
+    #[inline]
+    pub(crate) fn take_edge_event(&mut self, trigger: Trigger) -> bool {
+        self.gpio_state.gpio_mem.take_edge_event(self.pin, trigger)
     }
 
     #[inline]
     pub(crate) fn set_low(&mut self) {
-        self.gpio_state.gpio_mem.set_low(self.pin);
+        trace!("GPIO {}: set low", self.pin);
+
+        if !self.is_dry_run() {
+            self.gpio_state.gpio_mem.set_low(self.pin);
+        }
+
+        self.output_level = Some(Level::Low);
     }
 
     #[inline]
     pub(crate) fn set_high(&mut self) {
-        self.gpio_state.gpio_mem.set_high(self.pin);
+        trace!("GPIO {}: set high", self.pin);
+
+        if !self.is_dry_run() {
+            self.gpio_state.gpio_mem.set_high(self.pin);
+        }
+
+        self.output_level = Some(Level::High);
     }
 
     #[inline]
@@ -394,8 +738,10 @@ impl Pin {
 
 impl Drop for Pin {
     fn drop(&mut self) {
-        // Release taken pin
-        self.gpio_state.pins_taken[self.pin as usize].store(false, Ordering::SeqCst);
+        // Release taken pin, unless ownership was moved to another instance
+        if self.owned {
+            self.gpio_state.pins_taken[self.pin as usize].store(false, Ordering::SeqCst);
+        }
     }
 }
 
@@ -423,6 +769,9 @@ pub struct InputPin {
     pub(crate) pin: Pin,
     prev_mode: Option<Mode>,
     async_interrupt: Option<AsyncInterrupt>,
+    debounce: Option<Duration>,
+    event_buffer_size: u32,
+    edge_detect: Trigger,
     reset_on_drop: bool,
     bias: Bias,
 }
@@ -440,11 +789,16 @@ impl InputPin {
 
         pin.set_bias(bias);
 
+        let reset_on_drop = pin.default_reset_on_drop();
+
         InputPin {
             pin,
             prev_mode,
             async_interrupt: None,
-            reset_on_drop: true,
+            debounce: None,
+            event_buffer_size: 0,
+            edge_detect: Trigger::Disabled,
+            reset_on_drop,
             bias,
         }
     }
@@ -452,6 +806,48 @@ impl InputPin {
     impl_pin!();
     impl_input!();
 
+    /// Configures the GPIO peripheral's hardware edge detection.
+    ///
+    /// Unlike interrupts, hardware edge detection doesn't involve the kernel. Detected
+    /// edges are latched by the GPIO peripheral, and can be checked with
+    /// [`take_edge_event`] without any system calls, which makes this a low-latency
+    /// alternative for polling in tight control loops. Any pending edge events are
+    /// cleared.
+    ///
+    /// Setting `trigger` to [`Trigger::Disabled`] disables edge detection. Edge
+    /// detection is automatically disabled when `InputPin` goes out of scope.
+    ///
+    /// ## Note
+    ///
+    /// On the BCM283x and BCM2711, an enabled edge detection also signals the GPIO
+    /// interrupt handled by the kernel. Don't use hardware edge detection and
+    /// (a)synchronous interrupts on the same pin, and disable edge detection when
+    /// you no longer need it.
+    ///
+    /// [`take_edge_event`]: #method.take_edge_event
+    /// [`Trigger::Disabled`]: enum.Trigger.html#variant.Disabled
+    pub fn set_edge_detect(&mut self, trigger: Trigger) {
+        self.pin.set_edge_detect(trigger);
+        self.edge_detect = trigger;
+    }
+
+    /// Returns `true` if an edge was detected since the last call to `take_edge_event`,
+    /// and clears the pending edge event.
+    ///
+    /// Multiple edges that occur between two calls are reported as a single event.
+    /// If hardware edge detection hasn't been configured with [`set_edge_detect`],
+    /// `take_edge_event` always returns `false`.
+    ///
+    /// [`set_edge_detect`]: #method.set_edge_detect
+    #[inline]
+    pub fn take_edge_event(&mut self) -> bool {
+        if self.edge_detect == Trigger::Disabled {
+            return false;
+        }
+
+        self.pin.take_edge_event(self.edge_detect)
+    }
+
     /// Configures a synchronous interrupt trigger.
     ///
     /// After configuring a synchronous interrupt trigger, call [`poll_interrupt`] or
@@ -465,7 +861,36 @@ impl InputPin {
         self.clear_async_interrupt()?;
 
         // Each pin can only be configured for a single trigger type
-        (*self.pin.gpio_state.sync_interrupts.lock().unwrap()).set_interrupt(self.pin(), trigger)
+        (*self.pin.gpio_state.sync_interrupts.lock().unwrap()).set_interrupt(
+            self.pin(),
+            trigger,
+            self.line_settings(),
+        )
+    }
+
+    /// Changes the trigger condition of a previously configured (a)synchronous interrupt.
+    ///
+    /// Unlike [`set_interrupt`] and [`set_async_interrupt`], `update_trigger` keeps
+    /// the existing interrupt configuration, including any asynchronous callback.
+    ///
+    /// On Linux kernel 5.10 and newer, the line request is reconfigured in place through
+    /// the GPIO character device v2 uAPI, and any pending trigger events are kept. Older
+    /// kernels don't support changing the trigger condition of an active line event
+    /// request, so the request is reopened with the new trigger condition, and any
+    /// pending trigger events are discarded. For asynchronous interrupts, the poll
+    /// thread is then restarted using the same callback. If the new trigger condition
+    /// can't be applied, the previous trigger condition remains active.
+    ///
+    /// If the pin hasn't been configured for interrupts, `update_trigger` has no effect.
+    ///
+    /// [`set_interrupt`]: #method.set_interrupt
+    /// [`set_async_interrupt`]: #method.set_async_interrupt
+    pub fn update_trigger(&mut self, trigger: Trigger) -> Result<()> {
+        if let Some(ref mut interrupt) = self.async_interrupt {
+            return interrupt.set_trigger(trigger);
+        }
+
+        (*self.pin.gpio_state.sync_interrupts.lock().unwrap()).set_trigger(self.pin(), trigger)
     }
 
     /// Removes a previously configured synchronous interrupt trigger.
@@ -490,9 +915,14 @@ impl InputPin {
     /// for interrupt trigger events, after which an `Ok(None))` is returned.
     /// `timeout` can be set to `None` to wait indefinitely.
     ///
+    /// The returned [`Level`] is based on the type of edge reported by the kernel, rather
+    /// than on reading the pin after the event was received, so it indicates the pin's
+    /// logic level at the time of the edge, even if the level has changed again since.
+    ///
     /// [`set_interrupt`]: #method.set_interrupt
     /// [`Gpio::poll_interrupts`]: struct.Gpio.html#method.poll_interrupts
     /// [`set_async_interrupt`]: #method.set_async_interrupt
+    /// [`Level`]: enum.Level.html
     pub fn poll_interrupt(
         &mut self,
         reset: bool,
@@ -508,6 +938,391 @@ impl InputPin {
         }
     }
 
+    /// Returns the raw file descriptor for the line event request of a synchronous
+    /// interrupt trigger configured with [`set_interrupt`].
+    ///
+    /// The file descriptor becomes readable when an interrupt trigger event occurs,
+    /// which allows it to be registered with an external event loop based on
+    /// `epoll` or `poll`. Returns `None` if no synchronous interrupt trigger is configured.
+    ///
+    /// The file descriptor remains owned by `InputPin`. It shouldn't be closed, and
+    /// shouldn't be used after the interrupt trigger is cleared or changed. Calling
+    /// [`poll_interrupt`] or [`Gpio::poll_interrupts`] with `reset` set to `true` replaces
+    /// the file descriptor.
+    ///
+    /// [`set_interrupt`]: #method.set_interrupt
+    /// [`poll_interrupt`]: #method.poll_interrupt
+    /// [`Gpio::poll_interrupts`]: struct.Gpio.html#method.poll_interrupts
+    pub fn interrupt_fd(&self) -> Option<RawFd> {
+        (*self.pin.gpio_state.sync_interrupts.lock().unwrap()).interrupt_fd(self.pin())
+    }
+
+    /// Returns the debounce period for (a)synchronous interrupts, or `None` if
+    /// debouncing is disabled.
+    pub fn debounce(&self) -> Option<Duration> {
+        self.debounce
+    }
+
+    /// Configures the debounce period for (a)synchronous interrupts.
+    ///
+    /// When debouncing is enabled, the kernel filters out any level changes that don't
+    /// remain stable for at least the specified period, before they trigger an interrupt.
+    /// Depending on the GPIO driver, debouncing is handled by the GPIO peripheral's
+    /// hardware filter, or emulated in software by the kernel. Setting `debounce`
+    /// to `Duration::ZERO` disables debouncing.
+    ///
+    /// The debounce period applies to any currently configured interrupt trigger, as well
+    /// as to interrupt triggers configured afterwards. The line request of an existing
+    /// interrupt is reconfigured in place, the same way as [`update_trigger`].
+    ///
+    /// ## Note
+    ///
+    /// Debouncing requires the GPIO character device v2 uAPI, which is available on
+    /// Linux kernel 5.10 and newer. On older kernels, interrupts are configured without
+    /// debouncing. Use [`read_debounced`] to debounce the input level in software instead.
+    ///
+    /// [`read_debounced`]: #method.read_debounced
+    /// [`update_trigger`]: #method.update_trigger
+    pub fn set_debounce(&mut self, debounce: Duration) -> Result<()> {
+        let debounce = if debounce.as_micros() > 0 {
+            Some(debounce)
+        } else {
+            None
+        };
+
+        self.debounce = debounce;
+
+        self.apply_line_settings()
+    }
+
+    /// Returns the number of interrupt trigger events the kernel queues for this pin, or
+    /// `0` if the kernel's default buffer size is used.
+    pub fn event_buffer_size(&self) -> u32 {
+        self.event_buffer_size
+    }
+
+    /// Configures the number of interrupt trigger events the kernel queues for this pin.
+    ///
+    /// Trigger events are queued by the kernel until they're read by [`poll_interrupt`],
+    /// [`Gpio::poll_interrupts`] or the asynchronous interrupt thread. When the queue is
+    /// full, the oldest event is discarded. Bursts of edges that occur faster than your
+    /// application can process them may require a larger buffer. Dropped events can
+    /// be detected with [`dropped_event_count`].
+    ///
+    /// Setting `size` to `0` selects the kernel's default of 16 events. The kernel limits
+    /// the buffer size to 1024 events.
+    ///
+    /// The buffer size applies to any currently configured interrupt trigger, as well
+    /// as to interrupt triggers configured afterwards. Changing the buffer size of an
+    /// existing interrupt discards any pending trigger events.
+    ///
+    /// ## Note
+    ///
+    /// Configuring the buffer size requires the GPIO character device v2 uAPI, which is
+    /// available on Linux kernel 5.10 and newer. On older kernels, the buffer size is ignored.
+    ///
+    /// [`poll_interrupt`]: #method.poll_interrupt
+    /// [`Gpio::poll_interrupts`]: struct.Gpio.html#method.poll_interrupts
+    /// [`dropped_event_count`]: #method.dropped_event_count
+    pub fn set_event_buffer_size(&mut self, size: u32) -> Result<()> {
+        self.event_buffer_size = size;
+
+        self.apply_line_settings()
+    }
+
+    /// Returns the number of interrupt trigger events that were dropped because the
+    /// kernel's event buffer was full.
+    ///
+    /// Dropped events are detected through gaps in the sequence numbers of the events
+    /// read from the kernel. The count includes all events dropped since the current
+    /// (a)synchronous interrupt trigger was configured, and is reset when the interrupt
+    /// is cleared. Changing the trigger condition or line settings of an existing interrupt
+    /// doesn't reset the count. Returns `0` if no interrupt is configured.
+    ///
+    /// If the `log` feature is enabled, a warning is logged whenever dropped events
+    /// are detected. Use [`set_event_buffer_size`] to increase the buffer size.
+    ///
+    /// ## Note
+    ///
+    /// Sequence numbers require the GPIO character device v2 uAPI, which is available on
+    /// Linux kernel 5.10 and newer. On older kernels, `dropped_event_count` always
+    /// returns `0`.
+    ///
+    /// [`set_event_buffer_size`]: #method.set_event_buffer_size
+    pub fn dropped_event_count(&self) -> u64 {
+        if let Some(ref async_interrupt) = self.async_interrupt {
+            return async_interrupt.dropped_events();
+        }
+
+        (*self.pin.gpio_state.sync_interrupts.lock().unwrap())
+            .dropped_events(self.pin())
+            .unwrap_or(0)
+    }
+
+    /// Configures the built-in pull-up/pull-down resistors.
+    ///
+    /// `set_bias` changes the GPIO peripheral's bias settings directly. When an
+    /// (a)synchronous interrupt is configured, the bias is also applied to the
+    /// interrupt's line request through the GPIO character device, which lets the
+    /// kernel's GPIO driver handle any SoC-specific details. The bias is automatically
+    /// included in any interrupt triggers configured afterwards.
+    ///
+    /// The line request of an existing interrupt is reconfigured in place, the same way
+    /// as [`update_trigger`].
+    ///
+    /// [`update_trigger`]: #method.update_trigger
+    pub fn set_bias(&mut self, bias: Bias) -> Result<()> {
+        self.pin.set_bias(bias);
+        self.bias = bias;
+
+        self.apply_line_settings()
+    }
+
+    /// Enables or disables the Schmitt trigger (hysteresis) on the pin's input.
+    ///
+    /// With hysteresis enabled, the input switches at a higher voltage on a rising edge
+    /// than on a falling edge, which prevents slowly changing or noisy signals from
+    /// causing multiple level changes near the threshold.
+    ///
+    /// Hysteresis is enabled by default on all models, but can only be configured per pin
+    /// on the Raspberry Pi 5, Raspberry Pi 500 and Compute Module 5, where the GPIO pins
+    /// are controlled by the RP1. On earlier models, the BCM283x and BCM2711 pad control
+    /// registers configure hysteresis for a group of up to 28 pins at once. Since changing
+    /// the setting for a single pin would also affect unrelated pins, `set_hysteresis`
+    /// deliberately doesn't support those models, and returns `Err(`[`Error::Io`]`)` with
+    /// an `io::ErrorKind::Unsupported` error.
+    ///
+    /// [`Error::Io`]: enum.Error.html#variant.Io
+    pub fn set_hysteresis(&mut self, enabled: bool) -> Result<()> {
+        self.pin.set_hysteresis(enabled)
+    }
+
+    fn line_settings(&self) -> LineSettings {
+        LineSettings {
+            bias: self.bias,
+            debounce: self.debounce,
+            event_buffer_size: self.event_buffer_size,
+        }
+    }
+
+    // Requests the line for reading timestamped edges on the current thread
+    pub(crate) fn edge_reader(&self, trigger: Trigger) -> Result<EdgeReader> {
+        EdgeReader::new(
+            self.pin.gpio_state.cdev.as_raw_fd(),
+            self.pin(),
+            trigger,
+            self.line_settings(),
+        )
+    }
+
+    // Updates the line request of any configured (a)synchronous interrupt
+    fn apply_line_settings(&mut self) -> Result<()> {
+        let settings = self.line_settings();
+
+        if let Some(ref mut interrupt) = self.async_interrupt {
+            return interrupt.set_settings(settings);
+        }
+
+        (*self.pin.gpio_state.sync_interrupts.lock().unwrap()).set_settings(self.pin(), settings)
+    }
+
+    /// Returns the trigger condition of the currently configured (a)synchronous
+    /// interrupt, or `None` if no interrupt is configured.
+    pub fn interrupt_trigger(&self) -> Option<Trigger> {
+        if let Some(ref async_interrupt) = self.async_interrupt {
+            return Some(async_interrupt.trigger());
+        }
+
+        (*self.pin.gpio_state.sync_interrupts.lock().unwrap()).trigger(self.pin())
+    }
+
+    /// Measures how long the pin stays at the specified logic level.
+    ///
+    /// `measure_pulse` waits for the pin to change to `level`, and then for it to change
+    /// back again. The returned pulse width is calculated from the kernel timestamps of
+    /// both interrupt events, which makes it independent of any scheduling delays in
+    /// your application.
+    ///
+    /// The `timeout` duration indicates how long the call will block while waiting
+    /// for the complete pulse, after which an `Ok(None)` is returned. `timeout` can be
+    /// set to `None` to wait indefinitely.
+    ///
+    /// The pin can't be configured for (a)synchronous interrupts while a measurement takes
+    /// place. Clear any existing interrupt triggers before calling `measure_pulse`.
+    ///
+    /// ## Note
+    ///
+    /// The timestamps are recorded when the kernel handles the interrupt, so interrupt
+    /// latency introduces jitter of typically a few to tens of microseconds. Pulses
+    /// shorter than the interrupt latency may be reported with a reduced width, or
+    /// missed entirely.
+    pub fn measure_pulse(
+        &mut self,
+        level: Level,
+        timeout: Option<Duration>,
+    ) -> Result<Option<Duration>> {
+        // A timeout too large to be represented is treated the same as no timeout
+        let deadline = timeout.and_then(|timeout| Instant::now().checked_add(timeout));
+        let mut reader = self.edge_reader(Trigger::Both)?;
+
+        let mut start = None;
+        while let Some((event_level, timestamp)) = reader.next(deadline)? {
+            if event_level == level {
+                start = Some(timestamp);
+            } else if let Some(start) = start {
+                return Ok(Some(timestamp.saturating_sub(start)));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Measures the frequency of the signal on the pin in hertz (Hz).
+    ///
+    /// `measure_frequency` records the kernel timestamps of `samples + 1` consecutive
+    /// rising edges, and returns the frequency based on the average period.
+    ///
+    /// The `timeout` duration indicates how long the call will block while waiting
+    /// for all rising edges, after which an `Ok(None)` is returned. `timeout` can be
+    /// set to `None` to wait indefinitely. If `samples` is set to `0`, `Ok(None)` is returned.
+    ///
+    /// The pin can't be configured for (a)synchronous interrupts while a measurement takes
+    /// place. Clear any existing interrupt triggers before calling `measure_frequency`.
+    ///
+    /// More information on the achievable resolution can be found at [`measure_pulse`].
+    ///
+    /// [`measure_pulse`]: #method.measure_pulse
+    pub fn measure_frequency(
+        &mut self,
+        samples: u32,
+        timeout: Option<Duration>,
+    ) -> Result<Option<f64>> {
+        if samples == 0 {
+            return Ok(None);
+        }
+
+        // A timeout too large to be represented is treated the same as no timeout
+        let deadline = timeout.and_then(|timeout| Instant::now().checked_add(timeout));
+        let mut reader = self.edge_reader(Trigger::RisingEdge)?;
+
+        let first = match reader.next(deadline)? {
+            Some((_, timestamp)) => timestamp,
+            None => return Ok(None),
+        };
+
+        let mut last = first;
+        for _ in 0..samples {
+            match reader.next(deadline)? {
+                Some((_, timestamp)) => last = timestamp,
+                None => return Ok(None),
+            }
+        }
+
+        let period = last.saturating_sub(first).as_secs_f64() / f64::from(samples);
+        if period > 0.0 {
+            Ok(Some(1.0 / period))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Measures the frequency of the signal on the pin in hertz (Hz) by counting
+    /// rising edges during the specified time window.
+    ///
+    /// Unlike [`measure_frequency`], which waits for a fixed number of rising edges,
+    /// `measure_frequency_window` blocks for the duration of `window`, and counts
+    /// all rising edges that occur in the meantime. The frequency is calculated from
+    /// the number of periods between the first and last rising edge, divided by the
+    /// time between their kernel timestamps, rather than by `window`. This keeps the
+    /// measurement accurate when only a few edges occur, such as for a tachometer at
+    /// low speeds.
+    ///
+    /// If fewer than two rising edges occur during `window`, `Ok(0.0)` is returned.
+    ///
+    /// The pin can't be configured for (a)synchronous interrupts while a measurement takes
+    /// place. Clear any existing interrupt triggers before calling `measure_frequency_window`.
+    ///
+    /// [`measure_frequency`]: #method.measure_frequency
+    pub fn measure_frequency_window(&mut self, window: Duration) -> Result<f64> {
+        let deadline = Instant::now().checked_add(window);
+        let mut reader = self.edge_reader(Trigger::RisingEdge)?;
+
+        let first = match reader.next(deadline)? {
+            Some((_, timestamp)) => timestamp,
+            None => return Ok(0.0),
+        };
+
+        let mut last = first;
+        let mut periods: u32 = 0;
+        while let Some((_, timestamp)) = reader.next(deadline)? {
+            last = timestamp;
+            periods += 1;
+        }
+
+        let elapsed = last.saturating_sub(first).as_secs_f64();
+        if periods > 0 && elapsed > 0.0 {
+            Ok(f64::from(periods) / elapsed)
+        } else {
+            Ok(0.0)
+        }
+    }
+
+    /// Blocks until `count` edges matching `trigger` have occurred, or a timeout occurs.
+    ///
+    /// Returns the number of edges that occurred. If the timeout elapses first, the
+    /// returned value is lower than `count`. Edges are counted from the moment
+    /// `wait_for_edges` is called. Any edges that occurred earlier are ignored.
+    ///
+    /// The `timeout` duration indicates how long the call will block while waiting for
+    /// all edges. `timeout` can be set to `None` to wait indefinitely.
+    /// If `count` is set to `0`, `Ok(0)` is returned immediately.
+    ///
+    /// The pin can't be configured for (a)synchronous interrupts while `wait_for_edges`
+    /// is waiting. Clear any existing interrupt triggers before calling `wait_for_edges`.
+    ///
+    /// If `trigger` is set to [`Trigger::Disabled`], `wait_for_edges` returns
+    /// `Err(`[`Error::Io`]`)` with an `io::ErrorKind::InvalidInput` error.
+    ///
+    /// ## Note
+    ///
+    /// The kernel queues a limited number of edges while the calling thread is
+    /// waiting to be scheduled. At high edge rates, queued edges may be dropped,
+    /// which results in a lower count.
+    ///
+    /// [`Trigger::Disabled`]: enum.Trigger.html#variant.Disabled
+    /// [`Error::Io`]: enum.Error.html#variant.Io
+    pub fn wait_for_edges(
+        &mut self,
+        count: u32,
+        trigger: Trigger,
+        timeout: Option<Duration>,
+    ) -> Result<u32> {
+        if trigger == Trigger::Disabled {
+            return Err(Error::Io(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "trigger can't be set to Disabled",
+            )));
+        }
+
+        if count == 0 {
+            return Ok(0);
+        }
+
+        let deadline = timeout.and_then(|timeout| Instant::now().checked_add(timeout));
+        let mut reader = self.edge_reader(trigger)?;
+
+        let mut edges = 0;
+        while edges < count {
+            if reader.next(deadline)?.is_none() {
+                break;
+            }
+
+            edges += 1;
+        }
+
+        Ok(edges)
+    }
+
     /// Configures an asynchronous interrupt trigger, which executes the callback on a
     /// separate thread when the interrupt is triggered.
     ///
@@ -526,15 +1341,51 @@ impl InputPin {
         self.clear_async_interrupt()?;
 
         self.async_interrupt = Some(AsyncInterrupt::new(
-            self.pin.gpio_state.cdev.as_raw_fd(),
+            self.pin.gpio_state.clone(),
             self.pin(),
             trigger,
+            self.line_settings(),
+            *self
+                .pin
+                .gpio_state
+                .interrupt_thread_settings
+                .lock()
+                .unwrap(),
             callback,
         )?);
 
         Ok(())
     }
 
+    /// Configures an asynchronous interrupt trigger for both edges, which executes
+    /// `on_rising` or `on_falling` on a separate thread, depending on which edge
+    /// triggered the interrupt.
+    ///
+    /// `set_async_interrupt_edges` behaves similarly to [`set_async_interrupt`] with
+    /// [`Trigger::Both`]. The edge is determined by the trigger event reported by the
+    /// kernel, rather than by reading the pin's level after the interrupt was triggered,
+    /// so short pulses are still dispatched to the correct callback.
+    ///
+    /// Any previously configured (a)synchronous interrupt triggers for this pin are cleared
+    /// when `set_async_interrupt_edges` is called, or when `InputPin` goes out of scope.
+    ///
+    /// [`set_async_interrupt`]: #method.set_async_interrupt
+    /// [`Trigger::Both`]: enum.Trigger.html#variant.Both
+    pub fn set_async_interrupt_edges<R, F>(
+        &mut self,
+        mut on_rising: R,
+        mut on_falling: F,
+    ) -> Result<()>
+    where
+        R: FnMut() + Send + 'static,
+        F: FnMut() + Send + 'static,
+    {
+        self.set_async_interrupt(Trigger::Both, move |level| match level {
+            Level::High => on_rising(),
+            Level::Low => on_falling(),
+        })
+    }
+
     /// Removes a previously configured asynchronous interrupt trigger.
     pub fn clear_async_interrupt(&mut self) -> Result<()> {
         if let Some(mut interrupt) = self.async_interrupt.take() {
@@ -545,11 +1396,156 @@ impl InputPin {
     }
 
     impl_reset_on_drop!();
+    impl_release!();
+}
+
+impl Drop for InputPin {
+    /// Disables hardware edge detection, resets the pin's mode and disables the
+    /// built-in pull-up/pull-down resistors if `reset_on_drop` is set to `true` (default).
+    fn drop(&mut self) {
+        self.reset();
+    }
 }
 
-impl_drop!(InputPin);
+impl InputPin {
+    fn reset(&mut self) {
+        // Always disable edge detection, since it isn't managed by the kernel
+        if self.edge_detect != Trigger::Disabled {
+            self.pin.set_edge_detect(Trigger::Disabled);
+            self.edge_detect = Trigger::Disabled;
+        }
+
+        if !self.reset_on_drop {
+            return;
+        }
+
+        if let Some(prev_mode) = self.prev_mode {
+            self.pin.set_mode(prev_mode);
+        }
+
+        if self.bias != Bias::Off {
+            self.pin.set_bias(Bias::Off);
+        }
+    }
+}
 impl_eq!(InputPin);
 
+/// Builder for an [`InputPin`].
+///
+/// `InputPinBuilder`s are constructed by converting a [`Pin`] using [`Pin::input_builder`].
+/// The settings are applied in the appropriate order when [`build`] is called.
+///
+/// [`InputPin`]: struct.InputPin.html
+/// [`Pin`]: struct.Pin.html
+/// [`Pin::input_builder`]: struct.Pin.html#method.input_builder
+/// [`build`]: #method.build
+#[derive(Debug)]
+pub struct InputPinBuilder {
+    pin: Pin,
+    bias: Bias,
+    trigger: Option<Trigger>,
+    debounce: Option<Duration>,
+    event_buffer_size: Option<u32>,
+    reset_on_drop: bool,
+}
+
+impl InputPinBuilder {
+    fn new(pin: Pin) -> InputPinBuilder {
+        let reset_on_drop = pin.default_reset_on_drop();
+
+        InputPinBuilder {
+            pin,
+            bias: Bias::Off,
+            trigger: None,
+            debounce: None,
+            event_buffer_size: None,
+            reset_on_drop,
+        }
+    }
+
+    /// Configures the built-in pull-up/pull-down resistors.
+    ///
+    /// By default, `bias` is set to [`Bias::Off`].
+    ///
+    /// [`Bias::Off`]: enum.Bias.html#variant.Off
+    pub fn bias(mut self, bias: Bias) -> InputPinBuilder {
+        self.bias = bias;
+
+        self
+    }
+
+    /// Configures a synchronous interrupt trigger.
+    ///
+    /// More information can be found in the documentation for [`InputPin::set_interrupt`].
+    ///
+    /// [`InputPin::set_interrupt`]: struct.InputPin.html#method.set_interrupt
+    pub fn interrupt(mut self, trigger: Trigger) -> InputPinBuilder {
+        self.trigger = Some(trigger);
+
+        self
+    }
+
+    /// Configures the debounce period for interrupts.
+    ///
+    /// More information can be found in the documentation for [`InputPin::set_debounce`].
+    ///
+    /// [`InputPin::set_debounce`]: struct.InputPin.html#method.set_debounce
+    pub fn debounce(mut self, debounce: Duration) -> InputPinBuilder {
+        self.debounce = Some(debounce);
+
+        self
+    }
+
+    /// Configures the number of interrupt trigger events the kernel queues for the pin.
+    ///
+    /// More information can be found in the documentation for [`InputPin::set_event_buffer_size`].
+    ///
+    /// [`InputPin::set_event_buffer_size`]: struct.InputPin.html#method.set_event_buffer_size
+    pub fn event_buffer_size(mut self, size: u32) -> InputPinBuilder {
+        self.event_buffer_size = Some(size);
+
+        self
+    }
+
+    /// Configures `reset_on_drop`.
+    ///
+    /// More information can be found in the documentation for [`InputPin::set_reset_on_drop`].
+    ///
+    /// [`InputPin::set_reset_on_drop`]: struct.InputPin.html#method.set_reset_on_drop
+    pub fn reset_on_drop(mut self, reset_on_drop: bool) -> InputPinBuilder {
+        self.reset_on_drop = reset_on_drop;
+
+        self
+    }
+
+    /// Consumes the `InputPinBuilder` and returns an [`InputPin`].
+    ///
+    /// `build` sets the mode to [`Input`], and configures the pull-up/pull-down resistors
+    /// before any interrupt trigger is configured, to prevent spurious trigger events
+    /// while the input level settles.
+    ///
+    /// [`InputPin`]: struct.InputPin.html
+    /// [`Input`]: enum.Mode.html#variant.Input
+    pub fn build(self) -> Result<InputPin> {
+        let mut input = InputPin::new(self.pin, self.bias);
+        input.set_reset_on_drop(self.reset_on_drop);
+
+        if let Some(debounce) = self.debounce {
+            input.set_debounce(debounce)?;
+        }
+
+        if let Some(size) = self.event_buffer_size {
+            input.set_event_buffer_size(size)?;
+        }
+
+        if let Some(trigger) = self.trigger {
+            input.set_interrupt(trigger)?;
+        }
+
+        Ok(input)
+    }
+}
+
 /// GPIO pin configured as output.
 ///
 /// `OutputPin`s are constructed by converting a [`Pin`] using [`Pin::into_output`],
@@ -567,9 +1563,10 @@ impl_eq!(InputPin);
 /// [`Pin::into_output_high`]: struct.Pin.html#method.into_output_high
 #[derive(Debug)]
 pub struct OutputPin {
-    pin: Pin,
+    pub(crate) pin: Pin,
     prev_mode: Option<Mode>,
     reset_on_drop: bool,
+    drop_state: Option<(Mode, Level)>,
     bias: Bias,
     pub(crate) soft_pwm: Option<SoftPwm>,
     // Stores the softpwm frequency. Used for embedded_hal::PwmPin.
@@ -599,10 +1596,13 @@ impl OutputPin {
             Some(prev_mode)
         };
 
+        let reset_on_drop = pin.default_reset_on_drop();
+
         OutputPin {
             pin,
             prev_mode,
-            reset_on_drop: true,
+            reset_on_drop,
+            drop_state: None,
             bias: Bias::Off,
             soft_pwm: None,
             #[cfg(any(
@@ -624,25 +1624,123 @@ impl OutputPin {
 
     /// Returns `true` if the pin's output state is set to [`Low`].
     ///
+    /// `is_set_low` returns the intended output state, based on the last level written
+    /// to the pin. If the output state hasn't been changed since the pin was configured
+    /// as an output, or a software-based PWM signal was active, the intended state is
+    /// unknown, and `is_set_low` reads the pin's logic level instead.
+    ///
+    /// Use [`read_level`] to read the pin's actual logic level.
+    ///
     /// [`Low`]: enum.Level.html#variant.Low
+    /// [`read_level`]: #method.read_level
     #[inline]
     pub fn is_set_low(&self) -> bool {
-        self.pin.read() == Level::Low
+        self.output_level() == Level::Low
     }
 
     /// Returns `true` if the pin's output state is set to [`High`].
     ///
+    /// `is_set_high` returns the intended output state, based on the last level written
+    /// to the pin. If the output state hasn't been changed since the pin was configured
+    /// as an output, or a software-based PWM signal was active, the intended state is
+    /// unknown, and `is_set_high` reads the pin's logic level instead.
+    ///
+    /// Use [`read_level`] to read the pin's actual logic level.
+    ///
     /// [`High`]: enum.Level.html#variant.High
+    /// [`read_level`]: #method.read_level
     #[inline]
     pub fn is_set_high(&self) -> bool {
-        self.pin.read() == Level::High
+        self.output_level() == Level::High
+    }
+
+    /// Reads the pin's actual logic level.
+    ///
+    /// `read_level` reads the level on the pin's pad, which normally matches the
+    /// output state. A difference between the two indicates a drive fault, for
+    /// instance when the pin is shorted to ground or to 3.3 V, or an external device
+    /// is driving the same line.
+    #[inline]
+    pub fn read_level(&self) -> Level {
+        self.pin.read()
+    }
+
+    fn output_level(&self) -> Level {
+        if self.soft_pwm.is_some() {
+            return self.pin.read();
+        }
+
+        self.pin.output_level.unwrap_or_else(|| self.pin.read())
     }
 
     impl_output!();
     impl_reset_on_drop!();
+
+    /// Returns the mode and logic level the pin is set to when it goes out of scope,
+    /// or `None` if the pin's original mode is restored instead.
+    pub fn drop_state(&self) -> Option<(Mode, Level)> {
+        self.drop_state
+    }
+
+    /// Configures the mode and logic level the pin is set to when it goes out of scope.
+    ///
+    /// By default, the pin's mode is reset to its original state. This might not be
+    /// appropriate for outputs that control external hardware, such as relays or motor
+    /// drivers. `set_drop_state` replaces the default behavior. When the `OutputPin` goes
+    /// out of scope, any software-based PWM signal is stopped, the output state is changed
+    /// to `level`, and then the mode is changed to `mode`. Setting `mode` to
+    /// [`Mode::Output`] keeps driving the pin at `level` after the `OutputPin` is dropped.
+    ///
+    /// The drop state is only applied if [`reset_on_drop`] is set to `true` (default).
+    ///
+    /// [`Mode::Output`]: enum.Mode.html#variant.Output
+    /// [`reset_on_drop`]: #method.set_reset_on_drop
+    pub fn set_drop_state(&mut self, mode: Mode, level: Level) {
+        self.drop_state = Some((mode, level));
+    }
+
+    /// Clears a previously configured drop state, and restores the default behavior
+    /// of resetting the pin's mode to its original state.
+    pub fn clear_drop_state(&mut self) {
+        self.drop_state = None;
+    }
+
+    impl_release!();
+}
+
+impl Drop for OutputPin {
+    /// Sets the pin's configured drop state, or resets the pin's mode, if `reset_on_drop`
+    /// is set to `true` (default).
+    fn drop(&mut self) {
+        self.reset();
+    }
+}
+
+impl OutputPin {
+    fn reset(&mut self) {
+        if !self.reset_on_drop {
+            return;
+        }
+
+        if let Some((mode, level)) = self.drop_state {
+            // Make sure the PWM thread doesn't change the output state afterwards. Don't
+            // wait for the PWM thread if we're panicking, to avoid blocking while unwinding.
+            if !std::thread::panicking() {
+                let _ = self.clear_pwm();
+            }
+
+            self.pin.write(level);
+            self.pin.set_mode(mode);
+        } else if let Some(prev_mode) = self.prev_mode {
+            self.pin.set_mode(prev_mode);
+        }
+
+        if self.bias != Bias::Off {
+            self.pin.set_bias(Bias::Off);
+        }
+    }
 }
 
-impl_drop!(OutputPin);
 impl_eq!(OutputPin);
 
 /// GPIO pin that can be (re)configured for any mode or alternate function.
@@ -663,7 +1761,7 @@ impl_eq!(OutputPin);
 /// [`Pin::into_io`]: struct.Pin.html#method.into_io
 #[derive(Debug)]
 pub struct IoPin {
-    pin: Pin,
+    pub(crate) pin: Pin,
     mode: Mode,
     prev_mode: Option<Mode>,
     reset_on_drop: bool,
@@ -696,11 +1794,13 @@ impl IoPin {
             Some(prev_mode)
         };
 
+        let reset_on_drop = pin.default_reset_on_drop();
+
         IoPin {
             pin,
             mode,
             prev_mode,
-            reset_on_drop: true,
+            reset_on_drop,
             bias: Bias::Off,
             soft_pwm: None,
             #[cfg(any(
@@ -750,7 +1850,120 @@ impl IoPin {
     impl_input!();
     impl_output!();
     impl_reset_on_drop!();
+    impl_release!();
+}
+
+impl Drop for IoPin {
+    /// Resets the pin's mode and disables the built-in pull-up/pull-down
+    /// resistors if `reset_on_drop` is set to `true` (default).
+    fn drop(&mut self) {
+        self.reset();
+    }
 }
 
-impl_drop!(IoPin);
+impl IoPin {
+    fn reset(&mut self) {
+        if !self.reset_on_drop {
+            return;
+        }
+
+        if let Some(prev_mode) = self.prev_mode {
+            self.pin.set_mode(prev_mode);
+        }
+
+        if self.bias != Bias::Off {
+            self.pin.set_bias(Bias::Off);
+        }
+    }
+}
 impl_eq!(IoPin);
+
+/// GPIO pin configured for one of its alternate functions.
+///
+/// `AltPin`s are constructed by converting a [`Pin`] using [`Pin::into_alt`]. The pin's
+/// mode is set to the specified alternate function, which routes the pin to one of the
+/// SoC's peripherals.
+///
+/// By default, the pin's mode is reset to its original state when `AltPin` goes out of
+/// scope, unless [`set_reset_on_drop`] is set to `false`.
+///
+/// [`Pin`]: struct.Pin.html
+/// [`Pin::into_alt`]: struct.Pin.html#method.into_alt
+/// [`set_reset_on_drop`]: #method.set_reset_on_drop
+#[derive(Debug)]
+pub struct AltPin {
+    pub(crate) pin: Pin,
+    prev_mode: Option<Mode>,
+    reset_on_drop: bool,
+}
+
+impl AltPin {
+    fn new(mut pin: Pin, mode: Mode) -> AltPin {
+        let prev_mode = pin.mode();
+
+        let prev_mode = if prev_mode == mode {
+            None
+        } else {
+            pin.set_mode(mode);
+            Some(prev_mode)
+        };
+
+        let reset_on_drop = pin.default_reset_on_drop();
+
+        AltPin {
+            pin,
+            prev_mode,
+            reset_on_drop,
+        }
+    }
+
+    impl_pin!();
+
+    /// Returns the pin's mode.
+    #[inline]
+    pub fn mode(&self) -> Mode {
+        self.pin.mode()
+    }
+
+    /// Returns the value of `reset_on_drop`.
+    pub fn reset_on_drop(&self) -> bool {
+        self.reset_on_drop
+    }
+
+    /// When enabled, resets the pin's mode to its original state when the pin goes
+    /// out of scope. By default, this is set to `true`.
+    ///
+    /// ## Note
+    ///
+    /// Drop methods aren't called when a process is abnormally terminated, for
+    /// instance when a user presses <kbd>Ctrl</kbd> + <kbd>C</kbd>, and the `SIGINT` signal
+    /// isn't caught. You can catch those using crates such as [`simple_signal`].
+    ///
+    /// [`simple_signal`]: https://crates.io/crates/simple-signal
+    pub fn set_reset_on_drop(&mut self, reset_on_drop: bool) {
+        self.reset_on_drop = reset_on_drop;
+    }
+
+    impl_release!();
+}
+
+impl Drop for AltPin {
+    /// Resets the pin's mode if `reset_on_drop` is set to `true` (default).
+    fn drop(&mut self) {
+        self.reset();
+    }
+}
+
+impl AltPin {
+    fn reset(&mut self) {
+        if !self.reset_on_drop {
+            return;
+        }
+
+        if let Some(prev_mode) = self.prev_mode {
+            self.pin.set_mode(prev_mode);
+        }
+    }
+}
+
+impl_eq!(AltPin);
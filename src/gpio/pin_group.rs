@@ -0,0 +1,148 @@
+use crate::gpio::{Bias, IoPin, Mode};
+
+// Number of 32-bit registers needed to cover every addressable pin
+const BANKS: usize = (u8::MAX as usize + 1) / 32;
+
+/// An ordered group of GPIO pins that are read or written as a single value.
+///
+/// `PinGroup`s are constructed by retrieving them using [`Gpio::get_group`].
+///
+/// Each pin in the group represents a single bit. The first pin corresponds to the
+/// least significant bit (bit 0), the second pin to bit 1, and so on. A `PinGroup`
+/// can contain up to 32 pins.
+///
+/// Rather than accessing each pin individually, [`write_bits`] and [`read_bits`]
+/// access the GPIO registers directly for all affected pins at once, which makes
+/// `PinGroup` suitable for parallel buses, such as 4-bit or 8-bit LCD data lines.
+///
+/// On the BCM283x and BCM2711, pins set to [`High`] and pins set to [`Low`] are
+/// changed by two consecutive register writes. On the RP1 (Raspberry Pi 5), all
+/// pins change state simultaneously.
+///
+/// When the `PinGroup` goes out of scope, each pin is reset according to its
+/// [`reset_on_drop`] setting.
+///
+/// [`Gpio::get_group`]: struct.Gpio.html#method.get_group
+/// [`write_bits`]: #method.write_bits
+/// [`read_bits`]: #method.read_bits
+/// [`High`]: enum.Level.html#variant.High
+/// [`Low`]: enum.Level.html#variant.Low
+/// [`reset_on_drop`]: #method.set_reset_on_drop
+#[derive(Debug)]
+pub struct PinGroup {
+    pins: Vec<IoPin>,
+}
+
+impl PinGroup {
+    pub(crate) fn new(pins: Vec<IoPin>) -> PinGroup {
+        PinGroup { pins }
+    }
+
+    /// Returns the number of pins in the group.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.pins.len()
+    }
+
+    /// Returns `true` if the group doesn't contain any pins.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.pins.is_empty()
+    }
+
+    /// Returns the BCM GPIO numbers of the pins in the group, in bit order.
+    pub fn pins(&self) -> Vec<u8> {
+        self.pins.iter().map(|pin| pin.pin()).collect()
+    }
+
+    /// Sets the mode for all pins in the group.
+    ///
+    /// Switching between [`Mode::Input`] and [`Mode::Output`] is useful for
+    /// bidirectional buses.
+    ///
+    /// [`Mode::Input`]: enum.Mode.html#variant.Input
+    /// [`Mode::Output`]: enum.Mode.html#variant.Output
+    pub fn set_mode(&mut self, mode: Mode) {
+        for pin in self.pins.iter_mut() {
+            pin.set_mode(mode);
+        }
+    }
+
+    /// Configures the built-in pull-up/pull-down resistors for all pins in the group.
+    pub fn set_bias(&mut self, bias: Bias) {
+        for pin in self.pins.iter_mut() {
+            pin.set_bias(bias);
+        }
+    }
+
+    /// Reads the logic levels of all pins in the group.
+    ///
+    /// Bit `n` of the returned value contains the logic level of the `n`th pin
+    /// in the group.
+    pub fn read_bits(&self) -> u32 {
+        let mut banks = [None; BANKS];
+        let mut value = 0u32;
+
+        for (bit, pin) in self.pins.iter().enumerate() {
+            let bcm = pin.pin();
+            let bank = (bcm / 32) as usize;
+
+            // Read each register only once
+            let levels = *banks[bank]
+                .get_or_insert_with(|| pin.pin.gpio_state.gpio_mem.bank_levels(bank as u8));
+
+            value |= ((levels >> (bcm % 32)) & 0b1) << bit;
+        }
+
+        value
+    }
+
+    /// Sets the output state of all pins in the group.
+    ///
+    /// Bit `n` of `value` sets the output state of the `n`th pin in the group.
+    /// Any bits beyond the number of pins in the group are ignored.
+    pub fn write_bits(&mut self, value: u32) {
+        let mut masks = [0u32; BANKS];
+        let mut values = [0u32; BANKS];
+
+        for (bit, pin) in self.pins.iter().enumerate() {
+            let bcm = pin.pin();
+            let bank = (bcm / 32) as usize;
+
+            masks[bank] |= 1 << (bcm % 32);
+            values[bank] |= ((value >> bit) & 0b1) << (bcm % 32);
+        }
+
+        if let Some(pin) = self.pins.first() {
+            for (bank, (&mask, &value)) in masks.iter().zip(values.iter()).enumerate() {
+                if mask > 0 {
+                    pin.pin
+                        .gpio_state
+                        .gpio_mem
+                        .write_bank(bank as u8, mask, value);
+                }
+            }
+        }
+    }
+
+    /// Returns the value of `reset_on_drop`.
+    pub fn reset_on_drop(&self) -> bool {
+        self.pins.first().map_or(true, |pin| pin.reset_on_drop())
+    }
+
+    /// When enabled, resets each pin's mode to its original state and disables the
+    /// built-in pull-up/pull-down resistors when the group goes out of scope.
+    /// By default, this is set to `true`.
+    pub fn set_reset_on_drop(&mut self, reset_on_drop: bool) {
+        for pin in self.pins.iter_mut() {
+            pin.set_reset_on_drop(reset_on_drop);
+        }
+    }
+
+    /// Consumes the `PinGroup` and returns the individual [`IoPin`]s, in bit order.
+    ///
+    /// [`IoPin`]: struct.IoPin.html
+    pub fn into_pins(self) -> Vec<IoPin> {
+        self.pins
+    }
+}
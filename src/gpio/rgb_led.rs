@@ -0,0 +1,207 @@
+use std::fmt;
+use std::io;
+
+use super::{Error, OutputPin, Result};
+use crate::pwm::{self, Pwm};
+
+// Software-based PWM frequency. High enough to avoid visible flicker, while keeping
+// the CPU load of the PWM threads reasonable.
+const SOFT_PWM_FREQUENCY: f64 = 200.0;
+
+const DEFAULT_GAMMA: f64 = 2.2;
+
+/// RGB LED polarities.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum RgbLedPolarity {
+    /// The LEDs share a common cathode connected to ground, and each color is lit
+    /// when its pin is high.
+    CommonCathode,
+    /// The LEDs share a common anode connected to 3.3 V, and each color is lit
+    /// when its pin is low.
+    CommonAnode,
+}
+
+impl fmt::Display for RgbLedPolarity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            RgbLedPolarity::CommonCathode => write!(f, "CommonCathode"),
+            RgbLedPolarity::CommonAnode => write!(f, "CommonAnode"),
+        }
+    }
+}
+
+/// Output used to drive a single color of an [`RgbLed`].
+///
+/// [`RgbLed`]: struct.RgbLed.html
+#[derive(Debug)]
+pub enum RgbLedChannel {
+    /// Software-based PWM on a GPIO pin.
+    Pin(OutputPin),
+    /// Hardware PWM channel.
+    Pwm(Pwm),
+}
+
+impl RgbLedChannel {
+    fn set_duty_cycle(&mut self, duty_cycle: f64) -> Result<()> {
+        match *self {
+            RgbLedChannel::Pin(ref mut pin) => {
+                pin.set_pwm_frequency(SOFT_PWM_FREQUENCY, duty_cycle)
+            }
+            RgbLedChannel::Pwm(ref pwm) => pwm.set_duty_cycle(duty_cycle).map_err(pwm_error),
+        }
+    }
+}
+
+impl From<OutputPin> for RgbLedChannel {
+    fn from(pin: OutputPin) -> RgbLedChannel {
+        RgbLedChannel::Pin(pin)
+    }
+}
+
+impl From<Pwm> for RgbLedChannel {
+    fn from(pwm: Pwm) -> RgbLedChannel {
+        RgbLedChannel::Pwm(pwm)
+    }
+}
+
+/// RGB LED driver.
+///
+/// `RgbLed` mixes colors on an RGB LED by controlling the duty cycle of three
+/// PWM outputs. Each output can be either an [`OutputPin`], which uses software-based
+/// PWM at 200 Hz, or a hardware [`Pwm`] channel. Hardware PWM channels should be
+/// configured with a period or frequency before they're passed to `RgbLed`, and are
+/// enabled automatically.
+///
+/// The perceived brightness of an LED isn't proportional to its duty cycle. To make
+/// color values appear perceptually linear, `RgbLed` applies gamma correction, by
+/// calculating each duty cycle as `(value / 255) ^ gamma`. The gamma value defaults
+/// to `2.2`, and can be changed with [`set_gamma`].
+///
+/// [`OutputPin`]: struct.OutputPin.html
+/// [`Pwm`]: ../pwm/struct.Pwm.html
+/// [`set_gamma`]: #method.set_gamma
+#[derive(Debug)]
+pub struct RgbLed {
+    channels: [RgbLedChannel; 3],
+    polarity: RgbLedPolarity,
+    gamma: f64,
+    color: (u8, u8, u8),
+}
+
+impl RgbLed {
+    /// Constructs a new `RgbLed`, and turns the LED off.
+    ///
+    /// `red`, `green` and `blue` accept either an [`OutputPin`] or a [`Pwm`], which
+    /// can be mixed. `polarity` indicates whether the LED has a common cathode or a
+    /// common anode.
+    ///
+    /// [`OutputPin`]: struct.OutputPin.html
+    /// [`Pwm`]: ../pwm/struct.Pwm.html
+    pub fn new<R, G, B>(red: R, green: G, blue: B, polarity: RgbLedPolarity) -> Result<RgbLed>
+    where
+        R: Into<RgbLedChannel>,
+        G: Into<RgbLedChannel>,
+        B: Into<RgbLedChannel>,
+    {
+        let mut led = RgbLed {
+            channels: [red.into(), green.into(), blue.into()],
+            polarity,
+            gamma: DEFAULT_GAMMA,
+            color: (0, 0, 0),
+        };
+
+        led.apply()?;
+
+        for channel in led.channels.iter() {
+            if let RgbLedChannel::Pwm(ref pwm) = *channel {
+                pwm.enable().map_err(pwm_error)?;
+            }
+        }
+
+        Ok(led)
+    }
+
+    /// Returns the polarity.
+    pub fn polarity(&self) -> RgbLedPolarity {
+        self.polarity
+    }
+
+    /// Returns the gamma value.
+    pub fn gamma(&self) -> f64 {
+        self.gamma
+    }
+
+    /// Sets the gamma value used for gamma correction, and updates the LED.
+    ///
+    /// Setting `gamma` to `1.0` disables gamma correction, which maps color values
+    /// to duty cycles linearly. By default, `gamma` is set to `2.2`.
+    ///
+    /// If `gamma` isn't a positive number, `set_gamma` returns `Err(`[`Error::Io`]`)`
+    /// with an `io::ErrorKind::InvalidInput` error.
+    ///
+    /// [`Error::Io`]: enum.Error.html#variant.Io
+    pub fn set_gamma(&mut self, gamma: f64) -> Result<()> {
+        if !gamma.is_finite() || gamma <= 0.0 {
+            return Err(Error::Io(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "gamma must be a positive number",
+            )));
+        }
+
+        self.gamma = gamma;
+
+        self.apply()
+    }
+
+    /// Returns the current color as `(red, green, blue)`.
+    pub fn color(&self) -> (u8, u8, u8) {
+        self.color
+    }
+
+    /// Sets the color.
+    ///
+    /// `red`, `green` and `blue` range from `0` (off) to `255` (full brightness).
+    pub fn set_color(&mut self, red: u8, green: u8, blue: u8) -> Result<()> {
+        self.color = (red, green, blue);
+
+        self.apply()
+    }
+
+    /// Turns the LED off.
+    pub fn off(&mut self) -> Result<()> {
+        self.set_color(0, 0, 0)
+    }
+
+    /// Consumes the `RgbLed`, and returns the [`RgbLedChannel`]s for red, green
+    /// and blue.
+    ///
+    /// The outputs keep their current duty cycle.
+    ///
+    /// [`RgbLedChannel`]: enum.RgbLedChannel.html
+    pub fn into_channels(self) -> [RgbLedChannel; 3] {
+        self.channels
+    }
+
+    fn apply(&mut self) -> Result<()> {
+        let (red, green, blue) = self.color;
+
+        for (channel, value) in self.channels.iter_mut().zip([red, green, blue].iter()) {
+            let mut duty_cycle = (f64::from(*value) / 255.0).powf(self.gamma);
+
+            if self.polarity == RgbLedPolarity::CommonAnode {
+                duty_cycle = 1.0 - duty_cycle;
+            }
+
+            channel.set_duty_cycle(duty_cycle)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn pwm_error(err: pwm::Error) -> Error {
+    match err {
+        pwm::Error::Io(e) => Error::Io(e),
+        e => Error::Io(io::Error::new(io::ErrorKind::Other, e)),
+    }
+}
@@ -0,0 +1,97 @@
+use std::io;
+
+use super::{Error, Level, OutputPin, Result};
+use crate::spi::{self, Spi};
+
+#[derive(Debug)]
+struct Pins {
+    data: OutputPin,
+    clock: OutputPin,
+    latch: OutputPin,
+}
+
+#[derive(Debug)]
+enum Output {
+    // OutputPin is much larger than Spi when the embedded-hal features are enabled
+    Pins(Box<Pins>),
+    Spi(Spi),
+}
+
+/// Serial-in, parallel-out shift register driver.
+///
+/// `ShiftRegister` drives 74HC595-style shift registers, which expand a few GPIO pins
+/// into 8 outputs per register. Multiple registers can be chained by connecting the
+/// serial output (QH') of each register to the serial input (SER) of the next one.
+///
+/// The bits are shifted out either by toggling the data (SER), clock (SRCLK) and latch
+/// (RCLK) pins through [`new`], or by using the SPI peripheral through [`with_spi`],
+/// which is considerably faster.
+///
+/// [`new`]: #method.new
+/// [`with_spi`]: #method.with_spi
+#[derive(Debug)]
+pub struct ShiftRegister {
+    output: Output,
+}
+
+impl ShiftRegister {
+    /// Constructs a new `ShiftRegister` using the specified data, clock and latch pins.
+    ///
+    /// `data`, `clock` and `latch` are connected to the shift register's SER, SRCLK and
+    /// RCLK inputs. The clock and latch pins are set low.
+    pub fn new(data: OutputPin, mut clock: OutputPin, mut latch: OutputPin) -> ShiftRegister {
+        clock.set_low();
+        latch.set_low();
+
+        ShiftRegister {
+            output: Output::Pins(Box::new(Pins { data, clock, latch })),
+        }
+    }
+
+    /// Constructs a new `ShiftRegister` using the SPI peripheral.
+    ///
+    /// Connect MOSI to SER, SCLK to SRCLK, and the Slave Select pin to RCLK. Slave
+    /// Select is set inactive (high) when a transfer completes, which latches the data.
+    /// `spi` should be configured for [`Mode0`], with the default [`MsbFirst`] bit order.
+    ///
+    /// [`Mode0`]: ../spi/enum.Mode.html#variant.Mode0
+    /// [`MsbFirst`]: ../spi/enum.BitOrder.html
+    pub fn with_spi(spi: Spi) -> ShiftRegister {
+        ShiftRegister {
+            output: Output::Spi(spi),
+        }
+    }
+
+    /// Shifts out the bytes in `data`, and latches the result to the outputs.
+    ///
+    /// Each byte is shifted out MSB first. For chained registers, the first byte ends up
+    /// in the register furthest from the Raspberry Pi, so `data` should contain one byte
+    /// for each register in the chain, starting with the last one.
+    pub fn write(&mut self, data: &[u8]) -> Result<()> {
+        match self.output {
+            Output::Pins(ref mut pins) => {
+                for &byte in data {
+                    for bit in (0..8).rev() {
+                        pins.data.write(Level::from(byte & (1 << bit) > 0));
+
+                        // Data is shifted in on the rising edge of SRCLK
+                        pins.clock.set_high();
+                        pins.clock.set_low();
+                    }
+                }
+
+                // Data is transferred to the outputs on the rising edge of RCLK
+                pins.latch.set_high();
+                pins.latch.set_low();
+            }
+            Output::Spi(ref mut spi) => {
+                spi.write(data).map_err(|e| match e {
+                    spi::Error::Io(e) => Error::Io(e),
+                    e => Error::Io(io::Error::new(io::ErrorKind::Other, e)),
+                })?;
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -2,14 +2,16 @@
 #![allow(clippy::cast_lossless)]
 #![allow(dead_code)]
 
-use std::sync::mpsc::{self, Receiver, Sender};
-use std::sync::Arc;
+use std::result;
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender, TryRecvError};
+use std::sync::{Arc, Mutex};
 use std::thread::{self, sleep};
 use std::time::Duration;
 
 use libc::{self, sched_param, timespec, CLOCK_MONOTONIC, PR_SET_TIMERSLACK, SCHED_RR};
 
-use super::{Error, GpioState, Result};
+use super::{Error, GpioState, Level, Result};
 
 // Only call sleep() if we have enough time remaining
 const SLEEP_THRESHOLD: i64 = 250_000;
@@ -20,142 +22,443 @@ const BUSYWAIT_REMAINDER: i64 = 100;
 
 const NANOS_PER_SEC: i64 = 1_000_000_000;
 
+// Number of get_time_ns() calls used to measure its overhead
+const CALIBRATION_SAMPLES: i64 = 1000;
+
+// Measured get_time_ns() overhead, or -1 if it hasn't been measured yet
+static TIME_OVERHEAD_NS: AtomicI64 = AtomicI64::new(-1);
+
 #[derive(Debug, PartialEq, Eq, Copy, Clone)]
 enum Msg {
-    Reconfigure(Duration, Duration),
     Stop,
 }
 
+// Messages sent to the scheduler thread
+enum SchedulerMsg {
+    Add(Channel),
+    Reconfigure(u64, Duration, Duration),
+    // Removes the channel and sets its pin low, before acknowledging through the sender
+    Remove(u64, Sender<()>),
+}
+
+/// Timing statistics for a software-based PWM signal.
+///
+/// The period error is the difference between the measured duration of a PWM cycle and
+/// the configured period, in nanoseconds. Positive values indicate cycles that took
+/// longer than configured. On a kernel without real-time scheduling, preemption can
+/// cause errors of tens to hundreds of microseconds.
+#[derive(Debug, PartialEq, Copy, Clone, Default)]
+pub struct JitterStats {
+    /// Number of completed PWM cycles.
+    pub periods: u64,
+    /// Smallest period error in nanoseconds.
+    pub min_error_ns: i64,
+    /// Largest period error in nanoseconds.
+    pub max_error_ns: i64,
+    /// Mean period error in nanoseconds.
+    pub mean_error_ns: f64,
+}
+
+// Period error statistics, updated by the PWM thread at the end of each cycle
 #[derive(Debug)]
-pub(crate) struct SoftPwm {
-    pwm_thread: Option<thread::JoinHandle<Result<()>>>,
-    sender: Sender<Msg>,
+struct Stats {
+    periods: AtomicU64,
+    min_error_ns: AtomicI64,
+    max_error_ns: AtomicI64,
+    sum_error_ns: AtomicI64,
 }
 
-impl SoftPwm {
-    pub(crate) fn new(
+impl Stats {
+    fn new() -> Stats {
+        Stats {
+            periods: AtomicU64::new(0),
+            min_error_ns: AtomicI64::new(i64::MAX),
+            max_error_ns: AtomicI64::new(i64::MIN),
+            sum_error_ns: AtomicI64::new(0),
+        }
+    }
+
+    fn update(&self, error_ns: i64) {
+        // Only the PWM thread writes to the stats, so we don't need compare-and-swap loops
+        if error_ns < self.min_error_ns.load(Ordering::Relaxed) {
+            self.min_error_ns.store(error_ns, Ordering::Relaxed);
+        }
+
+        if error_ns > self.max_error_ns.load(Ordering::Relaxed) {
+            self.max_error_ns.store(error_ns, Ordering::Relaxed);
+        }
+
+        self.sum_error_ns.fetch_add(error_ns, Ordering::Relaxed);
+        self.periods.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn reset(&self) {
+        self.periods.store(0, Ordering::Relaxed);
+        self.min_error_ns.store(i64::MAX, Ordering::Relaxed);
+        self.max_error_ns.store(i64::MIN, Ordering::Relaxed);
+        self.sum_error_ns.store(0, Ordering::Relaxed);
+    }
+
+    fn snapshot(&self) -> JitterStats {
+        let periods = self.periods.load(Ordering::Relaxed);
+        if periods == 0 {
+            return JitterStats::default();
+        }
+
+        JitterStats {
+            periods,
+            min_error_ns: self.min_error_ns.load(Ordering::Relaxed),
+            max_error_ns: self.max_error_ns.load(Ordering::Relaxed),
+            mean_error_ns: self.sum_error_ns.load(Ordering::Relaxed) as f64 / periods as f64,
+        }
+    }
+}
+
+// A single PWM signal driven by the scheduler thread
+struct Channel {
+    id: u64,
+    pin: u8,
+    // Keeps the GPIO registers mapped while the channel is active
+    gpio_state: Arc<GpioState>,
+    period_ns: i64,
+    pulse_width_ns: i64,
+    // Period and pulse width that take effect at the start of the next period
+    pending: Option<(i64, i64)>,
+    start_ns: i64,
+    active: bool,
+    stats: Arc<Stats>,
+}
+
+impl Channel {
+    fn new(
+        id: u64,
         pin: u8,
         gpio_state: Arc<GpioState>,
         period: Duration,
         pulse_width: Duration,
-    ) -> SoftPwm {
-        let (sender, receiver): (Sender<Msg>, Receiver<Msg>) = mpsc::channel();
+        stats: Arc<Stats>,
+    ) -> Channel {
+        let (period_ns, pulse_width_ns) = timing_ns(period, pulse_width);
+
+        Channel {
+            id,
+            pin,
+            gpio_state,
+            period_ns,
+            pulse_width_ns,
+            pending: None,
+            start_ns: 0,
+            active: false,
+            stats,
+        }
+    }
 
-        let pwm_thread = thread::spawn(move || -> Result<()> {
-            // Set the scheduling policy to real-time round robin at the highest priority. This
-            // will silently fail if we're not running as root.
-            #[cfg(target_env = "gnu")]
-            let params = sched_param {
-                sched_priority: unsafe { libc::sched_get_priority_max(SCHED_RR) },
-            };
-
-            #[cfg(target_env = "musl")]
-            let params = sched_param {
-                sched_priority: unsafe { libc::sched_get_priority_max(SCHED_RR) },
-                sched_ss_low_priority: 0,
-                sched_ss_repl_period: timespec {
-                    tv_sec: 0,
-                    tv_nsec: 0,
-                },
-                sched_ss_init_budget: timespec {
-                    tv_sec: 0,
-                    tv_nsec: 0,
-                },
-                sched_ss_max_repl: 0,
-            };
-
-            unsafe {
-                libc::sched_setscheduler(0, SCHED_RR, &params);
+    // Time of the next pin state change, or i64::MAX if there's nothing to do
+    fn next_ns(&self) -> i64 {
+        if self.period_ns <= 0 {
+            i64::MAX
+        } else if self.active && self.pulse_width_ns < self.period_ns {
+            self.start_ns + self.pulse_width_ns
+        } else {
+            self.start_ns + self.period_ns
+        }
+    }
+
+    fn start_period(&mut self, current_ns: i64) {
+        self.start_ns = current_ns;
+        self.active = self.period_ns > 0 && self.pulse_width_ns > 0;
+
+        if self.active {
+            self.gpio_state.gpio_mem.set_high(self.pin);
+        } else {
+            self.gpio_state.gpio_mem.set_low(self.pin);
+        }
+    }
+
+    fn reconfigure(&mut self, period: Duration, pulse_width: Duration, current_ns: i64) {
+        let timing = timing_ns(period, pulse_width);
+
+        // Inactive channels don't have a period that needs to complete first
+        if self.period_ns <= 0 {
+            self.period_ns = timing.0;
+            self.pulse_width_ns = timing.1;
+            self.stats.reset();
+            self.start_period(current_ns);
+        } else {
+            self.pending = Some(timing);
+        }
+    }
+
+    fn update(&mut self, current_ns: i64) {
+        // PWM inactive
+        if self.active && self.pulse_width_ns < self.period_ns {
+            self.gpio_state.gpio_mem.set_low(self.pin);
+            self.active = false;
+
+            return;
+        }
+
+        // End of the period
+        self.stats
+            .update(current_ns - self.start_ns - self.period_ns);
+
+        if let Some((period_ns, pulse_width_ns)) = self.pending.take() {
+            self.period_ns = period_ns;
+            self.pulse_width_ns = pulse_width_ns;
+            self.stats.reset();
+        }
+
+        self.start_period(current_ns);
+    }
+}
+
+// Converts period and pulse width to nanoseconds, limiting the pulse width to the period
+fn timing_ns(period: Duration, pulse_width: Duration) -> (i64, i64) {
+    let period_ns = period.as_nanos().min(i64::MAX as u128 / 2) as i64;
+    let pulse_width_ns = (pulse_width.as_nanos().min(i64::MAX as u128 / 2) as i64).min(period_ns);
+
+    (period_ns, pulse_width_ns)
+}
+
+// Shared thread that drives the software-based PWM signals for all pins. The thread
+// is started when the first signal is configured, and stopped when the Scheduler
+// is dropped together with the GpioState.
+#[derive(Debug, Default)]
+pub(crate) struct Scheduler {
+    sender: Mutex<Option<Sender<SchedulerMsg>>>,
+    next_id: AtomicU64,
+}
+
+impl Scheduler {
+    pub(crate) fn new() -> Scheduler {
+        Scheduler::default()
+    }
+
+    // Adds a channel, and returns a sender for further messages
+    fn add(&self, channel: Channel) -> Sender<SchedulerMsg> {
+        let mut sender = self.sender.lock().unwrap();
+
+        let tx = match *sender {
+            Some(ref tx) => tx.clone(),
+            None => {
+                let (tx, receiver) = mpsc::channel();
+                thread::spawn(move || run(receiver));
+                *sender = Some(tx.clone());
+
+                tx
             }
+        };
+
+        if let Err(mpsc::SendError(SchedulerMsg::Add(channel))) =
+            tx.send(SchedulerMsg::Add(channel))
+        {
+            // The thread is no longer running, which only happens if it panicked.
+            // Start a new thread.
+            *sender = None;
+            drop(sender);
+
+            return self.add(channel);
+        }
+
+        tx
+    }
+}
+
+fn run(receiver: Receiver<SchedulerMsg>) {
+    set_realtime_priority();
 
-            // Set timer slack to 1 ns (default = 50 µs). This is only relevant if we're unable
-            // to set a real-time scheduling policy.
-            unsafe {
-                libc::prctl(PR_SET_TIMERSLACK, 1);
+    let mut channels: Vec<Channel> = Vec::new();
+
+    loop {
+        // Handle any messages first, so they can't be delayed indefinitely by
+        // channels that only busy-wait
+        loop {
+            match receiver.try_recv() {
+                Ok(msg) => handle_msg(&mut channels, msg),
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => return stop_all(&mut channels),
             }
+        }
 
-            let mut period_ns = period.as_nanos() as i64;
-            let mut pulse_width_ns = pulse_width.as_nanos() as i64;
+        let next_ns = channels
+            .iter()
+            .map(Channel::next_ns)
+            .min()
+            .unwrap_or(i64::MAX);
+
+        // Without any pin state changes, there's nothing to do until we receive a message
+        if next_ns == i64::MAX {
+            match receiver.recv() {
+                Ok(msg) => handle_msg(&mut channels, msg),
+                Err(_) => return stop_all(&mut channels),
+            }
 
-            let mut start_ns = get_time_ns();
+            continue;
+        }
 
-            loop {
-                // PWM active
-                if pulse_width_ns > 0 {
-                    gpio_state.gpio_mem.set_high(pin);
-                }
+        match wait_until_ns_or_msg(&receiver, next_ns) {
+            Ok(Some(msg)) => {
+                handle_msg(&mut channels, msg);
+                continue;
+            }
+            Ok(None) => {}
+            Err(_) => return stop_all(&mut channels),
+        }
 
-                // Sleep if we have enough time remaining, while reserving some time
-                // for busy waiting to compensate for sleep taking longer than needed.
-                if pulse_width_ns >= SLEEP_THRESHOLD {
-                    sleep(Duration::from_nanos((pulse_width_ns - BUSYWAIT_MAX) as u64));
-                }
+        let current_ns = get_time_ns();
+        for channel in channels.iter_mut() {
+            if channel.next_ns() - current_ns <= BUSYWAIT_REMAINDER {
+                channel.update(current_ns);
+            }
+        }
+    }
+}
 
-                // Busy-wait for the remaining active time, minus BUSYWAIT_REMAINDER
-                // to account for get_time_ns() overhead
-                loop {
-                    if (pulse_width_ns - (get_time_ns() - start_ns)) <= BUSYWAIT_REMAINDER {
-                        break;
-                    }
-                }
+fn handle_msg(channels: &mut Vec<Channel>, msg: SchedulerMsg) {
+    match msg {
+        SchedulerMsg::Add(mut channel) => {
+            channel.start_period(get_time_ns());
+            channels.push(channel);
+        }
+        SchedulerMsg::Reconfigure(id, period, pulse_width) => {
+            if let Some(channel) = channels.iter_mut().find(|channel| channel.id == id) {
+                channel.reconfigure(period, pulse_width, get_time_ns());
+            }
+        }
+        SchedulerMsg::Remove(id, ack) => {
+            if let Some(idx) = channels.iter().position(|channel| channel.id == id) {
+                let channel = channels.swap_remove(idx);
+                channel.gpio_state.gpio_mem.set_low(channel.pin);
+            }
 
-                // PWM inactive
-                gpio_state.gpio_mem.set_low(pin);
-
-                while let Ok(msg) = receiver.try_recv() {
-                    match msg {
-                        Msg::Reconfigure(period, pulse_width) => {
-                            // Reconfigure period and pulse width
-                            pulse_width_ns = pulse_width.as_nanos() as i64;
-                            period_ns = period.as_nanos() as i64;
-
-                            if pulse_width_ns > period_ns {
-                                pulse_width_ns = period_ns;
-                            }
-                        }
-                        Msg::Stop => {
-                            // The main thread asked us to stop
-                            return Ok(());
-                        }
+            let _ = ack.send(());
+        }
+    }
+}
+
+fn stop_all(channels: &mut Vec<Channel>) {
+    for channel in channels.drain(..) {
+        channel.gpio_state.gpio_mem.set_low(channel.pin);
+    }
+}
+
+// The PWM signal is either driven by the shared scheduler thread, or the pin plays a
+// pattern on its own thread.
+#[derive(Debug)]
+enum Driver {
+    Scheduler(u64, Sender<SchedulerMsg>),
+    Pattern(Option<thread::JoinHandle<Result<()>>>, Sender<Msg>),
+}
+
+#[derive(Debug)]
+pub(crate) struct SoftPwm {
+    driver: Driver,
+    stats: Arc<Stats>,
+}
+
+impl SoftPwm {
+    pub(crate) fn new(
+        pin: u8,
+        gpio_state: Arc<GpioState>,
+        period: Duration,
+        pulse_width: Duration,
+    ) -> SoftPwm {
+        let stats = Arc::new(Stats::new());
+        let scheduler = &gpio_state.soft_pwm_scheduler;
+        let id = scheduler.next_id.fetch_add(1, Ordering::Relaxed);
+
+        let channel = Channel::new(
+            id,
+            pin,
+            gpio_state.clone(),
+            period,
+            pulse_width,
+            stats.clone(),
+        );
+        let sender = scheduler.add(channel);
+
+        SoftPwm {
+            driver: Driver::Scheduler(id, sender),
+            stats,
+        }
+    }
+
+    // Plays a sequence of output levels, each held for the specified duration. The
+    // pin is left at the last level once the pattern completes, unless repeat is set.
+    pub(crate) fn with_pattern(
+        pin: u8,
+        gpio_state: Arc<GpioState>,
+        steps: Vec<(Level, Duration)>,
+        repeat: bool,
+    ) -> SoftPwm {
+        let (sender, receiver): (Sender<Msg>, Receiver<Msg>) = mpsc::channel();
+
+        let pwm_thread = thread::spawn(move || -> Result<()> {
+            set_realtime_priority();
+
+            let mut target_ns = get_time_ns();
+
+            loop {
+                for &(level, duration) in &steps {
+                    match level {
+                        Level::Low => gpio_state.gpio_mem.set_low(pin),
+                        Level::High => gpio_state.gpio_mem.set_high(pin),
                     }
-                }
 
-                let remaining_ns = period_ns - (get_time_ns() - start_ns);
+                    // Schedule steps relative to the previous step, so any delays don't add up
+                    target_ns += duration.as_nanos() as i64;
 
-                // Sleep if we have enough time remaining, while reserving some time
-                // for busy waiting to compensate for sleep taking longer than needed.
-                if remaining_ns >= SLEEP_THRESHOLD {
-                    sleep(Duration::from_nanos((remaining_ns - BUSYWAIT_MAX) as u64));
+                    if wait_until_ns_or_stop(&receiver, target_ns) {
+                        return Ok(());
+                    }
                 }
 
-                // Busy-wait for the remaining inactive time, minus BUSYWAIT_REMAINDER
-                // to account for get_time_ns() overhead
-                loop {
-                    let current_ns = get_time_ns();
-                    if (period_ns - (current_ns - start_ns)) <= BUSYWAIT_REMAINDER {
-                        start_ns = current_ns;
-                        break;
-                    }
+                if !repeat {
+                    return Ok(());
                 }
             }
         });
 
         SoftPwm {
-            pwm_thread: Some(pwm_thread),
-            sender,
+            driver: Driver::Pattern(Some(pwm_thread), sender),
+            stats: Arc::new(Stats::new()),
         }
     }
 
+    pub(crate) fn is_pattern(&self) -> bool {
+        matches!(self.driver, Driver::Pattern(_, _))
+    }
+
+    pub(crate) fn jitter_stats(&self) -> JitterStats {
+        self.stats.snapshot()
+    }
+
     pub(crate) fn reconfigure(&mut self, period: Duration, pulse_width: Duration) {
-        let _ = self.sender.send(Msg::Reconfigure(period, pulse_width));
+        if let Driver::Scheduler(id, ref sender) = self.driver {
+            let _ = sender.send(SchedulerMsg::Reconfigure(id, period, pulse_width));
+        }
     }
 
     pub(crate) fn stop(&mut self) -> Result<()> {
-        let _ = self.sender.send(Msg::Stop);
-        if let Some(pwm_thread) = self.pwm_thread.take() {
-            match pwm_thread.join() {
-                Ok(r) => return r,
-                Err(_) => return Err(Error::ThreadPanic),
+        match self.driver {
+            Driver::Scheduler(id, ref sender) => {
+                let (ack, ack_receiver) = mpsc::channel();
+
+                // The scheduler thread dropped the channel if it panicked
+                if sender.send(SchedulerMsg::Remove(id, ack)).is_err()
+                    || ack_receiver.recv().is_err()
+                {
+                    return Err(Error::ThreadPanic);
+                }
+            }
+            Driver::Pattern(ref mut pwm_thread, ref sender) => {
+                let _ = sender.send(Msg::Stop);
+                if let Some(pwm_thread) = pwm_thread.take() {
+                    match pwm_thread.join() {
+                        Ok(r) => return r,
+                        Err(_) => return Err(Error::ThreadPanic),
+                    }
+                }
             }
         }
 
@@ -165,10 +468,22 @@ impl SoftPwm {
 
 impl Drop for SoftPwm {
     fn drop(&mut self) {
-        // Don't wait for the pwm thread to exit if the main thread is panicking,
+        // Don't wait for the pwm thread to respond if the main thread is panicking,
         // because we could potentially block indefinitely while unwinding if the
-        // pwm thread doesn't respond to the Stop message for some reason.
-        if !thread::panicking() {
+        // pwm thread doesn't respond to the message for some reason. The message
+        // still needs to be sent, since the shared scheduler thread would otherwise
+        // keep driving the pin.
+        if thread::panicking() {
+            match self.driver {
+                Driver::Scheduler(id, ref sender) => {
+                    let (ack, _) = mpsc::channel();
+                    let _ = sender.send(SchedulerMsg::Remove(id, ack));
+                }
+                Driver::Pattern(_, ref sender) => {
+                    let _ = sender.send(Msg::Stop);
+                }
+            }
+        } else {
             let _ = self.stop();
         }
     }
@@ -178,8 +493,105 @@ impl Drop for SoftPwm {
 // safe because all usage of Sender::send() is locked behind &mut self.
 unsafe impl Sync for SoftPwm {}
 
+// Set the scheduling policy for the current thread to real-time round robin at
+// the highest priority. This will silently fail if we're not running as root.
+pub(crate) fn set_realtime_priority() {
+    #[cfg(target_env = "gnu")]
+    let params = sched_param {
+        sched_priority: unsafe { libc::sched_get_priority_max(SCHED_RR) },
+    };
+
+    #[cfg(target_env = "musl")]
+    let params = sched_param {
+        sched_priority: unsafe { libc::sched_get_priority_max(SCHED_RR) },
+        sched_ss_low_priority: 0,
+        sched_ss_repl_period: timespec {
+            tv_sec: 0,
+            tv_nsec: 0,
+        },
+        sched_ss_init_budget: timespec {
+            tv_sec: 0,
+            tv_nsec: 0,
+        },
+        sched_ss_max_repl: 0,
+    };
+
+    unsafe {
+        libc::sched_setscheduler(0, SCHED_RR, &params);
+    }
+
+    // Set timer slack to 1 ns (default = 50 µs). This is only relevant if we're unable
+    // to set a real-time scheduling policy.
+    unsafe {
+        libc::prctl(PR_SET_TIMERSLACK, 1);
+    }
+}
+
+// Sleep and busy-wait until the monotonic clock reaches target_ns.
+#[inline(always)]
+pub(crate) fn wait_until_ns(target_ns: i64) {
+    let remaining_ns = target_ns - get_time_ns();
+
+    // Sleep if we have enough time remaining, while reserving some time
+    // for busy waiting to compensate for sleep taking longer than needed.
+    if remaining_ns >= SLEEP_THRESHOLD {
+        sleep(Duration::from_nanos((remaining_ns - BUSYWAIT_MAX) as u64));
+    }
+
+    // Busy-wait for the remaining time, minus BUSYWAIT_REMAINDER
+    // to account for get_time_ns() overhead
+    while target_ns - get_time_ns() > BUSYWAIT_REMAINDER {}
+}
+
+// Sleep and busy-wait until the monotonic clock reaches target_ns, while checking for
+// scheduler messages. Returns the first message received while sleeping, if any.
+fn wait_until_ns_or_msg(
+    receiver: &Receiver<SchedulerMsg>,
+    target_ns: i64,
+) -> result::Result<Option<SchedulerMsg>, RecvTimeoutError> {
+    let remaining_ns = target_ns - get_time_ns();
+
+    // Sleep while waiting for messages, and reserve some time for busy waiting
+    if remaining_ns >= SLEEP_THRESHOLD {
+        match receiver.recv_timeout(Duration::from_nanos((remaining_ns - BUSYWAIT_MAX) as u64)) {
+            Ok(msg) => return Ok(Some(msg)),
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(e) => return Err(e),
+        }
+    }
+
+    // Busy-wait for the remaining time, minus BUSYWAIT_REMAINDER
+    // to account for get_time_ns() overhead
+    while target_ns - get_time_ns() > BUSYWAIT_REMAINDER {}
+
+    Ok(None)
+}
+
+// Sleep and busy-wait until the monotonic clock reaches target_ns, while checking for
+// Stop messages. Returns true if the thread should stop.
+fn wait_until_ns_or_stop(receiver: &Receiver<Msg>, target_ns: i64) -> bool {
+    loop {
+        let remaining_ns = target_ns - get_time_ns();
+        if remaining_ns < SLEEP_THRESHOLD {
+            break;
+        }
+
+        // Sleep while waiting for messages, and reserve some time for busy waiting
+        match receiver.recv_timeout(Duration::from_nanos((remaining_ns - BUSYWAIT_MAX) as u64)) {
+            Ok(Msg::Stop) | Err(RecvTimeoutError::Disconnected) => return true,
+            Err(RecvTimeoutError::Timeout) => {}
+        }
+    }
+
+    // Busy-wait for the remaining time, minus BUSYWAIT_REMAINDER
+    // to account for get_time_ns() overhead
+    while target_ns - get_time_ns() > BUSYWAIT_REMAINDER {}
+
+    matches!(receiver.try_recv(), Ok(Msg::Stop))
+}
+
 #[inline(always)]
-fn get_time_ns() -> i64 {
+pub(crate) fn get_time_ns() -> i64 {
     let mut ts = timespec {
         tv_sec: 0,
         tv_nsec: 0,
@@ -191,3 +603,30 @@ fn get_time_ns() -> i64 {
 
     (ts.tv_sec as i64 * NANOS_PER_SEC) + ts.tv_nsec as i64
 }
+
+// Returns the average time spent in a single get_time_ns() call. The overhead is
+// measured once, and cached for subsequent calls.
+pub(crate) fn time_overhead_ns() -> i64 {
+    let overhead_ns = TIME_OVERHEAD_NS.load(Ordering::Relaxed);
+    if overhead_ns >= 0 {
+        return overhead_ns;
+    }
+
+    let start_ns = get_time_ns();
+    for _ in 0..CALIBRATION_SAMPLES {
+        get_time_ns();
+    }
+    let overhead_ns = (get_time_ns() - start_ns) / (CALIBRATION_SAMPLES + 1);
+
+    TIME_OVERHEAD_NS.store(overhead_ns, Ordering::Relaxed);
+
+    overhead_ns
+}
+
+// Busy-waits until the monotonic clock reaches target_ns.
+#[inline(always)]
+pub(crate) fn busy_wait_until_ns(target_ns: i64) {
+    let overhead_ns = time_overhead_ns();
+
+    while target_ns - get_time_ns() > overhead_ns {}
+}
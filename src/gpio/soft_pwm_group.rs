@@ -0,0 +1,333 @@
+use std::mem;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use super::soft_pwm::{get_time_ns, set_realtime_priority, wait_until_ns};
+use super::{Error, GpioState, Level, OutputPin, Result};
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+struct Channel {
+    duty_cycle: f64,
+    phase: f64,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+enum Msg {
+    Reconfigure(Duration, Vec<Channel>),
+    Stop,
+}
+
+// Pin state changes for a single period. The initial state is applied at the
+// start of each period, followed by the edges in chronological order.
+#[derive(Debug, Default)]
+struct Schedule {
+    period_ns: i64,
+    initial: Vec<(u8, Level)>,
+    edges: Vec<(i64, u8, Level)>,
+}
+
+impl Schedule {
+    fn new(pins: &[u8], period: Duration, channels: &[Channel]) -> Schedule {
+        let period_ns = period.as_nanos() as i64;
+        let mut initial = Vec::with_capacity(pins.len());
+        let mut edges = Vec::with_capacity(pins.len() * 2);
+
+        for (&pin, channel) in pins.iter().zip(channels.iter()) {
+            let pulse_width_ns = (period_ns as f64 * channel.duty_cycle) as i64;
+            let rise_ns = (period_ns as f64 * channel.phase) as i64 % period_ns.max(1);
+            let fall_ns = rise_ns + pulse_width_ns;
+
+            if pulse_width_ns <= 0 {
+                initial.push((pin, Level::Low));
+                continue;
+            } else if pulse_width_ns >= period_ns {
+                initial.push((pin, Level::High));
+                continue;
+            }
+
+            // The pin is active at the start of the period if the pulse starts at
+            // the beginning of the period, or wraps around from the previous period.
+            if rise_ns == 0 || fall_ns > period_ns {
+                initial.push((pin, Level::High));
+            } else {
+                initial.push((pin, Level::Low));
+            }
+
+            if rise_ns > 0 {
+                edges.push((rise_ns, pin, Level::High));
+            }
+
+            if fall_ns % period_ns > 0 {
+                edges.push((fall_ns % period_ns, pin, Level::Low));
+            }
+        }
+
+        edges.sort_by_key(|edge| edge.0);
+
+        Schedule {
+            period_ns,
+            initial,
+            edges,
+        }
+    }
+}
+
+/// Multiple software-based PWM signals that share a single period and thread.
+///
+/// A `SoftPwmGroup` emulates a PWM signal on each of its pins by toggling their
+/// output states on a single separate thread. All signals share the same period,
+/// while the duty cycle and phase offset can be configured for each pin (channel)
+/// individually. Channels are numbered in the order the pins were provided.
+///
+/// The phase offset delays the start of a channel's active pulse by a fraction of the
+/// period, which can be used to keep the rising edges of multiple signals from
+/// coinciding.
+///
+/// Software-based PWM is inherently inaccurate on a multi-threaded OS due to
+/// scheduling/preemption. If an accurate or faster PWM signal is required, use the
+/// hardware [`Pwm`] peripheral instead. More information can be found [here].
+///
+/// When the `SoftPwmGroup` goes out of scope, the PWM thread is stopped, and all pins
+/// are set to [`Low`].
+///
+/// [`Pwm`]: ../pwm/struct.Pwm.html
+/// [here]: index.html#software-based-pwm
+/// [`Low`]: enum.Level.html#variant.Low
+#[derive(Debug)]
+pub struct SoftPwmGroup {
+    pins: Vec<OutputPin>,
+    period: Duration,
+    channels: Vec<Channel>,
+    pwm_thread: Option<thread::JoinHandle<Result<()>>>,
+    sender: Sender<Msg>,
+}
+
+impl SoftPwmGroup {
+    /// Constructs a new `SoftPwmGroup` for the specified pins.
+    ///
+    /// `period` indicates the time it takes to complete one cycle. The duty cycle and
+    /// phase offset for each channel are initially set to `0.0`.
+    ///
+    /// Any software-based PWM signals previously configured on the individual pins
+    /// are stopped.
+    pub fn new(mut pins: Vec<OutputPin>, period: Duration) -> Result<SoftPwmGroup> {
+        for pin in pins.iter_mut() {
+            pin.clear_pwm()?;
+        }
+
+        let channels = vec![
+            Channel {
+                duty_cycle: 0.0,
+                phase: 0.0,
+            };
+            pins.len()
+        ];
+
+        let pin_numbers: Vec<u8> = pins.iter().map(|pin| pin.pin()).collect();
+        let gpio_state = pins.first().map(|pin| pin.pin.gpio_state.clone());
+
+        let (sender, receiver): (Sender<Msg>, Receiver<Msg>) = mpsc::channel();
+        let schedule = Schedule::new(&pin_numbers, period, &channels);
+
+        let pwm_thread = thread::spawn(move || -> Result<()> {
+            if let Some(gpio_state) = gpio_state {
+                run(&gpio_state, &pin_numbers, schedule, receiver);
+            }
+
+            Ok(())
+        });
+
+        Ok(SoftPwmGroup {
+            pins,
+            period,
+            channels,
+            pwm_thread: Some(pwm_thread),
+            sender,
+        })
+    }
+
+    /// Returns the number of channels in the group.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.pins.len()
+    }
+
+    /// Returns `true` if the group doesn't contain any channels.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.pins.is_empty()
+    }
+
+    /// Returns the period.
+    pub fn period(&self) -> Duration {
+        self.period
+    }
+
+    /// Sets the period for all channels.
+    ///
+    /// The PWM thread is reconfigured at the end of the current cycle.
+    pub fn set_period(&mut self, period: Duration) {
+        self.period = period;
+        self.reconfigure();
+    }
+
+    /// Sets the period for all channels by converting `frequency` to a period.
+    ///
+    /// `frequency` is specified in hertz (Hz).
+    pub fn set_frequency(&mut self, frequency: f64) {
+        let period = if frequency <= 0.0 {
+            Duration::from_nanos(0)
+        } else {
+            Duration::from_nanos((1.0 / frequency * 1_000_000_000.0) as u64)
+        };
+
+        self.set_period(period);
+    }
+
+    /// Returns the duty cycle for the specified channel, or `None` if the
+    /// channel doesn't exist.
+    pub fn duty_cycle(&self, channel: usize) -> Option<f64> {
+        self.channels.get(channel).map(|c| c.duty_cycle)
+    }
+
+    /// Sets the duty cycle for the specified channel.
+    ///
+    /// `duty_cycle` is specified as a floating point value between `0.0` (0%) and `1.0` (100%).
+    ///
+    /// The PWM thread is reconfigured at the end of the current cycle. If the channel
+    /// doesn't exist, `set_duty_cycle` has no effect.
+    pub fn set_duty_cycle(&mut self, channel: usize, duty_cycle: f64) {
+        if let Some(c) = self.channels.get_mut(channel) {
+            c.duty_cycle = duty_cycle.clamp(0.0, 1.0);
+            self.reconfigure();
+        }
+    }
+
+    /// Returns the phase offset for the specified channel, or `None` if the
+    /// channel doesn't exist.
+    pub fn phase(&self, channel: usize) -> Option<f64> {
+        self.channels.get(channel).map(|c| c.phase)
+    }
+
+    /// Sets the phase offset for the specified channel.
+    ///
+    /// `phase` is specified as a fraction of the period, as a floating point value
+    /// between `0.0` (no delay) and `1.0` (delayed by one full period).
+    ///
+    /// The PWM thread is reconfigured at the end of the current cycle. If the channel
+    /// doesn't exist, `set_phase` has no effect.
+    pub fn set_phase(&mut self, channel: usize, phase: f64) {
+        if let Some(c) = self.channels.get_mut(channel) {
+            c.phase = phase.clamp(0.0, 1.0);
+            self.reconfigure();
+        }
+    }
+
+    /// Stops the PWM thread, and returns the individual [`OutputPin`]s, in channel order.
+    ///
+    /// [`OutputPin`]: struct.OutputPin.html
+    pub fn into_pins(mut self) -> Result<Vec<OutputPin>> {
+        self.stop()?;
+
+        Ok(mem::take(&mut self.pins))
+    }
+
+    fn reconfigure(&mut self) {
+        let _ = self
+            .sender
+            .send(Msg::Reconfigure(self.period, self.channels.clone()));
+    }
+
+    fn stop(&mut self) -> Result<()> {
+        let _ = self.sender.send(Msg::Stop);
+        if let Some(pwm_thread) = self.pwm_thread.take() {
+            match pwm_thread.join() {
+                Ok(r) => return r,
+                Err(_) => return Err(Error::ThreadPanic),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Drop for SoftPwmGroup {
+    fn drop(&mut self) {
+        // Don't wait for the pwm thread to exit if the main thread is panicking,
+        // because we could potentially block indefinitely while unwinding if the
+        // pwm thread doesn't respond to the Stop message for some reason.
+        if !thread::panicking() {
+            let _ = self.stop();
+        }
+    }
+}
+
+// Required because Sender isn't Sync. Implementing Sync for SoftPwmGroup is
+// safe because all usage of Sender::send() is locked behind &mut self.
+unsafe impl Sync for SoftPwmGroup {}
+
+fn run(gpio_state: &Arc<GpioState>, pins: &[u8], mut schedule: Schedule, receiver: Receiver<Msg>) {
+    set_realtime_priority();
+
+    let mut start_ns = get_time_ns();
+
+    loop {
+        for &(pin, level) in &schedule.initial {
+            write(gpio_state, pin, level);
+        }
+
+        for &(offset_ns, pin, level) in &schedule.edges {
+            wait_until_ns(start_ns + offset_ns);
+            write(gpio_state, pin, level);
+        }
+
+        while let Ok(msg) = receiver.try_recv() {
+            match msg {
+                Msg::Reconfigure(period, channels) => {
+                    schedule = Schedule::new(pins, period, &channels);
+                }
+                Msg::Stop => {
+                    // The main thread asked us to stop
+                    for &pin in pins {
+                        gpio_state.gpio_mem.set_low(pin);
+                    }
+
+                    return;
+                }
+            }
+        }
+
+        // Without any edges, there's nothing to do until we're reconfigured or stopped
+        if schedule.edges.is_empty() {
+            // A schedule received through try_recv() hasn't been applied yet
+            for &(pin, level) in &schedule.initial {
+                write(gpio_state, pin, level);
+            }
+
+            if let Ok(Msg::Reconfigure(period, channels)) = receiver.recv() {
+                schedule = Schedule::new(pins, period, &channels);
+                start_ns = get_time_ns();
+                continue;
+            }
+
+            for &pin in pins {
+                gpio_state.gpio_mem.set_low(pin);
+            }
+
+            return;
+        }
+
+        start_ns += schedule.period_ns;
+        wait_until_ns(start_ns);
+    }
+}
+
+#[inline(always)]
+fn write(gpio_state: &GpioState, pin: u8, level: Level) {
+    match level {
+        Level::Low => gpio_state.gpio_mem.set_low(pin),
+        Level::High => gpio_state.gpio_mem.set_high(pin),
+    }
+}
@@ -0,0 +1,324 @@
+use std::fmt;
+use std::io;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use super::{Error, Level, OutputPin, Result};
+
+// Coil activation sequences, starting with the first coil in the least significant bit
+const SEQUENCE_FULL: [u8; 4] = [0b0011, 0b0110, 0b1100, 0b1001];
+const SEQUENCE_HALF: [u8; 8] = [
+    0b0001, 0b0011, 0b0010, 0b0110, 0b0100, 0b1100, 0b1000, 0b1001,
+];
+
+// Minimum step pulse width. A4988 and DRV8825 drivers require 1-2 µs.
+const STEP_PULSE: Duration = Duration::from_micros(2);
+// Minimum delay between a direction change and the next step pulse
+const DIR_SETUP: Duration = Duration::from_micros(1);
+
+const DEFAULT_SPEED: f64 = 100.0;
+// Minimum speed in steps per second (one step per day), which keeps the step interval
+// within the range of Duration and Instant
+const MIN_SPEED: f64 = 1.0 / 86_400.0;
+
+/// Stepper motor step sequences.
+///
+/// The step mode only applies to motors driven directly through four coil pins. For
+/// step/dir drivers, microstepping is configured on the driver itself.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum StepMode {
+    /// Energizes two coils at a time, which provides the most torque.
+    Full,
+    /// Alternates between energizing one and two coils, which doubles the number of steps
+    /// per revolution, and results in smoother movement.
+    Half,
+}
+
+impl StepMode {
+    fn sequence(self) -> &'static [u8] {
+        match self {
+            StepMode::Full => &SEQUENCE_FULL,
+            StepMode::Half => &SEQUENCE_HALF,
+        }
+    }
+}
+
+impl fmt::Display for StepMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            StepMode::Full => write!(f, "Full"),
+            StepMode::Half => write!(f, "Half"),
+        }
+    }
+}
+
+#[derive(Debug)]
+enum Pins {
+    Coils([OutputPin; 4], StepMode),
+    StepDir(OutputPin, OutputPin),
+}
+
+#[derive(Debug)]
+struct Driver {
+    pins: Pins,
+    // Index into the step sequence
+    phase: usize,
+    // Current direction for step/dir drivers
+    forward: Option<bool>,
+    position: i64,
+}
+
+impl Driver {
+    fn step(&mut self, forward: bool) {
+        match self.pins {
+            Pins::Coils(ref mut coils, mode) => {
+                let sequence = mode.sequence();
+                self.phase = if forward {
+                    (self.phase + 1) % sequence.len()
+                } else {
+                    (self.phase + sequence.len() - 1) % sequence.len()
+                };
+
+                for (idx, coil) in coils.iter_mut().enumerate() {
+                    coil.write(Level::from(sequence[self.phase] & (1 << idx) > 0));
+                }
+            }
+            Pins::StepDir(ref mut step, ref mut dir) => {
+                if self.forward != Some(forward) {
+                    dir.write(Level::from(forward));
+                    self.forward = Some(forward);
+                    thread::sleep(DIR_SETUP);
+                }
+
+                step.set_high();
+                thread::sleep(STEP_PULSE);
+                step.set_low();
+            }
+        }
+
+        self.position += if forward { 1 } else { -1 };
+    }
+
+    fn disable(&mut self) {
+        if let Pins::Coils(ref mut coils, _) = self.pins {
+            for coil in coils.iter_mut() {
+                coil.set_low();
+            }
+        }
+    }
+}
+
+// Moves the motor by count steps, while sleeping in between steps to maintain the
+// specified interval. Returns early if stop is set.
+fn run_steps(driver: &Mutex<Driver>, count: i32, interval: Duration, stop: &AtomicBool) {
+    let forward = count >= 0;
+    let mut next_step = Instant::now();
+
+    for _ in 0..count.unsigned_abs() {
+        if stop.load(Ordering::SeqCst) {
+            break;
+        }
+
+        let now = Instant::now();
+        if next_step > now {
+            thread::sleep(next_step - now);
+        }
+
+        driver.lock().unwrap().step(forward);
+
+        // Schedule steps relative to the previous step, so any delays don't add up
+        next_step += interval;
+    }
+}
+
+/// Stepper motor driver.
+///
+/// `Stepper` supports unipolar motors driven through four coil pins, such as the
+/// 28BYJ-48 with a ULN2003 driver board, and stepper drivers with a step/dir interface,
+/// such as the A4988 or DRV8825.
+///
+/// [`step`] moves the motor and blocks until all steps have been completed. [`run`]
+/// moves the motor on a separate thread, and returns immediately.
+///
+/// Steps are timed using `thread::sleep`, which is subject to scheduling delays. Small
+/// delays are compensated for during subsequent steps. At high speeds, missing a step
+/// interval may cause the motor to stall.
+///
+/// [`step`]: #method.step
+/// [`run`]: #method.run
+#[derive(Debug)]
+pub struct Stepper {
+    driver: Arc<Mutex<Driver>>,
+    speed: f64,
+    run_thread: Option<thread::JoinHandle<()>>,
+    running: Arc<AtomicBool>,
+    stop: Arc<AtomicBool>,
+}
+
+impl Stepper {
+    /// Constructs a new `Stepper` for a motor driven through four coil pins.
+    ///
+    /// `coils` lists the pins connected to the driver inputs in the order they should
+    /// be energized. For a ULN2003 driver board, these are IN1-IN4. `mode` selects
+    /// the step sequence.
+    pub fn new(coils: [OutputPin; 4], mode: StepMode) -> Stepper {
+        Stepper::with_pins(Pins::Coils(coils, mode))
+    }
+
+    /// Constructs a new `Stepper` for a stepper driver with a step/dir interface.
+    ///
+    /// Each rising edge on `step` moves the motor by a single (micro)step. `dir`
+    /// is set high for forward steps, and low for reverse steps.
+    pub fn with_step_dir(step: OutputPin, dir: OutputPin) -> Stepper {
+        Stepper::with_pins(Pins::StepDir(step, dir))
+    }
+
+    fn with_pins(pins: Pins) -> Stepper {
+        Stepper {
+            driver: Arc::new(Mutex::new(Driver {
+                pins,
+                phase: 0,
+                forward: None,
+                position: 0,
+            })),
+            speed: DEFAULT_SPEED,
+            run_thread: None,
+            running: Arc::new(AtomicBool::new(false)),
+            stop: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Returns the speed in steps per second.
+    pub fn speed(&self) -> f64 {
+        self.speed
+    }
+
+    /// Sets the speed in steps per second.
+    ///
+    /// The new speed applies to movements started after `set_speed` is called.
+    /// By default, `speed` is set to 100 steps per second.
+    ///
+    /// If `steps_per_second` isn't a positive number, or is slower than one step per
+    /// day, `set_speed` returns `Err(`[`Error::Io`]`)` with an
+    /// `io::ErrorKind::InvalidInput` error.
+    ///
+    /// [`Error::Io`]: enum.Error.html#variant.Io
+    pub fn set_speed(&mut self, steps_per_second: f64) -> Result<()> {
+        if !steps_per_second.is_finite() || steps_per_second <= 0.0 {
+            return Err(Error::Io(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "stepper speed must be a positive number",
+            )));
+        }
+
+        if steps_per_second < MIN_SPEED {
+            return Err(Error::Io(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "stepper speed must be at least one step per day",
+            )));
+        }
+
+        self.speed = steps_per_second;
+
+        Ok(())
+    }
+
+    /// Moves the motor by `count` steps, and blocks until all steps have been completed.
+    ///
+    /// Positive values of `count` move the motor forward, negative values in reverse.
+    /// If a movement started with [`run`] is still in progress, `step` waits for it
+    /// to complete first.
+    ///
+    /// [`run`]: #method.run
+    pub fn step(&mut self, count: i32) {
+        self.wait();
+
+        run_steps(&self.driver, count, self.interval(), &self.stop);
+    }
+
+    /// Moves the motor by `count` steps on a separate thread, and returns immediately.
+    ///
+    /// Positive values of `count` move the motor forward, negative values in reverse.
+    /// Any movement previously started with `run` is stopped first. Use [`wait`] to
+    /// block until the movement has completed, or [`stop`] to stop it early.
+    ///
+    /// [`wait`]: #method.wait
+    /// [`stop`]: #method.stop
+    pub fn run(&mut self, count: i32) {
+        self.stop();
+
+        let driver = Arc::clone(&self.driver);
+        let running = Arc::clone(&self.running);
+        let stop = Arc::clone(&self.stop);
+        let interval = self.interval();
+
+        self.running.store(true, Ordering::SeqCst);
+        self.run_thread = Some(thread::spawn(move || {
+            run_steps(&driver, count, interval, &stop);
+            running.store(false, Ordering::SeqCst);
+        }));
+    }
+
+    /// Returns `true` if a movement started with [`run`] is still in progress.
+    ///
+    /// [`run`]: #method.run
+    pub fn is_running(&self) -> bool {
+        self.running.load(Ordering::SeqCst)
+    }
+
+    /// Blocks until a movement started with [`run`] has completed.
+    ///
+    /// [`run`]: #method.run
+    pub fn wait(&mut self) {
+        if let Some(run_thread) = self.run_thread.take() {
+            let _ = run_thread.join();
+        }
+    }
+
+    /// Stops a movement started with [`run`] after the current step.
+    ///
+    /// [`run`]: #method.run
+    pub fn stop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        self.wait();
+        self.stop.store(false, Ordering::SeqCst);
+    }
+
+    /// Returns the number of steps moved since the `Stepper` was constructed, or since
+    /// the position was last reset.
+    ///
+    /// Forward steps increase the position, reverse steps decrease it.
+    pub fn position(&self) -> i64 {
+        self.driver.lock().unwrap().position
+    }
+
+    /// Resets the position to `0`.
+    pub fn reset_position(&mut self) {
+        self.driver.lock().unwrap().position = 0;
+    }
+
+    /// De-energizes all coils, which lets the motor turn freely and reduces power
+    /// consumption and heat.
+    ///
+    /// The motor loses its holding torque until the next step. `disable` has no effect
+    /// on step/dir drivers, which provide a separate enable pin.
+    pub fn disable(&mut self) {
+        self.stop();
+        self.driver.lock().unwrap().disable();
+    }
+
+    fn interval(&self) -> Duration {
+        Duration::from_secs_f64(1.0 / self.speed)
+    }
+}
+
+impl Drop for Stepper {
+    fn drop(&mut self) {
+        // Don't wait for the run thread if the main thread is panicking
+        if !thread::panicking() {
+            self.stop();
+        }
+    }
+}
@@ -79,6 +79,20 @@
 //! clock frequency in hertz (Hz). Remember to reboot
 //! the Raspberry Pi afterwards.
 //!
+//! ## Blocking behavior
+//!
+//! All I2C and SMBus transactions block the calling thread until the transaction
+//! has completed, or until the timeout set with [`set_timeout`] elapses. `i2cdev` doesn't
+//! support non-blocking I/O, so opening the device in non-blocking mode wouldn't have
+//! any effect. At 100 kHz, each byte (including the address byte) takes about 90 µs to
+//! transfer, in addition to a fixed overhead per call for the system call and driver
+//! setup. Slave devices that use clock stretching can extend the duration further.
+//!
+//! If your application uses a single-threaded event loop, consider moving I2C transactions
+//! to a separate thread, and reporting the results back through a channel. Note that
+//! an `io::ErrorKind::WouldBlock` (`EAGAIN`) error returned by the underlying driver
+//! indicates lost bus arbitration, rather than a transaction that's still in progress.
+//!
 //! ## Not supported
 //!
 //! Some I2C and SMBus features aren't fully supported by the `i2cdev` interface, the underlying driver or
@@ -97,10 +111,21 @@
 //!
 //! ### Permission denied
 //!
-//! If [`new`] or [`with_bus`] returns an `io::ErrorKind::PermissionDenied`
-//! error, make sure the file permissions for `/dev/i2c-1` or `/dev/i2c-0`
+//! If [`new`] or [`with_bus`] returns `Err(`[`Error::PermissionDenied`]`)`,
+//! make sure the file permissions for `/dev/i2c-1` or `/dev/i2c-0`
 //! are correct, and the current user is a member of the `i2c` group.
 //!
+//! ### Not enabled
+//!
+//! If [`new`] or [`with_bus`] returns `Err(`[`Error::NotEnabled`]`)`, the
+//! device node for the selected bus doesn't exist. Make sure the I2C bus has
+//! been enabled as described above, for instance through `sudo raspi-config`.
+//!
+//! ### Busy
+//!
+//! If [`new`] or [`with_bus`] returns `Err(`[`Error::Busy`]`)`, the bus is
+//! already in use by a kernel driver.
+//!
 //! ### Timed out
 //!
 //! Transactions return an `io::ErrorKind::TimedOut` error when their duration
@@ -109,33 +134,51 @@
 //! [`new`]: struct.I2c.html#method.new
 //! [`with_bus`]: struct.I2c.html#method.with_bus
 //! [`set_timeout`]: struct.I2c.html#method.set_timeout
+//! [`Error::PermissionDenied`]: enum.Error.html#variant.PermissionDenied
+//! [`Error::NotEnabled`]: enum.Error.html#variant.NotEnabled
+//! [`Error::Busy`]: enum.Error.html#variant.Busy
 
 #![allow(dead_code)]
 
+use std::cell::Cell;
 use std::error;
 use std::fmt;
 use std::fs::{File, OpenOptions};
 use std::io;
 use std::io::{Read, Write};
 use std::marker::PhantomData;
-use std::os::unix::io::AsRawFd;
+use std::os::unix::fs::MetadataExt;
+use std::os::unix::io::{AsRawFd, RawFd};
+use std::path::{Path, PathBuf};
 use std::result;
+use std::thread;
+use std::time::Duration;
 
 use libc::c_ulong;
 
 use crate::system;
 use crate::system::{DeviceInfo, Model};
 
+mod bsc;
+mod crc;
+mod eeprom;
 #[cfg(any(
     feature = "embedded-hal-0",
     feature = "embedded-hal",
     feature = "embedded-hal-nb"
 ))]
 mod hal;
+mod hat;
 mod ioctl;
 
+pub use self::crc::{crc16_arc, crc8_sensirion, crc8_smbus};
+pub use self::eeprom::{Eeprom24x, EepromAddressWidth};
+pub use self::hat::{HatEeprom, HatGpioMap, HatPin, HatVendorInfo};
 pub use self::ioctl::Capabilities;
 
+// I2C_TIMEOUT is specified in units of 10 ms
+const TIMEOUT_UNIT_NS: u128 = 10_000_000;
+
 /// Errors that can occur when accessing the I2C peripheral.
 #[derive(Debug)]
 pub enum Error {
@@ -150,6 +193,19 @@ pub enum Error {
     ///
     /// The underlying drivers don't support the selected I2C feature or SMBus protocol.
     FeatureNotSupported,
+    /// I2C bus not enabled.
+    ///
+    /// The I2C device node doesn't exist. Make sure the I2C bus has been enabled.
+    /// More information can be found [here](index.html).
+    NotEnabled,
+    /// Permission denied.
+    ///
+    /// The current user doesn't have the required permissions to access the I2C device node.
+    PermissionDenied,
+    /// I2C bus busy.
+    ///
+    /// The I2C bus is already in use by another driver or process.
+    Busy,
     /// Unknown model.
     ///
     /// The Raspberry Pi model or SoC can't be identified. Support for
@@ -169,12 +225,22 @@ impl fmt::Display for Error {
             Error::Io(ref err) => write!(f, "I/O error: {}", err),
             Error::InvalidSlaveAddress(address) => write!(f, "Invalid slave address: {}", address),
             Error::FeatureNotSupported => write!(f, "I2C/SMBus feature not supported"),
+            Error::NotEnabled => write!(f, "I2C bus not enabled"),
+            Error::PermissionDenied => write!(f, "Permission denied"),
+            Error::Busy => write!(f, "I2C bus busy"),
             Error::UnknownModel => write!(f, "Unknown Raspberry Pi model"),
         }
     }
 }
 
-impl error::Error for Error {}
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            Error::Io(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
 
 impl From<io::Error> for Error {
     fn from(err: io::Error) -> Error {
@@ -188,9 +254,46 @@ impl From<system::Error> for Error {
     }
 }
 
+// Converts an error returned while opening the I2C device node into a more specific error.
+fn open_error(err: io::Error) -> Error {
+    match err.kind() {
+        io::ErrorKind::NotFound => Error::NotEnabled,
+        io::ErrorKind::PermissionDenied => Error::PermissionDenied,
+        _ if err.raw_os_error() == Some(libc::EBUSY) => Error::Busy,
+        _ => Error::Io(err),
+    }
+}
+
+// Returns true if the error was caused by a condition that might not occur again
+// when the transaction is repeated.
+fn is_transient(err: &io::Error) -> bool {
+    matches!(
+        err.raw_os_error(),
+        Some(libc::ENXIO) | Some(libc::EREMOTEIO) | Some(libc::EAGAIN) | Some(libc::ETIMEDOUT)
+    )
+}
+
 /// Result type returned from methods that can have `i2c::Error`s.
 pub type Result<T> = result::Result<T, Error>;
 
+/// Byte orders for multi-byte register values.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Endian {
+    /// The most significant byte is transferred first.
+    Big,
+    /// The least significant byte is transferred first.
+    Little,
+}
+
+impl fmt::Display for Endian {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Endian::Big => write!(f, "Big"),
+            Endian::Little => write!(f, "Little"),
+        }
+    }
+}
+
 /// Provides access to the Raspberry Pi's I2C peripheral.
 ///
 /// Before using `I2c`, make sure your Raspberry Pi has the necessary I2C buses
@@ -212,8 +315,11 @@ pub struct I2c {
     bus: u8,
     funcs: Capabilities,
     i2cdev: File,
+    // Device node path, used by try_clone
+    path: PathBuf,
     addr_10bit: bool,
     address: u16,
+    pec: Cell<bool>,
     // The not_sync field is a workaround to force !Sync. I2c isn't safe for
     // Sync because of ioctl() and the underlying drivers. This avoids needing
     // #![feature(optin_builtin_traits)] to manually add impl !Sync for I2c.
@@ -256,11 +362,40 @@ impl I2c {
     pub fn with_bus(bus: u8) -> Result<I2c> {
         // bus is a u8, because any 8-bit bus ID could potentially
         // be configured for bit banging I2C using i2c-gpio.
+        let path = PathBuf::from(format!("/dev/i2c-{}", bus));
         let i2cdev = OpenOptions::new()
             .read(true)
             .write(true)
-            .open(format!("/dev/i2c-{}", bus))?;
+            .open(&path)
+            .map_err(open_error)?;
+
+        I2c::with_device(i2cdev, path, bus)
+    }
+
+    /// Constructs a new `I2c` using the I2C device node at the specified path.
+    ///
+    /// [`with_bus`] expects the device node for bus `n` at `/dev/i2c-<n>`.
+    /// `with_path` can be used in containers or on custom systems where the device
+    /// nodes are located elsewhere, or have been renamed. The bus ID returned by
+    /// [`bus`] is determined by the device node's minor number, which the kernel
+    /// assigns based on the I2C adapter number.
+    ///
+    /// [`with_bus`]: #method.with_bus
+    /// [`bus`]: #method.bus
+    pub fn with_path<P: AsRef<Path>>(path: P) -> Result<I2c> {
+        let path = path.as_ref().to_path_buf();
+        let i2cdev = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(&path)
+            .map_err(open_error)?;
+
+        let bus = libc::minor(i2cdev.metadata()?.rdev()) as u8;
+
+        I2c::with_device(i2cdev, path, bus)
+    }
 
+    fn with_device(i2cdev: File, path: PathBuf, bus: u8) -> Result<I2c> {
         let capabilities = ioctl::funcs(i2cdev.as_raw_fd())?;
 
         // Disable 10-bit addressing if it's supported
@@ -277,12 +412,50 @@ impl I2c {
             bus,
             funcs: capabilities,
             i2cdev,
+            path,
             addr_10bit: false,
             address: 0,
+            pec: Cell::new(false),
             not_sync: PhantomData,
         })
     }
 
+    /// Constructs a new `I2c` for the same bus, with the same slave address,
+    /// 10-bit addressing and Packet Error Checking settings.
+    ///
+    /// `I2c` can't be shared between threads. Instead of wrapping `I2c` in an
+    /// `Arc<Mutex<I2c>>`, `try_clone` can be used to give each thread its own
+    /// instance. Each instance opens the I2C device node separately, so changing the
+    /// slave address or any of the other settings mentioned above on one instance
+    /// doesn't affect the others. Transactions from different instances are
+    /// serialized by the kernel, so they never interleave on the bus. Settings that
+    /// apply to the entire bus, such as the [`timeout`], are shared.
+    ///
+    /// [`timeout`]: #method.set_timeout
+    pub fn try_clone(&self) -> Result<I2c> {
+        let i2cdev = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(&self.path)
+            .map_err(open_error)?;
+
+        let mut i2c = I2c::with_device(i2cdev, self.path.clone(), self.bus)?;
+
+        if self.addr_10bit {
+            i2c.set_addr_10bit(true)?;
+        }
+
+        if self.address != 0 {
+            i2c.set_slave_address(self.address)?;
+        }
+
+        if self.pec.get() {
+            i2c.set_smbus_pec(true)?;
+        }
+
+        Ok(i2c)
+    }
+
     /// Returns information on the functionality supported by the underlying drivers.
     ///
     /// The returned [`Capabilities`] instance lists the available
@@ -299,19 +472,40 @@ impl I2c {
     }
 
     /// Returns the clock frequency in hertz (Hz).
+    ///
+    /// `clock_speed` reads the bus speed the I2C driver was configured with from the
+    /// `clock-frequency` device tree property, which is available in
+    /// `/sys/bus/i2c/devices/i2c-N/of_node/` on most kernels.
+    ///
+    /// If the device tree doesn't specify a clock frequency, `clock_speed` calculates the
+    /// frequency based on the clock divider the driver programmed into the BSC
+    /// controller's `DIV` register, and the rate of the core clock. This requires
+    /// superuser privileges to access `/dev/mem` and `/sys/kernel/debug`, and isn't
+    /// supported on the Raspberry Pi 5. Because the frequency is derived from an integer
+    /// divider, it may differ slightly from the configured bus speed.
     pub fn clock_speed(&self) -> Result<u32> {
-        let mut buffer = [0u8; 4];
-
-        File::open(format!(
-            "/sys/class/i2c-adapter/i2c-{}/of_node/clock-frequency",
-            self.bus
-        ))?
-        .read_exact(&mut buffer)?;
+        let paths = [
+            format!(
+                "/sys/bus/i2c/devices/i2c-{}/of_node/clock-frequency",
+                self.bus
+            ),
+            format!(
+                "/sys/class/i2c-adapter/i2c-{}/of_node/clock-frequency",
+                self.bus
+            ),
+        ];
+
+        for path in paths.iter() {
+            let mut buffer = [0u8; 4];
+
+            if let Ok(mut file) = File::open(path) {
+                file.read_exact(&mut buffer)?;
+
+                return Ok(u32::from_be_bytes(buffer));
+            }
+        }
 
-        Ok(u32::from(buffer[3])
-            | (u32::from(buffer[2]) << 8)
-            | (u32::from(buffer[1]) << 16)
-            | (u32::from(buffer[0]) << 24))
+        bsc::Bsc::open(self.bus)?.clock_speed()
     }
 
     /// Sets a 7-bit or 10-bit slave address.
@@ -327,41 +521,181 @@ impl I2c {
     ///
     /// [`set_addr_10bit`]: #method.set_addr_10bit
     pub fn set_slave_address(&mut self, slave_address: u16) -> Result<()> {
-        // Filter out invalid and unsupported addresses
+        self.check_slave_address(slave_address)?;
+
+        ioctl::set_slave_address(self.i2cdev.as_raw_fd(), c_ulong::from(slave_address))?;
+
+        self.address = slave_address;
+
+        Ok(())
+    }
+
+    // Filters out invalid and unsupported addresses
+    fn check_slave_address(&self, slave_address: u16) -> Result<()> {
         if (!self.addr_10bit && ((slave_address >> 3) == 0b1111 || slave_address > 0x7F))
             || (self.addr_10bit && slave_address > 0x03FF)
         {
             return Err(Error::InvalidSlaveAddress(slave_address));
         }
 
-        ioctl::set_slave_address(self.i2cdev.as_raw_fd(), c_ulong::from(slave_address))?;
-
-        self.address = slave_address;
-
         Ok(())
     }
 
-    /// Sets the maximum duration of a transaction in milliseconds (ms).
+    /// Sets the maximum duration of a transaction.
     ///
     /// Transactions that take longer than `timeout` return an
-    /// `io::ErrorKind::TimedOut` error.
-    ///
-    /// `timeout` has a resolution of 10ms.
-    pub fn set_timeout(&self, timeout: u32) -> Result<()> {
+    /// `io::ErrorKind::TimedOut` error. A slave device that holds SCL low would
+    /// otherwise block the calling thread until the driver's default timeout elapses.
+    ///
+    /// The `I2C_TIMEOUT` setting is specified in units of 10 ms, so `timeout` is
+    /// rounded up to the next multiple of 10 ms. The kernel converts the value to
+    /// jiffies, which may further reduce the resolution depending on the kernel's
+    /// tick rate. `set_timeout` configures the I2C adapter, so the timeout is shared
+    /// by all processes using the same bus.
+    pub fn set_timeout(&self, timeout: Duration) -> Result<()> {
         // Contrary to the i2cdev documentation, this seems to
         // be used as a timeout for (part of?) the I2C transaction.
-        ioctl::set_timeout(self.i2cdev.as_raw_fd(), timeout as c_ulong)?;
+        let units = (timeout.as_nanos() + TIMEOUT_UNIT_NS - 1) / TIMEOUT_UNIT_NS;
+        let units = units.min(i32::MAX as u128) as c_ulong;
+
+        ioctl::set_timeout(self.i2cdev.as_raw_fd(), units)?;
 
         Ok(())
     }
 
-    fn set_retries(&self, retries: u32) -> Result<()> {
-        // Set to private. While i2cdev implements retries, the underlying drivers don't.
+    /// Returns the clock stretch timeout of the BSC controller.
+    ///
+    /// More information can be found in the documentation for [`set_clock_stretch_timeout`].
+    ///
+    /// [`set_clock_stretch_timeout`]: #method.set_clock_stretch_timeout
+    pub fn clock_stretch_timeout(&self) -> Result<Duration> {
+        let cycles = bsc::Bsc::open(self.bus)?.clock_stretch_timeout();
+
+        Ok(Duration::from_nanos(
+            u64::from(cycles) * 1_000_000_000 / u64::from(self.clock_speed()?.max(1)),
+        ))
+    }
+
+    /// Sets the maximum duration a slave device is allowed to stretch the clock.
+    ///
+    /// Slave devices can hold SCL low to delay a transfer, for instance while a sensor
+    /// completes a conversion. If SCL is held low for longer than the clock stretch
+    /// timeout, the BSC controller aborts the transaction, which returns an
+    /// `Err(`[`Error::Io`]`)`. Unlike [`set_timeout`], which limits the
+    /// duration of an entire transaction, the clock stretch timeout applies to each
+    /// individual clock stretch.
+    ///
+    /// The BSC controller's `CLKT` register specifies the timeout in SCL clock cycles.
+    /// `timeout` is converted based on the current [`clock_speed`], and rounded up to the
+    /// next cycle. The `CLKT` register is 16 bits wide, which limits the timeout to 65535
+    /// cycles, or about 655 ms at 100 kbit/s. If `timeout` exceeds the maximum,
+    /// `set_clock_stretch_timeout` returns `Err(`[`Error::Io`]`)` with an
+    /// `io::ErrorKind::InvalidInput` error. Setting `timeout` to `Duration::ZERO`
+    /// disables the timeout, in which case a slave device can block the bus indefinitely.
+    ///
+    /// The hardware reset value is 64 cycles. The Linux driver sets the timeout to 35 ms
+    /// when it configures the clock speed, as recommended by the SMBus specification. The
+    /// new timeout remains in effect until the driver is reloaded, and is shared by all
+    /// processes using the same bus.
+    ///
+    /// ## Note
+    ///
+    /// The `CLKT` register isn't exposed through `i2cdev`, so it's accessed directly
+    /// through `/dev/mem`, which requires superuser privileges. The clock stretch timeout
+    /// is only available for the BSC controllers that are bound to bus 0 and bus 1 on
+    /// the BCM283x, and bus 0, 1 and 3-6 on the BCM2711. For other buses, or on the
+    /// Raspberry Pi 5, `set_clock_stretch_timeout` returns
+    /// `Err(`[`Error::FeatureNotSupported`]`)`.
+    ///
+    /// [`set_timeout`]: #method.set_timeout
+    /// [`clock_speed`]: #method.clock_speed
+    /// [`Error::Io`]: enum.Error.html#variant.Io
+    /// [`Error::FeatureNotSupported`]: enum.Error.html#variant.FeatureNotSupported
+    pub fn set_clock_stretch_timeout(&self, timeout: Duration) -> Result<()> {
+        let cycles =
+            (timeout.as_nanos() * u128::from(self.clock_speed()?) + 999_999_999) / 1_000_000_000;
+
+        if cycles > u128::from(bsc::CLKT_TOUT_MASK) {
+            return Err(Error::Io(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "clock stretch timeout out of range",
+            )));
+        }
+
+        bsc::Bsc::open(self.bus)?.set_clock_stretch_timeout(cycles as u32);
+
+        Ok(())
+    }
+
+    /// Sets the number of times the kernel retries a transaction after losing arbitration.
+    ///
+    /// `set_retries` configures the `I2C_RETRIES` setting of the I2C adapter, which
+    /// is shared by all processes using the same bus. The kernel only retries transactions
+    /// when the underlying driver reports arbitration loss (`EAGAIN`), which doesn't apply
+    /// to most single-master buses. Not all drivers report arbitration loss, in which case
+    /// `set_retries` has no effect. Transactions that fail because the slave device didn't
+    /// acknowledge its address or data aren't retried.
+    ///
+    /// To retry failed transactions regardless of the underlying driver, use [`retry`].
+    ///
+    /// [`retry`]: #method.retry
+    pub fn set_retries(&self, retries: u32) -> Result<()> {
         ioctl::set_retries(self.i2cdev.as_raw_fd(), retries as c_ulong)?;
 
         Ok(())
     }
 
+    /// Calls `f`, and retries the call in case of a transient error.
+    ///
+    /// `retry` is a convenience method for unreliable buses, for instance when using long
+    /// wires or electrically noisy environments. `f` is called up to `attempts` times, until
+    /// it returns `Ok`, or returns an error that isn't considered transient. The returned
+    /// value is the result of the last call.
+    ///
+    /// The following errors are considered transient: the slave device didn't acknowledge
+    /// its address (`ENXIO`) or data (`EREMOTEIO`), arbitration was lost (`EAGAIN`), or the
+    /// transaction timed out (`ETIMEDOUT`).
+    ///
+    /// `delay` indicates how long to wait before the first retry. The delay is doubled for
+    /// each subsequent retry.
+    ///
+    /// ## Note
+    ///
+    /// Only retry transactions that can safely be repeated. A write that failed partway
+    /// through may have already been processed by the slave device.
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// # use std::time::Duration;
+    /// # use rppal::i2c::I2c;
+    /// # fn main() -> Result<(), rppal::i2c::Error> {
+    /// let mut i2c = I2c::new()?;
+    /// i2c.set_slave_address(0x40)?;
+    ///
+    /// let value = i2c.retry(3, Duration::from_millis(5), |i2c| i2c.smbus_read_byte(0x00))?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn retry<T, F>(&mut self, attempts: u32, delay: Duration, mut f: F) -> Result<T>
+    where
+        F: FnMut(&mut I2c) -> Result<T>,
+    {
+        let mut delay = delay;
+        let mut attempt = 1;
+
+        loop {
+            match f(self) {
+                Err(Error::Io(ref e)) if attempt < attempts && is_transient(e) => {
+                    thread::sleep(delay);
+                    delay = delay.saturating_mul(2);
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
     /// Enables or disables 10-bit addressing.
     ///
     /// 10-bit addressing currently isn't supported on the Raspberry Pi. `set_addr_10bit` returns
@@ -429,6 +763,213 @@ impl I2c {
         Ok(())
     }
 
+    /// Receives incoming data from the slave device at `slave_address` and writes it
+    /// to `buffer`.
+    ///
+    /// Unlike [`read`], `read_from` specifies the slave address as part of the
+    /// transfer, using the `I2C_RDWR` ioctl, rather than using the address configured
+    /// with [`set_slave_address`]. This avoids an additional system call when
+    /// communicating with multiple slave devices on the same bus. The address
+    /// configured with [`set_slave_address`] isn't changed.
+    ///
+    /// `read_from` reads as many bytes as can fit in `buffer`, up to a maximum of 8192.
+    /// If `slave_address` isn't valid, `read_from` returns
+    /// `Err(`[`Error::InvalidSlaveAddress`]`)`.
+    ///
+    /// Sequence: START → Address + Read Bit → Incoming Bytes → STOP
+    ///
+    /// [`read`]: #method.read
+    /// [`set_slave_address`]: #method.set_slave_address
+    /// [`Error::InvalidSlaveAddress`]: enum.Error.html#variant.InvalidSlaveAddress
+    pub fn read_from(&self, slave_address: u16, buffer: &mut [u8]) -> Result<()> {
+        self.check_slave_address(slave_address)?;
+
+        ioctl::i2c_read(
+            self.i2cdev.as_raw_fd(),
+            slave_address,
+            self.addr_10bit,
+            buffer,
+        )?;
+
+        Ok(())
+    }
+
+    /// Sends the outgoing data contained in `buffer` to the slave device at
+    /// `slave_address`.
+    ///
+    /// Unlike [`write`], `write_to` specifies the slave address as part of the
+    /// transfer. More information can be found at [`read_from`].
+    ///
+    /// The maximum number of bytes in `buffer` can't exceed 8192. If `slave_address`
+    /// isn't valid, `write_to` returns `Err(`[`Error::InvalidSlaveAddress`]`)`.
+    ///
+    /// Sequence: START → Address + Write Bit → Outgoing Bytes → STOP
+    ///
+    /// [`write`]: #method.write
+    /// [`read_from`]: #method.read_from
+    /// [`Error::InvalidSlaveAddress`]: enum.Error.html#variant.InvalidSlaveAddress
+    pub fn write_to(&self, slave_address: u16, buffer: &[u8]) -> Result<()> {
+        self.check_slave_address(slave_address)?;
+
+        ioctl::i2c_write(
+            self.i2cdev.as_raw_fd(),
+            slave_address,
+            self.addr_10bit,
+            buffer,
+        )?;
+
+        Ok(())
+    }
+
+    /// Sends the outgoing data contained in `write_buffer` to the slave device at
+    /// `slave_address`, and then fills `read_buffer` with incoming data.
+    ///
+    /// Unlike [`write_read`], `write_read_from` specifies the slave address as part
+    /// of the transfer. More information can be found at [`read_from`].
+    ///
+    /// The maximum number of bytes in either `write_buffer` or `read_buffer` can't
+    /// exceed 8192. If `slave_address` isn't valid, `write_read_from` returns
+    /// `Err(`[`Error::InvalidSlaveAddress`]`)`.
+    ///
+    /// Sequence: START → Address + Write Bit → Outgoing Bytes → Repeated START →
+    /// Address + Read Bit → Incoming Bytes → STOP
+    ///
+    /// [`write_read`]: #method.write_read
+    /// [`read_from`]: #method.read_from
+    /// [`Error::InvalidSlaveAddress`]: enum.Error.html#variant.InvalidSlaveAddress
+    pub fn write_read_from(
+        &self,
+        slave_address: u16,
+        write_buffer: &[u8],
+        read_buffer: &mut [u8],
+    ) -> Result<()> {
+        self.check_slave_address(slave_address)?;
+
+        ioctl::i2c_write_read(
+            self.i2cdev.as_raw_fd(),
+            slave_address,
+            self.addr_10bit,
+            write_buffer,
+            read_buffer,
+        )?;
+
+        Ok(())
+    }
+
+    /// Reads a single byte from the slave device's 8-bit register `register`.
+    ///
+    /// `read_register` is a convenience method that sends the register address, and then
+    /// reads the register's value after a repeated START, using [`write_read`].
+    ///
+    /// Sequence: START → Address + Write Bit → Register → Repeated START →
+    /// Address + Read Bit → Incoming Byte → STOP
+    ///
+    /// [`write_read`]: #method.write_read
+    pub fn read_register(&self, register: u8) -> Result<u8> {
+        let mut buffer = [0u8; 1];
+        self.write_read(&[register], &mut buffer)?;
+
+        Ok(buffer[0])
+    }
+
+    /// Fills `buffer` with data read from the slave device, starting at its 8-bit
+    /// register `register`.
+    ///
+    /// `read_registers` sends the register address, and then reads as many bytes as can
+    /// fit in `buffer` after a repeated START, using [`write_read`]. Most devices
+    /// automatically increment their internal register pointer after each byte, so
+    /// `buffer` is filled with the values of consecutive registers. Some devices only
+    /// auto-increment when a specific bit in the register address or a configuration
+    /// register is set, and keep returning the same register otherwise. Check the
+    /// device's datasheet for details.
+    ///
+    /// Sequence: START → Address + Write Bit → Register → Repeated START →
+    /// Address + Read Bit → Incoming Bytes → STOP
+    ///
+    /// [`write_read`]: #method.write_read
+    pub fn read_registers(&self, register: u8, buffer: &mut [u8]) -> Result<()> {
+        self.write_read(&[register], buffer)
+    }
+
+    /// Writes `value` to the slave device's 8-bit register `register`.
+    ///
+    /// Sequence: START → Address + Write Bit → Register → Outgoing Byte → STOP
+    pub fn write_register(&self, register: u8, value: u8) -> Result<()> {
+        ioctl::i2c_write(
+            self.i2cdev.as_raw_fd(),
+            self.address,
+            self.addr_10bit,
+            &[register, value],
+        )?;
+
+        Ok(())
+    }
+
+    /// Reads a 16-bit value from the slave device, starting at its 8-bit register
+    /// `register`.
+    ///
+    /// `read_register_u16` reads two consecutive bytes using [`read_registers`], and
+    /// combines them based on the byte order specified by `endian`.
+    ///
+    /// Sequence: START → Address + Write Bit → Register → Repeated START →
+    /// Address + Read Bit → Incoming Byte 1 → Incoming Byte 2 → STOP
+    ///
+    /// [`read_registers`]: #method.read_registers
+    pub fn read_register_u16(&self, register: u8, endian: Endian) -> Result<u16> {
+        let mut buffer = [0u8; 2];
+        self.read_registers(register, &mut buffer)?;
+
+        Ok(match endian {
+            Endian::Big => u16::from_be_bytes(buffer),
+            Endian::Little => u16::from_le_bytes(buffer),
+        })
+    }
+
+    /// Reads a signed 16-bit value from the slave device, starting at its 8-bit
+    /// register `register`.
+    ///
+    /// `read_register_i16` behaves similarly to [`read_register_u16`], and interprets
+    /// the value as a two's complement number.
+    ///
+    /// [`read_register_u16`]: #method.read_register_u16
+    pub fn read_register_i16(&self, register: u8, endian: Endian) -> Result<i16> {
+        Ok(self.read_register_u16(register, endian)? as i16)
+    }
+
+    /// Writes a 16-bit `value` to the slave device, starting at its 8-bit register
+    /// `register`.
+    ///
+    /// The bytes are sent in the byte order specified by `endian`.
+    ///
+    /// Sequence: START → Address + Write Bit → Register → Outgoing Byte 1 →
+    /// Outgoing Byte 2 → STOP
+    pub fn write_register_u16(&self, register: u8, value: u16, endian: Endian) -> Result<()> {
+        let bytes = match endian {
+            Endian::Big => value.to_be_bytes(),
+            Endian::Little => value.to_le_bytes(),
+        };
+
+        ioctl::i2c_write(
+            self.i2cdev.as_raw_fd(),
+            self.address,
+            self.addr_10bit,
+            &[register, bytes[0], bytes[1]],
+        )?;
+
+        Ok(())
+    }
+
+    /// Writes a signed 16-bit `value` to the slave device, starting at its 8-bit
+    /// register `register`.
+    ///
+    /// `write_register_i16` behaves similarly to [`write_register_u16`], and sends
+    /// the value as a two's complement number.
+    ///
+    /// [`write_register_u16`]: #method.write_register_u16
+    pub fn write_register_i16(&self, register: u8, value: i16, endian: Endian) -> Result<()> {
+        self.write_register_u16(register, value as u16, endian)
+    }
+
     /// Sends an 8-bit `command`, and then fills a multi-byte `buffer` with
     /// incoming data.
     ///
@@ -473,7 +1014,13 @@ impl I2c {
 
     /// Sends a 1-bit `command` in place of the R/W bit.
     ///
+    /// Setting `command` to `true` sends a 1 (Read), while `false` sends a 0 (Write).
+    /// Quick Command is often used to probe for devices, or to toggle a device's state.
+    /// Use [`capabilities`] to check if the underlying driver supports Quick Command.
+    ///
     /// Sequence: START → Address + Command Bit → STOP
+    ///
+    /// [`capabilities`]: #method.capabilities
     pub fn smbus_quick_command(&self, command: bool) -> Result<()> {
         ioctl::smbus_quick_command(self.i2cdev.as_raw_fd(), command)?;
 
@@ -673,14 +1220,44 @@ impl I2c {
     ///
     /// The PEC is calculated on all message bytes except the START, STOP, ACK and NACK bits.
     ///
+    /// When PEC is enabled, the PEC byte is appended to outgoing messages, and
+    /// verified for incoming messages. If the received PEC doesn't match the
+    /// calculated value, the SMBus method returns an [`Error::Io`] containing
+    /// an `EBADMSG` OS error.
+    ///
     /// By default, `pec` is set to `false`.
+    ///
+    /// If `pec` is set to `true` and the underlying drivers don't support Packet Error
+    /// Checking, `set_smbus_pec` returns [`Error::FeatureNotSupported`]. Disabling PEC
+    /// is always allowed.
+    ///
+    /// [`Error::Io`]: enum.Error.html#variant.Io
+    /// [`Error::FeatureNotSupported`]: enum.Error.html#variant.FeatureNotSupported
     pub fn set_smbus_pec(&self, pec: bool) -> Result<()> {
+        if pec && !self.capabilities().smbus_pec() {
+            return Err(Error::FeatureNotSupported);
+        }
+
         ioctl::set_pec(self.i2cdev.as_raw_fd(), pec as c_ulong)?;
 
+        self.pec.set(pec);
+
         Ok(())
     }
 }
 
+impl AsRawFd for I2c {
+    /// Returns the raw file descriptor for the underlying `i2cdev` character device.
+    ///
+    /// The file descriptor remains owned by `I2c`. It shouldn't be closed, and
+    /// shouldn't be used after `I2c` goes out of scope. Changing the slave address
+    /// or any other settings through the file descriptor may cause `I2c` to report
+    /// incorrect values.
+    fn as_raw_fd(&self) -> RawFd {
+        self.i2cdev.as_raw_fd()
+    }
+}
+
 // Send is safe for I2c, but we're marked !Send because of the dummy pointer that's
 // needed to force !Sync.
 unsafe impl Send for I2c {}
@@ -0,0 +1,125 @@
+use std::fs::{self, OpenOptions};
+use std::io;
+use std::os::unix::fs::OpenOptionsExt;
+use std::os::unix::io::AsRawFd;
+use std::ptr;
+
+use libc::{self, c_void, size_t, MAP_FAILED, MAP_SHARED, O_SYNC, PROT_READ, PROT_WRITE};
+
+use super::{Error, Result};
+use crate::system::{self, DeviceInfo};
+
+const PATH_DEV_MEM: &str = "/dev/mem";
+// The BSC controllers are clocked by the core (VPU) clock
+const PATH_CORE_CLOCK_RATE: &str = "/sys/kernel/debug/clk/vpu/clk_rate";
+// mmap offsets need to be page-aligned, so we map the entire page containing the
+// BSC registers
+const PAGE_SIZE: u64 = 4096;
+// Clock divider register
+const BSC_DIV: usize = 0x14 / std::mem::size_of::<u32>();
+// DIV.CDIV is 16 bits wide. A value of 0 is interpreted as 32768.
+const DIV_CDIV_MASK: u32 = 0xffff;
+// Clock stretch timeout register
+const BSC_CLKT: usize = 0x1c / std::mem::size_of::<u32>();
+// CLKT.TOUT is 16 bits wide
+pub(crate) const CLKT_TOUT_MASK: u32 = 0xffff;
+
+// Memory-mapped BSC registers, used to access settings that aren't exposed by i2cdev
+pub(crate) struct Bsc {
+    mem_ptr: *mut u32,
+    // Register offset within the mapped page
+    base: usize,
+}
+
+impl Bsc {
+    pub(crate) fn open(bus: u8) -> Result<Bsc> {
+        let device_info = DeviceInfo::new()?;
+
+        let offset = device_info
+            .peripheral_addresses()
+            .i2c
+            .get(usize::from(bus))
+            .copied()
+            .flatten()
+            .ok_or(Error::FeatureNotSupported)?;
+
+        // The BSC registers aren't accessible through /dev/gpiomem
+        let mem_file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .custom_flags(O_SYNC)
+            .open(PATH_DEV_MEM)
+            .map_err(|e| match e.kind() {
+                io::ErrorKind::PermissionDenied => Error::PermissionDenied,
+                _ => Error::Io(e),
+            })?;
+
+        let page_offset = system::devmem_offset(offset & !(PAGE_SIZE - 1))?;
+
+        let mem_ptr = unsafe {
+            libc::mmap(
+                ptr::null_mut(),
+                PAGE_SIZE as size_t,
+                PROT_READ | PROT_WRITE,
+                MAP_SHARED,
+                mem_file.as_raw_fd(),
+                page_offset,
+            )
+        };
+
+        if mem_ptr == MAP_FAILED {
+            return Err(Error::Io(io::Error::last_os_error()));
+        }
+
+        Ok(Bsc {
+            mem_ptr: mem_ptr as *mut u32,
+            base: (offset & (PAGE_SIZE - 1)) as usize / std::mem::size_of::<u32>(),
+        })
+    }
+
+    // Returns the SCL clock frequency in Hz, based on the core clock rate and
+    // the clock divider configured by the I2C driver
+    pub(crate) fn clock_speed(&self) -> Result<u32> {
+        let core_clock_rate: u32 = fs::read_to_string(PATH_CORE_CLOCK_RATE)?
+            .trim()
+            .parse()
+            .map_err(|_| Error::Io(io::Error::from(io::ErrorKind::InvalidData)))?;
+
+        let divider = match self.read(BSC_DIV) & DIV_CDIV_MASK {
+            0 => 32768,
+            divider => divider,
+        };
+
+        Ok(core_clock_rate / divider)
+    }
+
+    // Returns the clock stretch timeout in SCL clock cycles
+    pub(crate) fn clock_stretch_timeout(&self) -> u32 {
+        self.read(BSC_CLKT) & CLKT_TOUT_MASK
+    }
+
+    // Sets the clock stretch timeout in SCL clock cycles. 0 disables the timeout.
+    pub(crate) fn set_clock_stretch_timeout(&self, cycles: u32) {
+        self.write(BSC_CLKT, cycles & CLKT_TOUT_MASK);
+    }
+
+    #[inline(always)]
+    fn read(&self, offset: usize) -> u32 {
+        unsafe { ptr::read_volatile(self.mem_ptr.add(self.base + offset)) }
+    }
+
+    #[inline(always)]
+    fn write(&self, offset: usize, value: u32) {
+        unsafe {
+            ptr::write_volatile(self.mem_ptr.add(self.base + offset), value);
+        }
+    }
+}
+
+impl Drop for Bsc {
+    fn drop(&mut self) {
+        unsafe {
+            libc::munmap(self.mem_ptr as *mut c_void, PAGE_SIZE as size_t);
+        }
+    }
+}
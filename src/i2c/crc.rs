@@ -0,0 +1,75 @@
+// Calculates a CRC-8 using the specified MSB-first polynomial and initial value
+fn crc8(data: &[u8], polynomial: u8, init: u8) -> u8 {
+    let mut crc = init;
+
+    for &byte in data {
+        crc ^= byte;
+        for _ in 0..8 {
+            crc = if crc & 0x80 > 0 {
+                (crc << 1) ^ polynomial
+            } else {
+                crc << 1
+            };
+        }
+    }
+
+    crc
+}
+
+/// Calculates the SMBus Packet Error Code (PEC) for `data`.
+///
+/// The PEC is a CRC-8 with polynomial 0x07 and initial value 0x00. For an SMBus
+/// transaction, `data` includes every byte sent or received, including the address
+/// bytes with the R/W bit.
+///
+/// ```
+/// use rppal::i2c::crc8_smbus;
+///
+/// assert_eq!(crc8_smbus(b"123456789"), 0xf4);
+/// ```
+pub fn crc8_smbus(data: &[u8]) -> u8 {
+    crc8(data, 0x07, 0x00)
+}
+
+/// Calculates the CRC-8 used by Sensirion sensors, such as the SHT3x, SHT4x, SCD30
+/// and SCD4x.
+///
+/// The CRC is calculated with polynomial 0x31 and initial value 0xff. Sensirion
+/// sensors append the CRC after every 16-bit word, which means `data` is usually
+/// 2 bytes long.
+///
+/// ```
+/// use rppal::i2c::crc8_sensirion;
+///
+/// assert_eq!(crc8_sensirion(&[0xbe, 0xef]), 0x92);
+/// ```
+pub fn crc8_sensirion(data: &[u8]) -> u8 {
+    crc8(data, 0x31, 0xff)
+}
+
+/// Calculates a CRC-16/ARC for `data`.
+///
+/// The CRC is calculated with polynomial 0x8005 (reflected) and initial value 0x0000,
+/// which is used by the Raspberry Pi HAT ID EEPROM format, among others.
+///
+/// ```
+/// use rppal::i2c::crc16_arc;
+///
+/// assert_eq!(crc16_arc(b"123456789"), 0xbb3d);
+/// ```
+pub fn crc16_arc(data: &[u8]) -> u16 {
+    let mut crc: u16 = 0;
+
+    for &byte in data {
+        crc ^= u16::from(byte);
+        for _ in 0..8 {
+            crc = if crc & 0x0001 > 0 {
+                (crc >> 1) ^ 0xa001
+            } else {
+                crc >> 1
+            };
+        }
+    }
+
+    crc
+}
@@ -0,0 +1,232 @@
+use std::fmt;
+use std::io;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use super::{Error, I2c, Result};
+
+// Maximum number of bytes transferred in a single I2C_RDWR segment
+const MAX_READ_SIZE: usize = 8192;
+// Maximum duration of a write cycle. Most 24Cxx EEPROMs specify 5 or 10 ms.
+const WRITE_CYCLE_TIMEOUT: Duration = Duration::from_millis(25);
+// Delay in between acknowledge polls
+const POLL_INTERVAL: Duration = Duration::from_micros(200);
+
+/// Memory address widths for 24Cxx EEPROMs.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum EepromAddressWidth {
+    /// 8-bit memory addresses, used by the 24C01 through 24C16.
+    OneByte = 1,
+    /// 16-bit memory addresses, used by the 24C32 and larger.
+    TwoBytes = 2,
+}
+
+impl fmt::Display for EepromAddressWidth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            EepromAddressWidth::OneByte => write!(f, "OneByte"),
+            EepromAddressWidth::TwoBytes => write!(f, "TwoBytes"),
+        }
+    }
+}
+
+/// Helper for 24Cxx-style I2C EEPROMs.
+///
+/// `Eeprom24x` splits writes at page boundaries, and waits for each write cycle to
+/// complete by polling the EEPROM until it acknowledges its address. Reads are split
+/// where needed, so [`read`] and [`write`] can be used with any memory address and length.
+///
+/// Memory addresses that don't fit in the address width are sent as part of the slave
+/// address. For instance, the 24C16 uses 8-bit memory addresses, and selects one of
+/// eight 256-byte blocks using the 3 least significant bits of the slave address, while
+/// the 24CM01 uses 16-bit memory addresses and a single bit of the slave address. For
+/// those EEPROMs, set `slave_address` to the base address, which is usually `0x50`.
+///
+/// `Eeprom24x` doesn't know the EEPROM's capacity. Accessing memory addresses beyond the
+/// end of the EEPROM either wraps around, or targets a different slave device.
+///
+/// [`read`]: #method.read
+/// [`write`]: #method.write
+#[derive(Debug)]
+pub struct Eeprom24x {
+    i2c: I2c,
+    slave_address: u16,
+    address_width: EepromAddressWidth,
+    page_size: usize,
+}
+
+impl Eeprom24x {
+    /// Constructs a new `Eeprom24x`.
+    ///
+    /// `slave_address` is the EEPROM's base slave address. `address_width` and
+    /// `page_size` can be found in the EEPROM's datasheet. Common page sizes are 8 bytes
+    /// for the 24C01 and 24C02, 16 bytes for the 24C04 through 24C16, 32 bytes for the
+    /// 24C32 and 24C64, and 64 bytes or more for larger EEPROMs.
+    ///
+    /// If `page_size` is set to `0`, `new` returns `Err(`[`Error::Io`]`)` with an
+    /// `io::ErrorKind::InvalidInput` error.
+    ///
+    /// [`Error::Io`]: enum.Error.html#variant.Io
+    pub fn new(
+        i2c: I2c,
+        slave_address: u16,
+        address_width: EepromAddressWidth,
+        page_size: usize,
+    ) -> Result<Eeprom24x> {
+        if page_size == 0 {
+            return Err(invalid_input("page size can't be 0"));
+        }
+
+        Ok(Eeprom24x {
+            i2c,
+            slave_address,
+            address_width,
+            page_size,
+        })
+    }
+
+    /// Returns the memory address width.
+    pub fn address_width(&self) -> EepromAddressWidth {
+        self.address_width
+    }
+
+    /// Returns the page size in bytes.
+    pub fn page_size(&self) -> usize {
+        self.page_size
+    }
+
+    /// Reads `len` bytes starting at memory address `address`.
+    ///
+    /// If `address` doesn't fit in the address width and the 3 least significant bits
+    /// of the slave address combined, `read` returns `Err(`[`Error::Io`]`)` with an
+    /// `io::ErrorKind::InvalidInput` error.
+    ///
+    /// [`Error::Io`]: enum.Error.html#variant.Io
+    pub fn read(&self, address: u32, len: usize) -> Result<Vec<u8>> {
+        let mut buffer = vec![0u8; len];
+
+        let mut offset = 0;
+        while offset < len {
+            let current = self.offset_address(address, offset)?;
+            let (slave_address, memory_address) = self.split_address(current)?;
+
+            // Sequential reads wrap around at the end of the block selected by the
+            // slave address
+            let block_remaining = self.block_size() - (current as usize % self.block_size());
+            let chunk_len = (len - offset).min(block_remaining).min(MAX_READ_SIZE);
+
+            self.i2c.write_read_from(
+                slave_address,
+                &memory_address[..self.address_width as usize],
+                &mut buffer[offset..offset + chunk_len],
+            )?;
+
+            offset += chunk_len;
+        }
+
+        Ok(buffer)
+    }
+
+    /// Writes `data` starting at memory address `address`.
+    ///
+    /// `write` splits `data` at page boundaries, and blocks until the EEPROM has
+    /// completed each write cycle. If the EEPROM doesn't acknowledge its address within
+    /// 25 ms after a write, `write` returns `Err(`[`Error::Io`]`)` with an
+    /// `io::ErrorKind::TimedOut` error.
+    ///
+    /// If `address` doesn't fit in the address width and the 3 least significant bits
+    /// of the slave address combined, `write` returns `Err(`[`Error::Io`]`)` with an
+    /// `io::ErrorKind::InvalidInput` error.
+    ///
+    /// [`Error::Io`]: enum.Error.html#variant.Io
+    pub fn write(&self, address: u32, data: &[u8]) -> Result<()> {
+        let mut buffer = Vec::with_capacity(self.page_size + self.address_width as usize);
+
+        let mut offset = 0;
+        while offset < data.len() {
+            let current = self.offset_address(address, offset)?;
+            let (slave_address, memory_address) = self.split_address(current)?;
+
+            // Page writes wrap around at the end of the page
+            let page_remaining = self.page_size - (current as usize % self.page_size);
+            let chunk_len = (data.len() - offset).min(page_remaining);
+
+            buffer.clear();
+            buffer.extend_from_slice(&memory_address[..self.address_width as usize]);
+            buffer.extend_from_slice(&data[offset..offset + chunk_len]);
+
+            self.i2c.write_to(slave_address, &buffer)?;
+            self.wait_for_write_cycle(slave_address, &memory_address)?;
+
+            offset += chunk_len;
+        }
+
+        Ok(())
+    }
+
+    /// Consumes the `Eeprom24x`, and returns the underlying [`I2c`].
+    ///
+    /// [`I2c`]: struct.I2c.html
+    pub fn into_inner(self) -> I2c {
+        self.i2c
+    }
+
+    // Number of bytes addressed by the memory address bytes
+    fn block_size(&self) -> usize {
+        1 << (8 * self.address_width as usize)
+    }
+
+    fn offset_address(&self, address: u32, offset: usize) -> Result<u32> {
+        u32::try_from(offset)
+            .ok()
+            .and_then(|offset| address.checked_add(offset))
+            .ok_or_else(|| invalid_input("memory address out of range"))
+    }
+
+    // Splits a memory address into the slave address and the memory address bytes
+    fn split_address(&self, address: u32) -> Result<(u16, [u8; 2])> {
+        let block = address >> (8 * self.address_width as u32);
+        if block > 0x07 {
+            return Err(invalid_input("memory address out of range"));
+        }
+
+        let memory_address = match self.address_width {
+            EepromAddressWidth::OneByte => [address as u8, 0],
+            EepromAddressWidth::TwoBytes => [(address >> 8) as u8, address as u8],
+        };
+
+        Ok((self.slave_address | block as u16, memory_address))
+    }
+
+    // The EEPROM doesn't acknowledge its address until the write cycle completes.
+    // Sending the memory address only updates the EEPROM's address pointer.
+    fn wait_for_write_cycle(&self, slave_address: u16, memory_address: &[u8; 2]) -> Result<()> {
+        let start = Instant::now();
+
+        loop {
+            thread::sleep(POLL_INTERVAL);
+
+            if self
+                .i2c
+                .write_to(
+                    slave_address,
+                    &memory_address[..self.address_width as usize],
+                )
+                .is_ok()
+            {
+                return Ok(());
+            }
+
+            if start.elapsed() > WRITE_CYCLE_TIMEOUT {
+                return Err(Error::Io(io::Error::new(
+                    io::ErrorKind::TimedOut,
+                    "EEPROM write cycle didn't complete",
+                )));
+            }
+        }
+    }
+}
+
+fn invalid_input(message: &str) -> Error {
+    Error::Io(io::Error::new(io::ErrorKind::InvalidInput, message))
+}
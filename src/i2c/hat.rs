@@ -0,0 +1,378 @@
+use std::io;
+
+use super::{crc16_arc, Error, I2c, Result};
+use crate::gpio::{Bias, Mode};
+
+// HAT ID EEPROM slave address
+const EEPROM_ADDRESS: u16 = 0x50;
+// Maximum number of bytes read in a single transaction
+const READ_CHUNK_SIZE: usize = 256;
+
+// "R-Pi" in ASCII
+const SIGNATURE: [u8; 4] = [0x52, 0x2d, 0x50, 0x69];
+const HEADER_SIZE: usize = 12;
+const ATOM_HEADER_SIZE: usize = 8;
+const CRC_SIZE: usize = 2;
+
+const ATOM_VENDOR_INFO: u16 = 0x0001;
+const ATOM_GPIO_MAP: u16 = 0x0002;
+const ATOM_DEVICE_TREE: u16 = 0x0003;
+const ATOM_CUSTOM_DATA: u16 = 0x0004;
+
+// Number of GPIO pins described by the GPIO map (BCM GPIO 0-27)
+const GPIO_MAP_PINS: usize = 28;
+
+/// Vendor information stored on a HAT ID EEPROM.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct HatVendorInfo {
+    uuid: u128,
+    product_id: u16,
+    product_version: u16,
+    vendor: String,
+    product: String,
+}
+
+impl HatVendorInfo {
+    /// Returns the UUID, which uniquely identifies each individual HAT.
+    pub fn uuid(&self) -> u128 {
+        self.uuid
+    }
+
+    /// Returns the vendor-specific product ID.
+    pub fn product_id(&self) -> u16 {
+        self.product_id
+    }
+
+    /// Returns the vendor-specific product version.
+    pub fn product_version(&self) -> u16 {
+        self.product_version
+    }
+
+    /// Returns the vendor name.
+    pub fn vendor(&self) -> &str {
+        &self.vendor
+    }
+
+    /// Returns the product name.
+    pub fn product(&self) -> &str {
+        &self.product
+    }
+}
+
+/// Configuration of a single GPIO pin used by a HAT.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct HatPin {
+    pin: u8,
+    mode: Mode,
+    bias: Option<Bias>,
+}
+
+impl HatPin {
+    /// Returns the BCM GPIO pin number.
+    pub fn pin(&self) -> u8 {
+        self.pin
+    }
+
+    /// Returns the pin's mode.
+    pub fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    /// Returns the pin's bias, or `None` if the pull-up/pull-down resistors should be
+    /// left at their default settings.
+    pub fn bias(&self) -> Option<Bias> {
+        self.bias
+    }
+}
+
+/// GPIO map stored on a HAT ID EEPROM.
+///
+/// The GPIO map describes the GPIO pins used by a HAT, and how they should be configured.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct HatGpioMap {
+    drive: u8,
+    slew: u8,
+    hysteresis: u8,
+    back_power: u8,
+    pins: Vec<HatPin>,
+}
+
+impl HatGpioMap {
+    /// Returns the drive strength setting for the GPIO bank.
+    ///
+    /// `0` leaves the drive strength at its default setting. `1`-`8` select a drive
+    /// strength of 2-16 mA.
+    pub fn drive(&self) -> u8 {
+        self.drive
+    }
+
+    /// Returns the slew rate setting for the GPIO bank.
+    ///
+    /// `0` leaves the slew rate at its default setting, `1` limits the slew rate,
+    /// and `2` doesn't limit the slew rate.
+    pub fn slew(&self) -> u8 {
+        self.slew
+    }
+
+    /// Returns the input hysteresis setting for the GPIO bank.
+    ///
+    /// `0` leaves the hysteresis at its default setting, `1` enables hysteresis,
+    /// and `2` disables hysteresis.
+    pub fn hysteresis(&self) -> u8 {
+        self.hysteresis
+    }
+
+    /// Returns the back power setting.
+    ///
+    /// `0` indicates the HAT doesn't back power the Raspberry Pi. `1` and `2`
+    /// indicate the HAT supplies up to 1.3 A or 2 A to the Raspberry Pi, respectively.
+    pub fn back_power(&self) -> u8 {
+        self.back_power
+    }
+
+    /// Returns the GPIO pins used by the HAT.
+    pub fn pins(&self) -> &[HatPin] {
+        &self.pins
+    }
+}
+
+/// HAT ID EEPROM parser.
+///
+/// Add-on boards that follow the Raspberry Pi HAT specification include an ID
+/// EEPROM, which describes the board's vendor and product, the GPIO pins it uses,
+/// and optionally a device tree overlay and custom data.
+///
+/// The ID EEPROM is connected to a dedicated I2C bus on BCM GPIO 0 (ID_SD) and
+/// BCM GPIO 1 (ID_SC), at slave address `0x50`. By default, this bus is only used by
+/// the firmware during boot. You can enable it by adding `dtparam=i2c_vc=on` to
+/// `/boot/firmware/config.txt`, after which it's usually available as I2C0.
+///
+/// The firmware also exposes the vendor information it read during boot through
+/// `/proc/device-tree/hat`, which doesn't require access to the I2C bus.
+///
+/// `HatEeprom` currently only supports reading the ID EEPROM.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct HatEeprom {
+    version: u8,
+    vendor_info: Option<HatVendorInfo>,
+    gpio_map: Option<HatGpioMap>,
+    device_tree: Option<Vec<u8>>,
+    custom_data: Vec<Vec<u8>>,
+}
+
+impl HatEeprom {
+    /// Reads and parses the contents of a HAT ID EEPROM.
+    ///
+    /// `read` changes the slave address of `i2c` to `0x50`, and reads the
+    /// complete EEPROM image.
+    ///
+    /// If the EEPROM doesn't contain a valid image, `read` returns
+    /// `Err(`[`Error::Io`]`)` with an `io::ErrorKind::InvalidData` error.
+    ///
+    /// [`Error::Io`]: enum.Error.html#variant.Io
+    pub fn read(i2c: &mut I2c) -> Result<HatEeprom> {
+        i2c.set_slave_address(EEPROM_ADDRESS)?;
+
+        let mut header = [0u8; HEADER_SIZE];
+        read_eeprom(i2c, 0, &mut header)?;
+        check_signature(&header)?;
+
+        let length = u32::from_le_bytes([header[8], header[9], header[10], header[11]]) as usize;
+        if length < HEADER_SIZE || length > usize::from(u16::MAX) + 1 {
+            return Err(invalid_data("invalid EEPROM length"));
+        }
+
+        let mut data = vec![0u8; length];
+        data[..HEADER_SIZE].copy_from_slice(&header);
+        read_eeprom(i2c, HEADER_SIZE, &mut data[HEADER_SIZE..])?;
+
+        HatEeprom::parse(&data)
+    }
+
+    /// Parses a HAT ID EEPROM image.
+    ///
+    /// `parse` can be used to parse an image that was retrieved through other means,
+    /// for instance by reading a `.eep` file created with the `eepmake` utility.
+    ///
+    /// If `data` doesn't contain a valid image, or any of the atoms has an invalid
+    /// checksum, `parse` returns `Err(`[`Error::Io`]`)` with an `io::ErrorKind::InvalidData`
+    /// error.
+    ///
+    /// [`Error::Io`]: enum.Error.html#variant.Io
+    pub fn parse(data: &[u8]) -> Result<HatEeprom> {
+        if data.len() < HEADER_SIZE {
+            return Err(invalid_data("EEPROM image too short"));
+        }
+
+        check_signature(data)?;
+
+        let mut eeprom = HatEeprom {
+            version: data[4],
+            vendor_info: None,
+            gpio_map: None,
+            device_tree: None,
+            custom_data: Vec::new(),
+        };
+
+        let atoms = u16::from_le_bytes([data[6], data[7]]);
+        let mut offset = HEADER_SIZE;
+
+        for _ in 0..atoms {
+            if data.len() < offset + ATOM_HEADER_SIZE {
+                return Err(invalid_data("EEPROM atom header out of bounds"));
+            }
+
+            let atom_type = u16::from_le_bytes([data[offset], data[offset + 1]]);
+            let length = u32::from_le_bytes([
+                data[offset + 4],
+                data[offset + 5],
+                data[offset + 6],
+                data[offset + 7],
+            ]) as usize;
+
+            // The length includes the CRC
+            if length < CRC_SIZE || data.len() - offset - ATOM_HEADER_SIZE < length {
+                return Err(invalid_data("EEPROM atom data out of bounds"));
+            }
+
+            let end = offset + ATOM_HEADER_SIZE + length;
+            let crc = u16::from_le_bytes([data[end - 2], data[end - 1]]);
+            if crc16_arc(&data[offset..end - CRC_SIZE]) != crc {
+                return Err(invalid_data("EEPROM atom checksum mismatch"));
+            }
+
+            let atom_data = &data[offset + ATOM_HEADER_SIZE..end - CRC_SIZE];
+            match atom_type {
+                ATOM_VENDOR_INFO => eeprom.vendor_info = Some(parse_vendor_info(atom_data)?),
+                ATOM_GPIO_MAP => eeprom.gpio_map = Some(parse_gpio_map(atom_data)?),
+                ATOM_DEVICE_TREE => eeprom.device_tree = Some(atom_data.to_vec()),
+                ATOM_CUSTOM_DATA => eeprom.custom_data.push(atom_data.to_vec()),
+                // Ignore unknown atom types added by future versions of the specification
+                _ => {}
+            }
+
+            offset = end;
+        }
+
+        Ok(eeprom)
+    }
+
+    /// Returns the version of the EEPROM format.
+    pub fn version(&self) -> u8 {
+        self.version
+    }
+
+    /// Returns the vendor information.
+    pub fn vendor_info(&self) -> Option<&HatVendorInfo> {
+        self.vendor_info.as_ref()
+    }
+
+    /// Returns the GPIO map.
+    pub fn gpio_map(&self) -> Option<&HatGpioMap> {
+        self.gpio_map.as_ref()
+    }
+
+    /// Returns the device tree overlay, either as a compiled blob, or as the name of
+    /// an overlay that's loaded from `/boot/overlays`.
+    pub fn device_tree(&self) -> Option<&[u8]> {
+        self.device_tree.as_deref()
+    }
+
+    /// Returns the contents of all manufacturer custom data atoms.
+    pub fn custom_data(&self) -> &[Vec<u8>] {
+        &self.custom_data
+    }
+}
+
+// Reads from the EEPROM using 16-bit memory addresses
+fn read_eeprom(i2c: &I2c, address: usize, buffer: &mut [u8]) -> Result<()> {
+    for (idx, chunk) in buffer.chunks_mut(READ_CHUNK_SIZE).enumerate() {
+        let chunk_address = (address + idx * READ_CHUNK_SIZE) as u16;
+        i2c.write_read(&chunk_address.to_be_bytes(), chunk)?;
+    }
+
+    Ok(())
+}
+
+fn check_signature(data: &[u8]) -> Result<()> {
+    if data[..SIGNATURE.len()] != SIGNATURE {
+        return Err(invalid_data("EEPROM signature not found"));
+    }
+
+    Ok(())
+}
+
+fn parse_vendor_info(data: &[u8]) -> Result<HatVendorInfo> {
+    if data.len() < 22 {
+        return Err(invalid_data("vendor info atom too short"));
+    }
+
+    let mut uuid = [0u8; 16];
+    uuid.copy_from_slice(&data[..16]);
+
+    let vendor_len = data[20] as usize;
+    let product_len = data[21] as usize;
+    if data.len() < 22 + vendor_len + product_len {
+        return Err(invalid_data("vendor info atom too short"));
+    }
+
+    Ok(HatVendorInfo {
+        uuid: u128::from_le_bytes(uuid),
+        product_id: u16::from_le_bytes([data[16], data[17]]),
+        product_version: u16::from_le_bytes([data[18], data[19]]),
+        vendor: String::from_utf8_lossy(&data[22..22 + vendor_len]).into_owned(),
+        product: String::from_utf8_lossy(&data[22 + vendor_len..22 + vendor_len + product_len])
+            .into_owned(),
+    })
+}
+
+fn parse_gpio_map(data: &[u8]) -> Result<HatGpioMap> {
+    if data.len() < 2 + GPIO_MAP_PINS {
+        return Err(invalid_data("GPIO map atom too short"));
+    }
+
+    let mut pins = Vec::new();
+    for (pin, &setting) in data[2..2 + GPIO_MAP_PINS].iter().enumerate() {
+        // Bit 7 indicates whether the HAT uses the pin
+        if setting & 0x80 == 0 {
+            continue;
+        }
+
+        // The function select values match the GPFSEL register encoding
+        let mode = match setting & 0x07 {
+            0 => Mode::Input,
+            1 => Mode::Output,
+            2 => Mode::Alt5,
+            3 => Mode::Alt4,
+            4 => Mode::Alt0,
+            5 => Mode::Alt1,
+            6 => Mode::Alt2,
+            _ => Mode::Alt3,
+        };
+
+        let bias = match (setting >> 5) & 0x03 {
+            1 => Some(Bias::PullUp),
+            2 => Some(Bias::PullDown),
+            3 => Some(Bias::Off),
+            _ => None,
+        };
+
+        pins.push(HatPin {
+            pin: pin as u8,
+            mode,
+            bias,
+        });
+    }
+
+    Ok(HatGpioMap {
+        drive: data[0] & 0x0f,
+        slew: (data[0] >> 4) & 0x03,
+        hysteresis: (data[0] >> 6) & 0x03,
+        back_power: data[1] & 0x03,
+        pins,
+    })
+}
+
+fn invalid_data(message: &str) -> Error {
+    Error::Io(io::Error::new(io::ErrorKind::InvalidData, message))
+}
@@ -1,5 +1,6 @@
 use std::fmt;
 use std::io;
+use std::os::unix::io::RawFd;
 use std::ptr;
 use std::result;
 
@@ -485,6 +486,30 @@ struct RdwrSegment {
     data: usize,
 }
 
+impl RdwrSegment {
+    fn read(address: u16, addr_10bit: bool, buffer: &mut [u8]) -> RdwrSegment {
+        RdwrSegment {
+            addr: address,
+            flags: if addr_10bit {
+                RDWR_FLAG_RD | RDWR_FLAG_TEN
+            } else {
+                RDWR_FLAG_RD
+            },
+            len: buffer.len() as u16,
+            data: buffer.as_mut_ptr() as usize,
+        }
+    }
+
+    fn write(address: u16, addr_10bit: bool, buffer: &[u8]) -> RdwrSegment {
+        RdwrSegment {
+            addr: address,
+            flags: if addr_10bit { RDWR_FLAG_TEN } else { 0 },
+            len: buffer.len() as u16,
+            data: buffer.as_ptr() as usize,
+        }
+    }
+}
+
 // Specifies RWDR request parameters
 #[repr(C)]
 #[derive(Debug, PartialEq, Copy, Clone)]
@@ -507,28 +532,37 @@ pub fn i2c_write_read(
         return Ok(());
     }
 
-    let segment_write = RdwrSegment {
-        addr: address,
-        flags: if addr_10bit { RDWR_FLAG_TEN } else { 0 },
-        len: write_buffer.len() as u16,
-        data: write_buffer.as_ptr() as usize,
-    };
+    rdwr(
+        fd,
+        &mut [
+            RdwrSegment::write(address, addr_10bit, write_buffer),
+            RdwrSegment::read(address, addr_10bit, read_buffer),
+        ],
+    )
+}
 
-    let segment_read = RdwrSegment {
-        addr: address,
-        flags: if addr_10bit {
-            RDWR_FLAG_RD | RDWR_FLAG_TEN
-        } else {
-            RDWR_FLAG_RD
-        },
-        len: read_buffer.len() as u16,
-        data: read_buffer.as_mut_ptr() as usize,
-    };
+pub fn i2c_read(fd: RawFd, address: u16, addr_10bit: bool, buffer: &mut [u8]) -> Result<()> {
+    // 0 length buffers may cause issues
+    if buffer.is_empty() {
+        return Ok(());
+    }
+
+    rdwr(fd, &mut [RdwrSegment::read(address, addr_10bit, buffer)])
+}
+
+pub fn i2c_write(fd: RawFd, address: u16, addr_10bit: bool, buffer: &[u8]) -> Result<()> {
+    // 0 length buffers may cause issues
+    if buffer.is_empty() {
+        return Ok(());
+    }
+
+    rdwr(fd, &mut [RdwrSegment::write(address, addr_10bit, buffer)])
+}
 
-    let mut segments: [RdwrSegment; 2] = [segment_write, segment_read];
+fn rdwr(fd: RawFd, segments: &mut [RdwrSegment]) -> Result<()> {
     let mut request = RdwrRequest {
-        segments: &mut segments,
-        nmsgs: 2,
+        nmsgs: segments.len() as u32,
+        segments,
     };
 
     parse_retval!(unsafe { ioctl(fd, REQ_RDWR, &mut request) })?;
@@ -556,13 +590,7 @@ pub fn set_pec(fd: c_int, value: c_ulong) -> Result<()> {
 
 pub fn set_timeout(fd: c_int, value: c_ulong) -> Result<()> {
     // Timeout is specified in units of 10ms
-    let timeout: c_ulong = if value > 0 && value < 10 {
-        1
-    } else {
-        value / 10
-    };
-
-    parse_retval!(unsafe { ioctl(fd, REQ_TIMEOUT, timeout) })?;
+    parse_retval!(unsafe { ioctl(fd, REQ_TIMEOUT, value) })?;
 
     Ok(())
 }
@@ -20,6 +20,50 @@
 //! use with other peripherals. Be careful not to enable two peripherals on the same pin
 //! at the same time.
 //!
+//! ## PWM pins
+//!
+//! Each PWM channel can be routed to several pins by selecting the pin's alternate
+//! function. [`with_pin`] changes the mode of the specified pin, and selects the PWM
+//! channel associated with it. The following pins are supported.
+//!
+//! * BCM283x and BCM2711: PWM0 on BCM GPIO 12 (physical pin 32), 18 (physical pin 12)
+//!   and 40, PWM1 on BCM GPIO 13 (physical pin 33), 19 (physical pin 35), 41 and 45.
+//! * RP1 (Raspberry Pi 5): PWM0 on BCM GPIO 14 (physical pin 8) and 18 (physical pin 12),
+//!   PWM1 on BCM GPIO 15 (physical pin 10) and 19 (physical pin 35).
+//!
+//! GPIO 40, 41 and 45 aren't exposed on the GPIO header, but are connected to the
+//! analog audio output on some models. The PWM channel still needs to be enabled
+//! through one of the overlays mentioned above, which also configures the overlay's
+//! default pin. If the selected pin differs from the default pin, the signal is
+//! output on both pins. [`available_pins`] returns the supported pins for the enabled
+//! PWM channels on the current model.
+//!
+//! ## Clock and resolution
+//!
+//! The PWM peripheral's clock source and divider are configured by the kernel driver
+//! based on the device tree, and can't be changed through the `pwm` sysfs interface.
+//! By default, the PWM clock runs at 10 MHz on the BCM283x and BCM2711 SoCs,
+//! and 50 MHz on the RP1.
+//!
+//! The period and pulse width are rounded to a whole number of clock ticks, which
+//! limits the number of distinct duty cycle steps available at higher frequencies.
+//! Use [`resolution`] or [`resolution_bits`] to check the effective resolution for the
+//! configured period.
+//!
+//! ## FIFO output
+//!
+//! Besides generating a regular PWM signal, the PWM peripheral on the BCM283x and
+//! BCM2711 can shift out a sequence of 32-bit words from its FIFO as a serial bit
+//! stream, MSB first, which can be used to generate arbitrary waveforms. [`write_fifo`]
+//! feeds the FIFO directly through the memory-mapped registers, without DMA, which
+//! requires access to `/dev/mem`.
+//!
+//! The FIFO is 8 words deep and shared between both channels. [`write_fifo`] refills
+//! the FIFO from a busy loop, so if the calling thread gets preempted for longer than
+//! it takes to send the remaining words, the FIFO runs empty. When the FIFO underruns,
+//! the output is held low until the next word is available, which shows up as a gap in
+//! the waveform. The risk of an underrun increases with the bit rate.
+//!
 //! ## Using PWM without superuser privileges (`sudo`)
 //!
 //! As of kernel version 4.14.34, released on April 16 2018, it's possible to
@@ -45,27 +89,45 @@
 //!
 //! ### Permission denied
 //!
-//! If [`new`] returns an `io::ErrorKind::PermissionDenied`
-//! error, make sure `/sys/class/pwm` and all of its subdirectories
+//! If [`new`] returns `Err(`[`Error::PermissionDenied`]`)`,
+//! make sure `/sys/class/pwm` and all of its subdirectories
 //! are owned by `root:gpio`, the current user is a member of the `gpio` group
 //! and `udev` is properly configured as mentioned above. Alternatively, you can
 //! launch your application using `sudo`.
 //!
-//! ### Not found
+//! ### Not enabled
 //!
-//! If [`new`] returns an `io::ErrorKind::NotFound` error, you may have
+//! If [`new`] returns `Err(`[`Error::NotEnabled`]`)`, you may have
 //! forgotten to enable the selected PWM channel. The configuration options
 //! to enable either of the two PWM channels are listed above.
 //!
+//! ### Busy
+//!
+//! If [`new`] returns `Err(`[`Error::Busy`]`)`, the selected PWM channel is
+//! already in use by another driver.
+//!
 //! [patch]: https://github.com/raspberrypi/linux/issues/1983
 //! [`new`]: struct.Pwm.html#method.new
+//! [`Error::PermissionDenied`]: enum.Error.html#variant.PermissionDenied
+//! [`Error::NotEnabled`]: enum.Error.html#variant.NotEnabled
+//! [`Error::Busy`]: enum.Error.html#variant.Busy
+//! [`resolution`]: struct.Pwm.html#method.resolution
+//! [`resolution_bits`]: struct.Pwm.html#method.resolution_bits
+//! [`write_fifo`]: struct.Pwm.html#method.write_fifo
+//! [`available_pins`]: struct.Pwm.html#method.available_pins
+//! [`with_pin`]: struct.Pwm.html#method.with_pin
 
 use std::error;
 use std::fmt;
 use std::io;
+use std::path::Path;
 use std::result;
-use std::time::Duration;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::thread;
+use std::time::{Duration, Instant};
 
+mod fifo;
 #[cfg(any(
     feature = "embedded-hal-0",
     feature = "embedded-hal",
@@ -76,9 +138,36 @@ mod hal;
 mod hal_unproven;
 mod sysfs;
 
-use crate::system::DeviceInfo;
+use crate::gpio::{Mode, Pin};
+use crate::system::{DeviceInfo, GpioInterface};
 
 const NANOS_PER_SEC: f64 = 1_000_000_000.0;
+// Time between duty cycle updates while fading
+const FADE_INTERVAL: Duration = Duration::from_millis(10);
+
+// Pins that can output a PWM channel, and the alternate function that selects it
+const PWM_PINS_BCM: [(u8, Channel, Mode); 7] = [
+    (12, Channel::Pwm0, Mode::Alt0),
+    (13, Channel::Pwm1, Mode::Alt0),
+    (18, Channel::Pwm0, Mode::Alt5),
+    (19, Channel::Pwm1, Mode::Alt5),
+    (40, Channel::Pwm0, Mode::Alt0),
+    (41, Channel::Pwm1, Mode::Alt0),
+    (45, Channel::Pwm1, Mode::Alt0),
+];
+const PWM_PINS_RP1: [(u8, Channel, Mode); 4] = [
+    (14, Channel::Pwm0, Mode::Alt0),
+    (15, Channel::Pwm1, Mode::Alt0),
+    (18, Channel::Pwm0, Mode::Alt3),
+    (19, Channel::Pwm1, Mode::Alt3),
+];
+
+fn pwm_pins(device_info: &DeviceInfo) -> &'static [(u8, Channel, Mode)] {
+    match device_info.gpio_interface() {
+        GpioInterface::Bcm => &PWM_PINS_BCM,
+        GpioInterface::Rp1 => &PWM_PINS_RP1,
+    }
+}
 
 /// Errors that can occur when accessing the PWM peripheral.
 #[derive(Debug)]
@@ -96,6 +185,19 @@ pub enum Error {
     /// doesn't provide any of the common user-accessible system files
     /// that are used to identify the model and SoC.
     UnknownModel,
+    /// PWM channel not enabled.
+    ///
+    /// The PWM chip or channel doesn't exist. Make sure the PWM channel has been enabled.
+    /// More information can be found [here](index.html).
+    NotEnabled,
+    /// Permission denied.
+    ///
+    /// The current user doesn't have the required permissions to access the PWM chip or channel.
+    PermissionDenied,
+    /// PWM channel busy.
+    ///
+    /// The PWM channel is already in use by another driver or process.
+    Busy,
 }
 
 impl fmt::Display for Error {
@@ -103,11 +205,21 @@ impl fmt::Display for Error {
         match *self {
             Error::Io(ref err) => write!(f, "I/O error: {}", err),
             Error::UnknownModel => write!(f, "Unknown Raspberry Pi model"),
+            Error::NotEnabled => write!(f, "PWM channel not enabled"),
+            Error::PermissionDenied => write!(f, "Permission denied"),
+            Error::Busy => write!(f, "PWM channel busy"),
         }
     }
 }
 
-impl error::Error for Error {}
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            Error::Io(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
 
 impl From<io::Error> for Error {
     fn from(err: io::Error) -> Error {
@@ -115,6 +227,16 @@ impl From<io::Error> for Error {
     }
 }
 
+// Converts an error returned while opening the PWM sysfs files into a more specific error.
+fn open_error(err: io::Error) -> Error {
+    match err.kind() {
+        io::ErrorKind::NotFound => Error::NotEnabled,
+        io::ErrorKind::PermissionDenied => Error::PermissionDenied,
+        _ if err.raw_os_error() == Some(libc::EBUSY) => Error::Busy,
+        _ => Error::Io(err),
+    }
+}
+
 /// Result type returned from methods that can have `pwm::Error`s.
 pub type Result<T> = result::Result<T, Error>;
 
@@ -155,6 +277,54 @@ impl fmt::Display for Polarity {
     }
 }
 
+/// Easing curves used when fading the duty cycle.
+///
+/// [`Pwm::fade_to`] and [`Pwm::fade_to_blocking`] use the easing curve to
+/// interpolate between the current and the target duty cycle.
+///
+/// [`Pwm::fade_to`]: struct.Pwm.html#method.fade_to
+/// [`Pwm::fade_to_blocking`]: struct.Pwm.html#method.fade_to_blocking
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Easing {
+    /// Changes the duty cycle at a constant rate.
+    Linear,
+    /// Starts slow, and speeds up towards the end.
+    EaseIn,
+    /// Starts fast, and slows down towards the end.
+    EaseOut,
+    /// Starts and ends slow, and speeds up in the middle.
+    EaseInOut,
+}
+
+impl Easing {
+    // Maps the elapsed fraction of the fade duration to the fraction of the duty cycle change
+    fn apply(self, t: f64) -> f64 {
+        match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t,
+            Easing::EaseOut => t * (2.0 - t),
+            Easing::EaseInOut => {
+                if t < 0.5 {
+                    2.0 * t * t
+                } else {
+                    -1.0 + (4.0 - 2.0 * t) * t
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Display for Easing {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Easing::Linear => write!(f, "Linear"),
+            Easing::EaseIn => write!(f, "EaseIn"),
+            Easing::EaseOut => write!(f, "EaseOut"),
+            Easing::EaseInOut => write!(f, "EaseInOut"),
+        }
+    }
+}
+
 /// Provides access to the Raspberry Pi's PWM peripheral.
 ///
 /// Before using `Pwm`, make sure the selected PWM channel has been configured
@@ -166,9 +336,25 @@ impl fmt::Display for Polarity {
 /// [here]: index.html
 #[derive(Debug)]
 pub struct Pwm {
-    chip: u8,
+    // sysfs path of the PWM chip
+    chip: String,
     channel: u8,
+    clock_rate: u32,
     reset_on_drop: bool,
+    // Pin configured by with_pin, and its original mode
+    pin: Option<(Pin, Mode)>,
+    // Period and pulse width in ns, saved by disable
+    saved: Mutex<Option<(u64, u64)>>,
+    // Shared with the threads spawned by fade_to
+    fades: Arc<Fades>,
+}
+
+// Lets Drop stop any fades running on a background thread, and wait for them to exit
+#[derive(Debug, Default)]
+struct Fades {
+    stop: AtomicBool,
+    // Held for reading by each running fade
+    running: RwLock<()>,
 }
 
 impl Pwm {
@@ -182,15 +368,38 @@ impl Pwm {
         // Select chip/channel based on Pi model
         let device_info = DeviceInfo::new().map_err(|_| Error::UnknownModel)?;
 
-        let chip = device_info.pwm_chip();
-        let channel = device_info.pwm_channels()[channel as usize];
+        Pwm::with_chip_path(
+            sysfs::chip_path(device_info.pwm_chip()),
+            device_info.pwm_channels()[channel as usize],
+        )
+    }
 
-        sysfs::export(chip, channel)?;
+    /// Constructs a new `Pwm` using the PWM chip at the specified sysfs path.
+    ///
+    /// [`new`] selects the PWM chip and channel based on the Raspberry Pi model, and
+    /// expects the chip at `/sys/class/pwm/pwmchip<n>`. `with_chip_path` can be used
+    /// for custom device trees, or in environments where sysfs is mounted elsewhere. `path`
+    /// refers to the chip's directory, for instance `/sys/class/pwm/pwmchip0`, and
+    /// `channel` to the chip's channel number as used by its `export` file.
+    ///
+    /// The Raspberry Pi model is still used to determine the PWM clock rate. Otherwise,
+    /// `with_chip_path` behaves the same as [`new`].
+    ///
+    /// [`new`]: #method.new
+    pub fn with_chip_path<P: AsRef<Path>>(path: P, channel: u8) -> Result<Pwm> {
+        let device_info = DeviceInfo::new().map_err(|_| Error::UnknownModel)?;
+        let chip = path.as_ref().to_string_lossy().into_owned();
+
+        sysfs::export(&chip, channel).map_err(open_error)?;
 
         let pwm = Pwm {
             chip,
             channel,
+            clock_rate: device_info.pwm_clock_rate(),
             reset_on_drop: true,
+            pin: None,
+            saved: Mutex::new(None),
+            fades: Arc::new(Fades::default()),
         };
 
         // Always reset "enable" to 0. The sysfs interface has a bug where a previous
@@ -201,6 +410,98 @@ impl Pwm {
         Ok(pwm)
     }
 
+    /// Constructs a new `Pwm` for the PWM channel associated with the specified pin.
+    ///
+    /// `with_pin` changes the pin's mode to the alternate function that outputs the PWM
+    /// channel, and otherwise behaves the same as [`new`]. The supported pins are listed
+    /// [here]. When `Pwm` goes out of scope, the pin's mode is reset to its original
+    /// state, unless [`reset_on_drop`] is set to `false`.
+    ///
+    /// If the pin can't output any of the PWM channels, `with_pin` returns
+    /// `Err(`[`Error::Io`]`)` with an `io::ErrorKind::InvalidInput` error.
+    ///
+    /// [`new`]: #method.new
+    /// [`reset_on_drop`]: #method.set_reset_on_drop
+    /// [`Error::Io`]: enum.Error.html#variant.Io
+    /// [here]: index.html#pwm-pins
+    pub fn with_pin(mut pin: Pin) -> Result<Pwm> {
+        let device_info = DeviceInfo::new().map_err(|_| Error::UnknownModel)?;
+
+        let (channel, mode) = match pwm_pins(&device_info)
+            .iter()
+            .find(|&&(gpio, _, _)| gpio == pin.pin())
+        {
+            Some(&(_, channel, mode)) => (channel, mode),
+            None => {
+                return Err(Error::Io(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("GPIO {} doesn't have a PWM output", pin.pin()),
+                )))
+            }
+        };
+
+        let mut pwm = Pwm::new(channel)?;
+
+        let prev_mode = pin.mode();
+        pin.set_mode(mode);
+        pwm.pin = Some((pin, prev_mode));
+
+        Ok(pwm)
+    }
+
+    /// Returns the PWM channels that are enabled and available on this device.
+    ///
+    /// A channel is considered available when the associated PWM chip exists,
+    /// and it supports the channel. `channels_available` doesn't check if the
+    /// channel is currently being used by another process or driver.
+    ///
+    /// More information on enabling the PWM channels can be found [here].
+    ///
+    /// [here]: index.html
+    pub fn channels_available() -> Result<Vec<Channel>> {
+        let device_info = DeviceInfo::new().map_err(|_| Error::UnknownModel)?;
+
+        let chip = device_info.pwm_chip();
+        let npwm = match sysfs::npwm(&sysfs::chip_path(chip)) {
+            Ok(npwm) => npwm,
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(open_error(e)),
+        };
+
+        Ok([Channel::Pwm0, Channel::Pwm1]
+            .iter()
+            .filter(|&&channel| device_info.pwm_channels()[channel as usize] < npwm)
+            .copied()
+            .collect())
+    }
+
+    /// Returns the pins that can output one of the PWM channels on this device, and
+    /// the associated channel.
+    ///
+    /// Only pins for channels returned by [`channels_available`] are included. On models
+    /// with a GPIO header, pins that aren't exposed on the header are excluded. The
+    /// returned pins can be used with [`with_pin`]. More information on the supported
+    /// pins can be found [here].
+    ///
+    /// [`channels_available`]: #method.channels_available
+    /// [`with_pin`]: #method.with_pin
+    /// [here]: index.html#pwm-pins
+    pub fn available_pins() -> Result<Vec<(Channel, u8)>> {
+        let device_info = DeviceInfo::new().map_err(|_| Error::UnknownModel)?;
+        let channels = Pwm::channels_available()?;
+        // Compute Modules don't have a GPIO header, and expose all pins
+        let has_header = !device_info.header_pins().is_empty();
+
+        Ok(pwm_pins(&device_info)
+            .iter()
+            .filter(|&&(gpio, channel, _)| {
+                channels.contains(&channel)
+                    && (!has_header || device_info.physical_pin(gpio).is_some())
+            })
+            .map(|&(gpio, channel, _)| (channel, gpio))
+            .collect())
+    }
+
     /// Constructs a new `Pwm` using the specified settings.
     ///
     /// `period` indicates the time it takes for the PWM channel to complete one cycle.
@@ -229,7 +530,7 @@ impl Pwm {
         let pwm = Pwm::new(channel)?;
 
         // Set pulse width to 0 first in case the new period is shorter than the current pulse width
-        let _ = sysfs::set_pulse_width(pwm.chip, pwm.channel, 0);
+        let _ = sysfs::set_pulse_width(&pwm.chip, pwm.channel, 0);
 
         pwm.set_period(period)?;
         pwm.set_pulse_width(pulse_width)?;
@@ -269,7 +570,7 @@ impl Pwm {
         let pwm = Pwm::new(channel)?;
 
         // Set pulse width to 0 first in case the new period is shorter than the current pulse width
-        let _ = sysfs::set_pulse_width(pwm.chip, pwm.channel, 0);
+        let _ = sysfs::set_pulse_width(&pwm.chip, pwm.channel, 0);
 
         // Convert to nanoseconds
         let period = if frequency == 0.0 {
@@ -279,8 +580,8 @@ impl Pwm {
         };
         let pulse_width = period * duty_cycle.clamp(0.0, 1.0);
 
-        sysfs::set_period(pwm.chip, pwm.channel, period as u64)?;
-        sysfs::set_pulse_width(pwm.chip, pwm.channel, pulse_width as u64)?;
+        sysfs::set_period(&pwm.chip, pwm.channel, period as u64)?;
+        sysfs::set_pulse_width(&pwm.chip, pwm.channel, pulse_width as u64)?;
         pwm.set_polarity(polarity)?;
         if enabled {
             pwm.enable()?;
@@ -292,7 +593,7 @@ impl Pwm {
     /// Returns the period.
     pub fn period(&self) -> Result<Duration> {
         Ok(Duration::from_nanos(sysfs::period(
-            self.chip,
+            &self.chip,
             self.channel,
         )?))
     }
@@ -303,8 +604,10 @@ impl Pwm {
     ///
     /// This method will fail if `period` is shorter than the current pulse width.
     pub fn set_period(&self, period: Duration) -> Result<()> {
+        self.clear_saved();
+
         sysfs::set_period(
-            self.chip,
+            &self.chip,
             self.channel,
             u64::from(period.subsec_nanos())
                 .saturating_add(period.as_secs().saturating_mul(NANOS_PER_SEC as u64)),
@@ -316,7 +619,7 @@ impl Pwm {
     /// Returns the pulse width.
     pub fn pulse_width(&self) -> Result<Duration> {
         Ok(Duration::from_nanos(sysfs::pulse_width(
-            self.chip,
+            &self.chip,
             self.channel,
         )?))
     }
@@ -328,8 +631,10 @@ impl Pwm {
     ///
     /// This method will fail if `pulse_width` is longer than the current period.
     pub fn set_pulse_width(&self, pulse_width: Duration) -> Result<()> {
+        self.clear_saved();
+
         sysfs::set_pulse_width(
-            self.chip,
+            &self.chip,
             self.channel,
             u64::from(pulse_width.subsec_nanos())
                 .saturating_add(pulse_width.as_secs().saturating_mul(NANOS_PER_SEC as u64)),
@@ -343,7 +648,7 @@ impl Pwm {
     /// `frequency` is a convenience method that calculates the frequency in hertz (Hz)
     /// based on the configured period.
     pub fn frequency(&self) -> Result<f64> {
-        let period = sysfs::period(self.chip, self.channel)? as f64;
+        let period = sysfs::period(&self.chip, self.channel)? as f64;
 
         Ok(if period == 0.0 {
             0.0
@@ -361,8 +666,10 @@ impl Pwm {
     ///
     /// `duty_cycle` is specified as a floating point value between `0.0` (0%) and `1.0` (100%).
     pub fn set_frequency(&self, frequency: f64, duty_cycle: f64) -> Result<()> {
+        self.clear_saved();
+
         // Set duty cycle to 0 first in case the new period is shorter than the current duty cycle
-        let _ = sysfs::set_pulse_width(self.chip, self.channel, 0);
+        let _ = sysfs::set_pulse_width(&self.chip, self.channel, 0);
 
         // Convert to nanoseconds
         let period = if frequency == 0.0 {
@@ -372,8 +679,8 @@ impl Pwm {
         };
         let pulse_width = period * duty_cycle.clamp(0.0, 1.0);
 
-        sysfs::set_period(self.chip, self.channel, period as u64)?;
-        sysfs::set_pulse_width(self.chip, self.channel, pulse_width as u64)?;
+        sysfs::set_period(&self.chip, self.channel, period as u64)?;
+        sysfs::set_pulse_width(&self.chip, self.channel, pulse_width as u64)?;
 
         Ok(())
     }
@@ -384,8 +691,8 @@ impl Pwm {
     /// floating point value between `0.0` (0%) and `1.0` (100%) based on the configured
     /// period and pulse width.
     pub fn duty_cycle(&self) -> Result<f64> {
-        let period = sysfs::period(self.chip, self.channel)? as f64;
-        let pulse_width = sysfs::pulse_width(self.chip, self.channel)? as f64;
+        let period = sysfs::period(&self.chip, self.channel)? as f64;
+        let pulse_width = sysfs::pulse_width(&self.chip, self.channel)? as f64;
 
         Ok(if period == 0.0 {
             0.0
@@ -401,17 +708,178 @@ impl Pwm {
     ///
     /// `duty_cycle` is specified as a floating point value between `0.0` (0%) and `1.0` (100%).
     pub fn set_duty_cycle(&self, duty_cycle: f64) -> Result<()> {
-        let period = sysfs::period(self.chip, self.channel)? as f64;
+        self.clear_saved();
+
+        let period = sysfs::period(&self.chip, self.channel)? as f64;
         let pulse_width = period * duty_cycle.clamp(0.0, 1.0);
 
-        sysfs::set_pulse_width(self.chip, self.channel, pulse_width as u64)?;
+        sysfs::set_pulse_width(&self.chip, self.channel, pulse_width as u64)?;
 
         Ok(())
     }
 
+    /// Gradually changes the duty cycle to `target_duty_cycle` on a background thread.
+    ///
+    /// `fade_to` is a convenience method that returns immediately after spawning
+    /// a thread, which interpolates between the current and the target duty cycle
+    /// over the specified `duration` based on the selected `easing` curve. The duty
+    /// cycle is updated every 10 ms. Use the returned [`JoinHandle`] to wait for the fade
+    /// to complete, and to retrieve any errors.
+    ///
+    /// `target_duty_cycle` is specified as a floating point value between `0.0` (0%)
+    /// and `1.0` (100%).
+    ///
+    /// ## Note
+    ///
+    /// Changing the period or duty cycle while a fade is in progress, or starting a
+    /// new fade before the previous one has completed, results in unpredictable behavior.
+    ///
+    /// If `Pwm` goes out of scope while the fade is in progress, the fade is stopped
+    /// before the channel is reset, and leaves the duty cycle at its last interpolated
+    /// value.
+    ///
+    /// [`JoinHandle`]: https://doc.rust-lang.org/std/thread/struct.JoinHandle.html
+    pub fn fade_to(
+        &self,
+        target_duty_cycle: f64,
+        duration: Duration,
+        easing: Easing,
+    ) -> Result<thread::JoinHandle<Result<()>>> {
+        self.clear_saved();
+
+        let start_duty_cycle = self.duty_cycle()?;
+        let chip = self.chip.clone();
+        let channel = self.channel;
+        let fades = self.fades.clone();
+
+        Ok(thread::spawn(move || {
+            let _running = fades.running.read().unwrap();
+
+            fade(
+                &chip,
+                channel,
+                start_duty_cycle,
+                target_duty_cycle,
+                duration,
+                easing,
+                &fades.stop,
+            )
+        }))
+    }
+
+    /// Gradually changes the duty cycle to `target_duty_cycle`.
+    ///
+    /// `fade_to_blocking` interpolates between the current and the target duty cycle
+    /// over the specified `duration` based on the selected `easing` curve, and blocks
+    /// until the fade has completed. The duty cycle is updated every 10 ms.
+    ///
+    /// `target_duty_cycle` is specified as a floating point value between `0.0` (0%)
+    /// and `1.0` (100%).
+    pub fn fade_to_blocking(
+        &self,
+        target_duty_cycle: f64,
+        duration: Duration,
+        easing: Easing,
+    ) -> Result<()> {
+        self.clear_saved();
+
+        let start_duty_cycle = self.duty_cycle()?;
+
+        fade(
+            &self.chip,
+            self.channel,
+            start_duty_cycle,
+            target_duty_cycle,
+            duration,
+            easing,
+            &self.fades.stop,
+        )
+    }
+
+    /// Shifts out `words` as a serial bit stream through the PWM FIFO.
+    ///
+    /// Each word is sent MSB first, with the bits clocked out at `rate_hz` bits per
+    /// second. The bit rate is derived from PLLD (500 MHz on the BCM283x, 750 MHz on
+    /// the BCM2711) using an integer divider between 2 and 4095, and is rounded to the
+    /// closest available rate. More information can be found [here].
+    ///
+    /// `write_fifo` temporarily reconfigures the PWM clock and the channel's registers,
+    /// and blocks until the last word has been sent. Afterwards, the previous register
+    /// values are restored, and the channel continues where it left off.
+    ///
+    /// The FIFO is only available on the BCM283x and BCM2711. On other SoCs,
+    /// `write_fifo` returns `Err(`[`Error::Io`]`)` with an `io::ErrorKind::Unsupported`
+    /// error. If `rate_hz` is `0`, or the channel isn't PWM0 channel 0 or 1, which can
+    /// happen for `Pwm` instances constructed with [`with_chip_path`], `write_fifo`
+    /// returns `Err(`[`Error::Io`]`)` with an `io::ErrorKind::InvalidInput` error.
+    ///
+    /// If the words haven't been sent within twice the expected duration plus 100 ms,
+    /// for instance because the PWM clock isn't running, `write_fifo` discards the
+    /// remaining words, restores the previous register values, and returns
+    /// `Err(`[`Error::Io`]`)` with an `io::ErrorKind::TimedOut` error.
+    ///
+    /// ## Note
+    ///
+    /// The FIFO is shared between both channels. Don't use the other channel while
+    /// `write_fifo` is in progress.
+    ///
+    /// [here]: index.html#fifo-output
+    /// [`with_chip_path`]: #method.with_chip_path
+    /// [`Error::Io`]: enum.Error.html#variant.Io
+    pub fn write_fifo(&self, words: &[u32], rate_hz: u32) -> Result<()> {
+        if rate_hz == 0 {
+            return Err(Error::Io(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "FIFO bit rate can't be 0",
+            )));
+        }
+
+        // The PWM registers only contain the settings for channels 0 and 1
+        if self.channel > 1 {
+            return Err(Error::Io(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "FIFO is only available for PWM channels 0 and 1",
+            )));
+        }
+
+        fifo::PwmFifo::open()?.write(self.channel, words, rate_hz)
+    }
+
+    /// Returns the number of distinct duty cycle steps available for the
+    /// configured period.
+    ///
+    /// The pulse width is rounded to a whole number of PWM clock ticks.
+    /// `resolution` calculates the number of clock ticks in a single period, based
+    /// on the PWM clock rate set by the device tree. More information can be
+    /// found [here].
+    ///
+    /// [here]: index.html#clock-and-resolution
+    pub fn resolution(&self) -> Result<u64> {
+        let period = sysfs::period(&self.chip, self.channel)?;
+
+        Ok((u128::from(period) * u128::from(self.clock_rate) / NANOS_PER_SEC as u128) as u64)
+    }
+
+    /// Returns the effective duty cycle resolution in bits for the configured period.
+    ///
+    /// `resolution_bits` is a convenience method that returns the number of bits
+    /// required to represent all duty cycle steps returned by [`resolution`],
+    /// rounded down.
+    ///
+    /// [`resolution`]: #method.resolution
+    pub fn resolution_bits(&self) -> Result<u32> {
+        let steps = self.resolution()?;
+
+        Ok(if steps == 0 {
+            0
+        } else {
+            63 - steps.leading_zeros()
+        })
+    }
+
     /// Returns the polarity.
     pub fn polarity(&self) -> Result<Polarity> {
-        Ok(sysfs::polarity(self.chip, self.channel)?)
+        Ok(sysfs::polarity(&self.chip, self.channel)?)
     }
 
     /// Sets the polarity.
@@ -422,30 +890,74 @@ impl Pwm {
     /// [`Normal`]: enum.Polarity.html#variant.Normal
     /// [`Inverse`]: enum.Polarity.html#variant.Inverse
     pub fn set_polarity(&self, polarity: Polarity) -> Result<()> {
-        sysfs::set_polarity(self.chip, self.channel, polarity)?;
+        sysfs::set_polarity(&self.chip, self.channel, polarity)?;
 
         Ok(())
     }
 
     /// Returns `true` if the PWM channel is enabled.
     pub fn is_enabled(&self) -> Result<bool> {
-        Ok(sysfs::enabled(self.chip, self.channel)?)
+        Ok(sysfs::enabled(&self.chip, self.channel)?)
     }
 
     /// Enables the PWM channel.
+    ///
+    /// If the channel was disabled with [`disable`], `enable` resumes the output with
+    /// the period and pulse width that were configured at that time. Some PWM drivers
+    /// reset the pulse width when a channel is disabled, in which case the saved settings
+    /// are applied again before the channel is enabled. If the period, pulse width,
+    /// frequency or duty cycle was changed through `Pwm` while the channel was disabled,
+    /// the new settings are kept instead.
+    ///
+    /// [`disable`]: #method.disable
     pub fn enable(&self) -> Result<()> {
-        sysfs::set_enabled(self.chip, self.channel, true)?;
+        if let Some((period, pulse_width)) = self.saved.lock().unwrap().take() {
+            let current_period = sysfs::period(&self.chip, self.channel)?;
+            let mut current_pulse_width = sysfs::pulse_width(&self.chip, self.channel)?;
+
+            // The settings weren't changed since the channel was disabled, so anything
+            // that differs was reset by the driver. The pulse width can't exceed the
+            // period, which determines the order the settings are restored in.
+            if current_pulse_width > period {
+                sysfs::set_pulse_width(&self.chip, self.channel, pulse_width)?;
+                current_pulse_width = pulse_width;
+            }
+
+            if current_period != period {
+                sysfs::set_period(&self.chip, self.channel, period)?;
+            }
+
+            if current_pulse_width != pulse_width {
+                sysfs::set_pulse_width(&self.chip, self.channel, pulse_width)?;
+            }
+        }
+
+        sysfs::set_enabled(&self.chip, self.channel, true)?;
 
         Ok(())
     }
 
     /// Disables the PWM channel.
+    ///
+    /// `disable` stops the output without changing the configured period, pulse width
+    /// or polarity, so the channel can be resumed with [`enable`].
+    ///
+    /// [`enable`]: #method.enable
     pub fn disable(&self) -> Result<()> {
-        sysfs::set_enabled(self.chip, self.channel, false)?;
+        let period = sysfs::period(&self.chip, self.channel)?;
+        let pulse_width = sysfs::pulse_width(&self.chip, self.channel)?;
+        *self.saved.lock().unwrap() = Some((period, pulse_width));
+
+        sysfs::set_enabled(&self.chip, self.channel, false)?;
 
         Ok(())
     }
 
+    // Discards the settings saved by disable, so enable doesn't overwrite any changes
+    fn clear_saved(&self) {
+        *self.saved.lock().unwrap() = None;
+    }
+
     /// Returns the value of `reset_on_drop`.
     pub fn reset_on_drop(&self) -> bool {
         self.reset_on_drop
@@ -466,11 +978,57 @@ impl Pwm {
     }
 }
 
+// Interpolates the duty cycle between start and target over the specified duration,
+// or until stop is set
+fn fade(
+    chip: &str,
+    channel: u8,
+    start: f64,
+    target: f64,
+    duration: Duration,
+    easing: Easing,
+    stop: &AtomicBool,
+) -> Result<()> {
+    let period = sysfs::period(chip, channel)? as f64;
+    let target = target.clamp(0.0, 1.0);
+    let start_time = Instant::now();
+
+    loop {
+        if stop.load(Ordering::SeqCst) {
+            return Ok(());
+        }
+
+        let elapsed = start_time.elapsed();
+        if elapsed >= duration {
+            break;
+        }
+
+        let t = elapsed.as_secs_f64() / duration.as_secs_f64();
+        let duty_cycle = start + (target - start) * easing.apply(t);
+        sysfs::set_pulse_width(chip, channel, (period * duty_cycle) as u64)?;
+
+        thread::sleep(FADE_INTERVAL.min(duration - elapsed));
+    }
+
+    sysfs::set_pulse_width(chip, channel, (period * target) as u64)?;
+
+    Ok(())
+}
+
 impl Drop for Pwm {
     fn drop(&mut self) {
+        // Stop any background fades, and wait until they no longer access the channel.
+        // A fade that hasn't started yet sees the stop flag once it's running.
+        self.fades.stop.store(true, Ordering::SeqCst);
+        drop(self.fades.running.write());
+
         if self.reset_on_drop {
-            let _ = sysfs::set_enabled(self.chip, self.channel, false);
-            let _ = sysfs::unexport(self.chip, self.channel);
+            let _ = sysfs::set_enabled(&self.chip, self.channel, false);
+            let _ = sysfs::unexport(&self.chip, self.channel);
+
+            if let Some((ref mut pin, prev_mode)) = self.pin {
+                pin.set_mode(prev_mode);
+            }
         }
     }
 }
@@ -0,0 +1,299 @@
+use std::fs::OpenOptions;
+use std::io;
+use std::os::unix::fs::OpenOptionsExt;
+use std::os::unix::io::{AsRawFd, RawFd};
+use std::ptr;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use libc::{self, c_void, size_t, MAP_FAILED, MAP_SHARED, O_SYNC, PROT_READ, PROT_WRITE};
+
+use super::{Error, Result};
+use crate::system::{self, DeviceInfo, SoC};
+
+const PATH_DEV_MEM: &str = "/dev/mem";
+
+// The PWM clock registers end at CM_PWMDIV (0xa4)
+const CM_MEM_SIZE: usize = 0xa8;
+const CM_PWMCTL: usize = 0xa0 / std::mem::size_of::<u32>();
+const CM_PWMDIV: usize = 0xa4 / std::mem::size_of::<u32>();
+
+// All clock manager writes need to include the password in the MSB
+const CM_PASSWD: u32 = 0x5a << 24;
+const CM_CTL_ENAB: u32 = 1 << 4;
+const CM_CTL_BUSY: u32 = 1 << 7;
+const CM_CTL_MASK: u32 = 0x00ff_ffff;
+const CM_DIVI_SHIFT: u32 = 12;
+const CM_SRC_PLLD: u32 = 6;
+
+// The integer divider is 12 bits wide
+const DIVI_MIN: u32 = 2;
+const DIVI_MAX: u32 = 4095;
+
+const PWM_MEM_SIZE: usize = 0x28;
+const PWM_CTL: usize = 0x00;
+const PWM_STA: usize = 0x04 / std::mem::size_of::<u32>();
+const PWM_FIF1: usize = 0x18 / std::mem::size_of::<u32>();
+// RNG2 is located 0x10 bytes after RNG1
+const PWM_RNG1: usize = 0x10 / std::mem::size_of::<u32>();
+
+// Control register bits for channel 1. Channel 2 uses the same bits shifted by 8.
+const CTL_PWEN: u32 = 1 << 0;
+const CTL_MODE: u32 = 1 << 1;
+const CTL_USEF: u32 = 1 << 5;
+const CTL_CLRF: u32 = 1 << 6;
+const CTL_CHANNEL_MASK: u32 = 0xff;
+
+const STA_FULL: u32 = 1 << 0;
+const STA_EMPT: u32 = 1 << 1;
+// Write/read error and gap flags, cleared by writing 1
+const STA_ERRORS: u32 = 0x1fc;
+
+// Maximum number of times we check the BUSY flag after disabling the clock
+const BUSY_RETRIES: u32 = 100;
+
+// Number of bits shifted out for each FIFO word
+const BITS_PER_WORD: u32 = 32;
+// FIFO depth in words
+const FIFO_DEPTH: u32 = 8;
+// Additional time allowed for scheduling delays before a write times out
+const TIMEOUT_SLACK: Duration = Duration::from_millis(100);
+
+// Memory-mapped PWM and clock manager registers, used to feed the PWM FIFO
+pub(crate) struct PwmFifo {
+    cm_ptr: *mut u32,
+    pwm_ptr: *mut u32,
+    source_clock: u32,
+}
+
+impl PwmFifo {
+    pub(crate) fn open() -> Result<PwmFifo> {
+        let device_info = DeviceInfo::new().map_err(|_| Error::UnknownModel)?;
+
+        let source_clock = match device_info.soc() {
+            SoC::Bcm2835 | SoC::Bcm2836 | SoC::Bcm2837A1 | SoC::Bcm2837B0 => 500_000_000,
+            SoC::Bcm2711 => 750_000_000,
+            SoC::Bcm2712 => return Err(unsupported()),
+        };
+
+        let addresses = device_info.peripheral_addresses();
+        let (cm_address, pwm_address) = match (addresses.clock_manager, addresses.pwm) {
+            (Some(cm_address), Some(pwm_address)) => (cm_address, pwm_address),
+            _ => return Err(unsupported()),
+        };
+
+        // The PWM and clock manager registers aren't accessible through /dev/gpiomem
+        let mem_file = match OpenOptions::new()
+            .read(true)
+            .write(true)
+            .custom_flags(O_SYNC)
+            .open(PATH_DEV_MEM)
+        {
+            Ok(file) => file,
+            Err(ref e) if e.kind() == io::ErrorKind::PermissionDenied => {
+                return Err(Error::PermissionDenied)
+            }
+            Err(e) => return Err(Error::Io(e)),
+        };
+
+        let cm_ptr = map(mem_file.as_raw_fd(), cm_address, CM_MEM_SIZE)?;
+
+        let pwm_ptr = match map(mem_file.as_raw_fd(), pwm_address, PWM_MEM_SIZE) {
+            Ok(pwm_ptr) => pwm_ptr,
+            Err(e) => {
+                unsafe {
+                    libc::munmap(cm_ptr as *mut c_void, CM_MEM_SIZE as size_t);
+                }
+
+                return Err(e);
+            }
+        };
+
+        Ok(PwmFifo {
+            cm_ptr,
+            pwm_ptr,
+            source_clock,
+        })
+    }
+
+    // Shifts out the words through the FIFO for the specified channel, and blocks
+    // until the last word has been sent. The original register values are restored
+    // afterwards. If the words aren't sent within twice the expected duration, which
+    // happens when the PWM clock isn't running, an io::ErrorKind::TimedOut error is
+    // returned.
+    pub(crate) fn write(&self, channel: u8, words: &[u32], rate_hz: u32) -> Result<()> {
+        let shift = u32::from(channel) * 8;
+        let rng = PWM_RNG1 + usize::from(channel) * 4;
+
+        let saved_ctl = read(self.pwm_ptr, PWM_CTL);
+        let saved_rng = read(self.pwm_ptr, rng);
+        let saved_clk_ctl = read(self.cm_ptr, CM_PWMCTL);
+        let saved_clk_div = read(self.cm_ptr, CM_PWMDIV);
+
+        // Disable the channel before changing the clock
+        write(self.pwm_ptr, PWM_CTL, saved_ctl & !(CTL_PWEN << shift));
+
+        let divi = ((f64::from(self.source_clock) / f64::from(rate_hz)).round() as u32)
+            .clamp(DIVI_MIN, DIVI_MAX);
+        self.set_clock(CM_SRC_PLLD, divi << CM_DIVI_SHIFT);
+
+        write(self.pwm_ptr, rng, BITS_PER_WORD);
+        write(self.pwm_ptr, PWM_STA, STA_ERRORS);
+
+        // Clearing the FIFO affects both channels
+        let ctl = (saved_ctl & !(CTL_CHANNEL_MASK << shift)) | ((CTL_MODE | CTL_USEF) << shift);
+        write(self.pwm_ptr, PWM_CTL, ctl | CTL_CLRF);
+
+        // Fill the FIFO before enabling the channel, so the output doesn't start with a gap
+        let mut words = words.iter().peekable();
+        while read(self.pwm_ptr, PWM_STA) & STA_FULL == 0 {
+            match words.next() {
+                Some(&word) => write(self.pwm_ptr, PWM_FIF1, word),
+                None => break,
+            }
+        }
+
+        write(self.pwm_ptr, PWM_CTL, ctl | (CTL_PWEN << shift));
+
+        let word_duration = Duration::from_nanos(
+            (1_000_000_000u64 * u64::from(BITS_PER_WORD) * u64::from(divi))
+                / u64::from(self.source_clock),
+        );
+
+        // Without a deadline, we'd wait forever if the PWM clock isn't running. If the
+        // timeout doesn't fit in an Instant, we don't set a deadline at all.
+        let deadline = u32::try_from(words.len())
+            .ok()
+            .and_then(|len| len.checked_add(FIFO_DEPTH))
+            .and_then(|len| word_duration.checked_mul(len))
+            .and_then(|duration| duration.checked_mul(2))
+            .and_then(|duration| duration.checked_add(TIMEOUT_SLACK))
+            .and_then(|timeout| Instant::now().checked_add(timeout));
+        let timed_out = || deadline.map_or(false, |deadline| Instant::now() >= deadline);
+
+        let mut result = Ok(());
+
+        // At high rates, a word is sent every few hundred nanoseconds, which is too short
+        // to sleep in between.
+        while let Some(&&word) = words.peek() {
+            if read(self.pwm_ptr, PWM_STA) & STA_FULL == 0 {
+                write(self.pwm_ptr, PWM_FIF1, word);
+                words.next();
+            } else if timed_out() {
+                result = Err(timeout_error());
+                break;
+            } else {
+                thread::yield_now();
+            }
+        }
+
+        while result.is_ok() && read(self.pwm_ptr, PWM_STA) & STA_EMPT == 0 {
+            if timed_out() {
+                result = Err(timeout_error());
+            } else {
+                thread::yield_now();
+            }
+        }
+
+        // The last word is still being shifted out after the FIFO is empty
+        if result.is_ok() {
+            thread::sleep(word_duration);
+        }
+
+        write(self.pwm_ptr, PWM_CTL, saved_ctl & !(CTL_PWEN << shift));
+        self.set_clock(saved_clk_ctl & 0x0f, saved_clk_div & CM_CTL_MASK);
+        write(self.pwm_ptr, rng, saved_rng);
+        write(self.pwm_ptr, PWM_STA, STA_ERRORS);
+
+        // Discard any words that weren't sent before the timeout
+        if result.is_err() {
+            write(
+                self.pwm_ptr,
+                PWM_CTL,
+                (saved_ctl & !(CTL_PWEN << shift)) | CTL_CLRF,
+            );
+        }
+
+        write(self.pwm_ptr, PWM_CTL, saved_ctl);
+
+        result
+    }
+
+    // Stops the PWM clock, changes its source and divider, and restarts it
+    fn set_clock(&self, source: u32, div: u32) {
+        write(
+            self.cm_ptr,
+            CM_PWMCTL,
+            CM_PASSWD | (read(self.cm_ptr, CM_PWMCTL) & CM_CTL_MASK & !CM_CTL_ENAB),
+        );
+
+        for _ in 0..BUSY_RETRIES {
+            if read(self.cm_ptr, CM_PWMCTL) & CM_CTL_BUSY == 0 {
+                break;
+            }
+
+            thread::sleep(Duration::from_micros(10));
+        }
+
+        write(self.cm_ptr, CM_PWMDIV, CM_PASSWD | div);
+        // The clock source needs to be set before the clock is enabled
+        write(self.cm_ptr, CM_PWMCTL, CM_PASSWD | source);
+        write(self.cm_ptr, CM_PWMCTL, CM_PASSWD | source | CM_CTL_ENAB);
+    }
+}
+
+impl Drop for PwmFifo {
+    fn drop(&mut self) {
+        unsafe {
+            libc::munmap(self.cm_ptr as *mut c_void, CM_MEM_SIZE as size_t);
+            libc::munmap(self.pwm_ptr as *mut c_void, PWM_MEM_SIZE as size_t);
+        }
+    }
+}
+
+fn map(fd: RawFd, address: u64, size: usize) -> Result<*mut u32> {
+    let offset = system::devmem_offset(address)?;
+
+    let mem_ptr = unsafe {
+        libc::mmap(
+            ptr::null_mut(),
+            size,
+            PROT_READ | PROT_WRITE,
+            MAP_SHARED,
+            fd,
+            offset,
+        )
+    };
+
+    if mem_ptr == MAP_FAILED {
+        return Err(Error::Io(io::Error::last_os_error()));
+    }
+
+    Ok(mem_ptr as *mut u32)
+}
+
+#[inline(always)]
+fn read(mem_ptr: *mut u32, offset: usize) -> u32 {
+    unsafe { ptr::read_volatile(mem_ptr.add(offset)) }
+}
+
+#[inline(always)]
+fn write(mem_ptr: *mut u32, offset: usize, value: u32) {
+    unsafe {
+        ptr::write_volatile(mem_ptr.add(offset), value);
+    }
+}
+
+fn timeout_error() -> Error {
+    Error::Io(io::Error::new(
+        io::ErrorKind::TimedOut,
+        "the PWM FIFO didn't drain, which usually means the PWM clock isn't running",
+    ))
+}
+
+fn unsupported() -> Error {
+    Error::Io(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "the PWM FIFO is only supported on BCM283x and BCM2711",
+    ))
+}
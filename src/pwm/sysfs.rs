@@ -98,11 +98,34 @@ fn check_permissions(path: &str, gid: u32) -> bool {
     false
 }
 
-pub fn export(chip: u8, channel: u8) -> Result<()> {
+// Returns the sysfs path of the specified PWM chip
+pub fn chip_path(chip: u8) -> String {
+    format!("/sys/class/pwm/pwmchip{}", chip)
+}
+
+// Returns the number of channels supported by the PWM chip
+pub fn npwm(chip: &str) -> Result<u8> {
+    let npwm = fs::read_to_string(format!("{}/npwm", chip))?;
+    if let Ok(npwm) = npwm.trim().parse() {
+        Ok(npwm)
+    } else {
+        Ok(0)
+    }
+}
+
+pub fn export(chip: &str, channel: u8) -> Result<()> {
     // Only export if the channel isn't already exported
-    if !Path::new(&format!("/sys/class/pwm/pwmchip{}/pwm{}", chip, channel)).exists() {
-        File::create(format!("/sys/class/pwm/pwmchip{}/export", chip))?
-            .write_fmt(format_args!("{}", channel))?;
+    let channel_path = format!("{}/pwm{}", chip, channel);
+    if !Path::new(&channel_path).exists() {
+        if let Err(e) =
+            File::create(format!("{}/export", chip))?.write_fmt(format_args!("{}", channel))
+        {
+            // EBUSY is returned when the channel was exported in the meantime (which
+            // is fine), or when it's in use by another driver.
+            if e.raw_os_error() != Some(libc::EBUSY) || !Path::new(&channel_path).exists() {
+                return Err(e);
+            }
+        }
     }
 
     // If we're logged in as root or effective root, skip the permission checks
@@ -127,11 +150,11 @@ pub fn export(chip: u8, channel: u8) -> Result<()> {
     };
 
     let paths = &[
-        format!("/sys/class/pwm/pwmchip{}/pwm{}", chip, channel),
-        format!("/sys/class/pwm/pwmchip{}/pwm{}/period", chip, channel),
-        format!("/sys/class/pwm/pwmchip{}/pwm{}/duty_cycle", chip, channel),
-        format!("/sys/class/pwm/pwmchip{}/pwm{}/polarity", chip, channel),
-        format!("/sys/class/pwm/pwmchip{}/pwm{}/enable", chip, channel),
+        format!("{}/pwm{}", chip, channel),
+        format!("{}/pwm{}/period", chip, channel),
+        format!("{}/pwm{}/duty_cycle", chip, channel),
+        format!("{}/pwm{}/polarity", chip, channel),
+        format!("{}/pwm{}/enable", chip, channel),
     ];
 
     let mut counter = 0;
@@ -152,21 +175,17 @@ pub fn export(chip: u8, channel: u8) -> Result<()> {
     Ok(())
 }
 
-pub fn unexport(chip: u8, channel: u8) -> Result<()> {
+pub fn unexport(chip: &str, channel: u8) -> Result<()> {
     // Only unexport if the channel is actually exported
-    if Path::new(&format!("/sys/class/pwm/pwmchip{}/pwm{}", chip, channel)).exists() {
-        File::create(format!("/sys/class/pwm/pwmchip{}/unexport", chip))?
-            .write_fmt(format_args!("{}", channel))?;
+    if Path::new(&format!("{}/pwm{}", chip, channel)).exists() {
+        File::create(format!("{}/unexport", chip))?.write_fmt(format_args!("{}", channel))?;
     }
 
     Ok(())
 }
 
-pub fn period(chip: u8, channel: u8) -> Result<u64> {
-    let period = fs::read_to_string(format!(
-        "/sys/class/pwm/pwmchip{}/pwm{}/period",
-        chip, channel
-    ))?;
+pub fn period(chip: &str, channel: u8) -> Result<u64> {
+    let period = fs::read_to_string(format!("{}/pwm{}/period", chip, channel))?;
     if let Ok(period) = period.trim().parse() {
         Ok(period)
     } else {
@@ -174,23 +193,17 @@ pub fn period(chip: u8, channel: u8) -> Result<u64> {
     }
 }
 
-pub fn set_period(chip: u8, channel: u8, period: u64) -> Result<()> {
-    File::create(format!(
-        "/sys/class/pwm/pwmchip{}/pwm{}/period",
-        chip, channel
-    ))?
-    .write_fmt(format_args!("{}", period))?;
+pub fn set_period(chip: &str, channel: u8, period: u64) -> Result<()> {
+    File::create(format!("{}/pwm{}/period", chip, channel))?
+        .write_fmt(format_args!("{}", period))?;
 
     Ok(())
 }
 
-pub fn pulse_width(chip: u8, channel: u8) -> Result<u64> {
+pub fn pulse_width(chip: &str, channel: u8) -> Result<u64> {
     // The sysfs PWM interface specifies the duty cycle in nanoseconds, which
     // means it's actually the pulse width.
-    let duty_cycle = fs::read_to_string(format!(
-        "/sys/class/pwm/pwmchip{}/pwm{}/duty_cycle",
-        chip, channel
-    ))?;
+    let duty_cycle = fs::read_to_string(format!("{}/pwm{}/duty_cycle", chip, channel))?;
 
     if let Ok(duty_cycle) = duty_cycle.trim().parse() {
         Ok(duty_cycle)
@@ -199,23 +212,17 @@ pub fn pulse_width(chip: u8, channel: u8) -> Result<u64> {
     }
 }
 
-pub fn set_pulse_width(chip: u8, channel: u8, pulse_width: u64) -> Result<()> {
+pub fn set_pulse_width(chip: &str, channel: u8, pulse_width: u64) -> Result<()> {
     // The sysfs PWM interface specifies the duty cycle in nanoseconds, which
     // means it's actually the pulse width.
-    File::create(format!(
-        "/sys/class/pwm/pwmchip{}/pwm{}/duty_cycle",
-        chip, channel
-    ))?
-    .write_fmt(format_args!("{}", pulse_width))?;
+    File::create(format!("{}/pwm{}/duty_cycle", chip, channel))?
+        .write_fmt(format_args!("{}", pulse_width))?;
 
     Ok(())
 }
 
-pub fn polarity(chip: u8, channel: u8) -> Result<Polarity> {
-    let polarity = fs::read_to_string(format!(
-        "/sys/class/pwm/pwmchip{}/pwm{}/polarity",
-        chip, channel
-    ))?;
+pub fn polarity(chip: &str, channel: u8) -> Result<Polarity> {
+    let polarity = fs::read_to_string(format!("{}/pwm{}/polarity", chip, channel))?;
 
     match polarity.trim() {
         "normal" => Ok(Polarity::Normal),
@@ -223,26 +230,19 @@ pub fn polarity(chip: u8, channel: u8) -> Result<Polarity> {
     }
 }
 
-pub fn set_polarity(chip: u8, channel: u8, polarity: Polarity) -> Result<()> {
+pub fn set_polarity(chip: &str, channel: u8, polarity: Polarity) -> Result<()> {
     let b_polarity: &[u8] = match polarity {
         Polarity::Normal => b"normal",
         Polarity::Inverse => b"inversed",
     };
 
-    File::create(format!(
-        "/sys/class/pwm/pwmchip{}/pwm{}/polarity",
-        chip, channel
-    ))?
-    .write_all(b_polarity)?;
+    File::create(format!("{}/pwm{}/polarity", chip, channel))?.write_all(b_polarity)?;
 
     Ok(())
 }
 
-pub fn enabled(chip: u8, channel: u8) -> Result<bool> {
-    let enabled = fs::read_to_string(format!(
-        "/sys/class/pwm/pwmchip{}/pwm{}/enable",
-        chip, channel
-    ))?;
+pub fn enabled(chip: &str, channel: u8) -> Result<bool> {
+    let enabled = fs::read_to_string(format!("{}/pwm{}/enable", chip, channel))?;
 
     match enabled.trim() {
         "0" => Ok(false),
@@ -250,22 +250,19 @@ pub fn enabled(chip: u8, channel: u8) -> Result<bool> {
     }
 }
 
-pub fn set_enabled(chip: u8, channel: u8, enabled: bool) -> Result<()> {
-    File::create(format!(
-        "/sys/class/pwm/pwmchip{}/pwm{}/enable",
-        chip, channel
-    ))?
-    .write_fmt(format_args!("{}", enabled as u8))
-    .map_err(|e| {
-        if e.kind() == io::ErrorKind::InvalidInput {
-            io::Error::new(
-                io::ErrorKind::InvalidInput,
-                "Make sure you have set either a period or frequency before enabling PWM",
-            )
-        } else {
-            e
-        }
-    })?;
+pub fn set_enabled(chip: &str, channel: u8, enabled: bool) -> Result<()> {
+    File::create(format!("{}/pwm{}/enable", chip, channel))?
+        .write_fmt(format_args!("{}", enabled as u8))
+        .map_err(|e| {
+            if e.kind() == io::ErrorKind::InvalidInput {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "Make sure you have set either a period or frequency before enabling PWM",
+                )
+            } else {
+                e
+            }
+        })?;
 
     Ok(())
 }
@@ -107,17 +107,75 @@
 //! Remember to reboot the Raspberry Pi afterwards. The current value of bufsiz
 //! can be checked with `cat /sys/module/spidev/parameters/bufsiz`.
 //!
+//! ## Blocking behavior
+//!
+//! All reads, writes and transfers block the calling thread until the entire transfer
+//! has completed. `spidev` doesn't support non-blocking I/O, so opening the device in
+//! non-blocking mode wouldn't have any effect. The time spent waiting mostly depends on the
+//! number of bytes and the clock speed. At 1 MHz, each byte takes 8 µs to transfer, in
+//! addition to a fixed overhead of typically 10 to 50 µs per call for the system call and
+//! driver setup. Any delays configured for a [`Segment`] are added to the total duration.
+//!
+//! If your application uses a single-threaded event loop, consider moving SPI transfers
+//! to a separate thread, and reporting the results back through a channel.
+//!
+//! ## Three-wire mode
+//!
+//! Some slave devices use a single bidirectional data line instead of separate MOSI
+//! and MISO lines. [`set_3wire`] enables `SPI_3WIRE` mode, where the data line is
+//! connected to MOSI. During writes, MOSI is driven by the Raspberry Pi. During reads,
+//! MOSI is switched to an input, and the slave device drives the line.
+//!
+//! Because data can only travel in one direction at a time, three-wire mode only supports
+//! half-duplex communication. Use [`write`] and [`read`], or a [`transfer_segments`] call
+//! where each [`Segment`] contains either a read or a write buffer. Full-duplex transfers,
+//! such as [`transfer`], are rejected by the kernel with an `io::ErrorKind::InvalidInput`
+//! error.
+//!
+//! On the BCM283x and BCM2711, three-wire mode is supported by the main SPI controller
+//! (SPI0, and SPI3-SPI6 on the BCM2711), but not by the auxiliary SPI controllers
+//! (SPI1 and SPI2). Support on the RP1 (Raspberry Pi 5) depends on the kernel driver.
+//! If the driver doesn't support three-wire mode, [`set_3wire`] returns
+//! `Err(`[`Error::ThreeWireNotSupported`]`)`.
+//!
+//! ## Clock speed
+//!
+//! The SPI clock is derived from a source clock using an integer divider, so the
+//! clock speed set with [`set_clock_speed`] is treated as a maximum, and rounded down
+//! to the closest frequency the controller can generate. [`clock_speed`] returns the
+//! requested maximum, while [`effective_clock_speed`] calculates the frequency that's
+//! actually used.
+//!
+//! * The main SPI controller on the BCM283x and BCM2711 (SPI0, and SPI3-SPI6 on the
+//!   BCM2711) divides the core clock by an even divider between 2 and 65536.
+//! * The auxiliary SPI controllers (SPI1 and SPI2) divide the core clock by
+//!   2 × (n + 1), where n is a 12-bit value.
+//! * The SPI controllers on the RP1 (Raspberry Pi 5) divide their 200 MHz reference
+//!   clock by an even divider between 2 and 65534.
+//!
+//! The core clock runs at 250 MHz on the BCM2835 and BCM2836, 400 MHz on the BCM2837,
+//! and 500 MHz on the BCM2711. Because the divider only takes even values, the
+//! available clock speeds get more coarse-grained as they approach the source clock.
+//! For instance, with a 250 MHz core clock, requesting 10 MHz results in a divider of
+//! 26, and a clock speed of 9.62 MHz.
+//!
+//! When the CPU frequency scales down, the core clock may scale down with it, which
+//! lowers the SPI clock speed by the same ratio. Add `core_freq_min` with the core
+//! clock's default frequency to `/boot/firmware/config.txt` to keep the core clock fixed.
+//!
 //! ## Not supported
 //!
 //! Some features exposed by the generic `spidev` interface aren't fully
 //! supported by the underlying driver or the BCM283x SoC: `SPI_LSB_FIRST` (LSB
-//! first bit order), `SPI_3WIRE` (bidirectional mode), `SPI_LOOP` (loopback mode),
+//! first bit order), `SPI_LOOP` (loopback mode),
 //! `SPI_NO_CS` (no Slave Select), `SPI_READY` (slave ready signal),
 //! `SPI_TX_DUAL`/`SPI_RX_DUAL` (dual SPI), `SPI_TX_QUAD`/`SPI_RX_QUAD` (quad SPI),
 //! and any number of bits per word other than 8.
 //!
-//! If your slave device requires `SPI_LSB_FIRST`, you can use the
-//! [`reverse_bits`] function instead to reverse the bit order in software.
+//! If your slave device requires `SPI_LSB_FIRST`, [`Spi::set_bit_order`] falls back
+//! to reversing the bit order in software when the driver doesn't support it.
+//! Alternatively, you can use the [`reverse_bits`] function to reverse the bit
+//! order of specific buffers yourself.
 //!
 //! `SPI_LOOP` mode can be achieved by connecting the MOSI and MISO pins
 //! together.
@@ -132,16 +190,36 @@
 //! [`Mode1`]: enum.Mode.html
 //! [`Mode3`]: enum.Mode.html
 //! [`reverse_bits`]: fn.reverse_bits.html
+//! [`Spi::set_bit_order`]: struct.Spi.html#method.set_bit_order
+//! [`Segment`]: struct.Segment.html
+//! [`set_clock_speed`]: struct.Spi.html#method.set_clock_speed
+//! [`clock_speed`]: struct.Spi.html#method.clock_speed
+//! [`effective_clock_speed`]: struct.Spi.html#method.effective_clock_speed
+//! [`set_3wire`]: struct.Spi.html#method.set_3wire
+//! [`write`]: struct.Spi.html#method.write
+//! [`read`]: struct.Spi.html#method.read
+//! [`transfer`]: struct.Spi.html#method.transfer
+//! [`transfer_segments`]: struct.Spi.html#method.transfer_segments
+//! [`Error::ThreeWireNotSupported`]: enum.Error.html#variant.ThreeWireNotSupported
 
+use std::cell::Cell;
 use std::error;
 use std::fmt;
-use std::fs::{File, OpenOptions};
+use std::fs::{self, File, OpenOptions};
 use std::io;
 use std::io::{Read, Write};
 use std::marker::PhantomData;
-use std::os::unix::io::AsRawFd;
+use std::mem;
+use std::os::unix::io::{AsRawFd, RawFd};
+use std::path::{Path, PathBuf};
 use std::result;
 
+use crate::system::{DeviceInfo, SoC};
+
+const PATH_BUFSIZ: &str = "/sys/module/spidev/parameters/bufsiz";
+// Default spidev buffer size, used when bufsiz can't be read
+const DEFAULT_BUFSIZ: usize = 4096;
+
 #[cfg(any(
     feature = "embedded-hal-0",
     feature = "embedded-hal",
@@ -149,9 +227,13 @@ use std::result;
 ))]
 mod hal;
 mod ioctl;
+mod mcp3008;
 mod segment;
+mod ws2812;
 
+pub use self::mcp3008::{InputMode, Mcp3008};
 pub use self::segment::Segment;
+pub use self::ws2812::{Rgb, Ws2812};
 #[cfg(any(
     feature = "embedded-hal-0",
     feature = "embedded-hal",
@@ -187,6 +269,35 @@ pub enum Error {
     ModeNotSupported(Mode),
     /// The specified Slave Select polarity is not supported.
     PolarityNotSupported(Polarity),
+    /// Three-wire mode is not supported.
+    ///
+    /// The SPI controller's driver doesn't support the `SPI_3WIRE` mode flag.
+    /// More information can be found [here](index.html#three-wire-mode).
+    ThreeWireNotSupported,
+    /// Unknown model.
+    ///
+    /// The Raspberry Pi model or SoC can't be identified. Support for
+    /// new models is usually added shortly after they are officially
+    /// announced and available to the public. Make sure you're using
+    /// the latest release of RPPAL.
+    ///
+    /// You may also encounter this error if your Linux distribution
+    /// doesn't provide any of the common user-accessible system files
+    /// that are used to identify the model and SoC.
+    UnknownModel,
+    /// SPI bus not enabled.
+    ///
+    /// The spidev device node doesn't exist. Make sure the SPI bus and Slave Select pin has been enabled.
+    /// More information can be found [here](index.html).
+    NotEnabled,
+    /// Permission denied.
+    ///
+    /// The current user doesn't have the required permissions to access the spidev device node.
+    PermissionDenied,
+    /// SPI bus busy.
+    ///
+    /// The SPI bus and Slave Select pin is already in use by another driver or process.
+    Busy,
 }
 
 impl fmt::Display for Error {
@@ -206,11 +317,23 @@ impl fmt::Display for Error {
             Error::PolarityNotSupported(polarity) => {
                 write!(f, "Polarity value not supported: {:?}", polarity)
             }
+            Error::ThreeWireNotSupported => write!(f, "Three-wire mode not supported"),
+            Error::UnknownModel => write!(f, "Unknown Raspberry Pi model"),
+            Error::NotEnabled => write!(f, "SPI bus not enabled"),
+            Error::PermissionDenied => write!(f, "Permission denied"),
+            Error::Busy => write!(f, "SPI bus busy"),
         }
     }
 }
 
-impl error::Error for Error {}
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            Error::Io(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
 
 impl From<io::Error> for Error {
     fn from(err: io::Error) -> Error {
@@ -218,6 +341,16 @@ impl From<io::Error> for Error {
     }
 }
 
+// Converts an error returned while opening the spidev device node into a more specific error.
+fn open_error(err: io::Error) -> Error {
+    match err.kind() {
+        io::ErrorKind::NotFound => Error::NotEnabled,
+        io::ErrorKind::PermissionDenied => Error::PermissionDenied,
+        _ if err.raw_os_error() == Some(libc::EBUSY) => Error::Busy,
+        _ => Error::Io(err),
+    }
+}
+
 /// Result type returned from methods that can have `spi::Error`s.
 pub type Result<T> = result::Result<T, Error>;
 
@@ -374,6 +507,33 @@ impl fmt::Display for Mode {
     }
 }
 
+/// Mode flags configured for the SPI device.
+///
+/// `Flags` contains the settings stored in the `spidev` mode flags, as returned by
+/// [`Spi::flags`].
+///
+/// `Flags` might be extended with additional fields in a minor or patch revision,
+/// so it can't be constructed with a struct expression outside of RPPAL. Use
+/// `Flags::default()` instead.
+///
+/// [`Spi::flags`]: struct.Spi.html#method.flags
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Default)]
+#[non_exhaustive]
+pub struct Flags {
+    /// Slave Select is active high (`SPI_CS_HIGH`).
+    pub cs_high: bool,
+    /// Data is transferred least-significant bit first (`SPI_LSB_FIRST`).
+    pub lsb_first: bool,
+    /// MOSI is used as a bidirectional data line (`SPI_3WIRE`).
+    pub three_wire: bool,
+    /// MOSI is internally connected to MISO (`SPI_LOOP`).
+    pub loopback: bool,
+    /// Slave Select isn't asserted during transfers (`SPI_NO_CS`).
+    pub no_cs: bool,
+    /// The slave device pulls MISO low to pause transfers (`SPI_READY`).
+    pub ready: bool,
+}
+
 /// Bit orders.
 ///
 /// The bit order determines in what order data is shifted out and shifted in.
@@ -383,12 +543,12 @@ impl fmt::Display for Mode {
 /// `MsbFirst` will transfer the most-significant bit first. `LsbFirst` will
 /// transfer the least-significant bit first.
 ///
-/// The Raspberry Pi currently only supports the `MsbFirst` bit order. If you
-/// need the `LsbFirst` bit order, you can use the [`reverse_bits`] function
-/// instead to reverse the bit order in software by converting your write
-/// buffer before sending it to the slave device, and your read buffer after
-/// reading any incoming data.
+/// The Raspberry Pi currently only supports the `MsbFirst` bit order in hardware.
+/// When `LsbFirst` is selected with [`Spi::set_bit_order`], the bit order is
+/// reversed in software instead. You can also use the [`reverse_bits`] function
+/// to convert specific buffers yourself.
 ///
+/// [`Spi::set_bit_order`]: struct.Spi.html#method.set_bit_order
 /// [`reverse_bits`]: fn.reverse_bits.html
 #[derive(Debug, PartialEq, Eq, Copy, Clone)]
 pub enum BitOrder {
@@ -416,6 +576,16 @@ impl fmt::Display for BitOrder {
 /// [here]: index.html
 pub struct Spi {
     spidev: File,
+    // Device node path, used by try_clone
+    path: PathBuf,
+    bus: Bus,
+    // Set when the LsbFirst bit order is emulated in software, because the driver
+    // doesn't support SPI_LSB_FIRST.
+    reverse_bits: Cell<bool>,
+    // Delays in µs applied to every transfer
+    word_delay: Cell<u8>,
+    ss_setup_delay: Cell<u16>,
+    ss_hold_delay: Cell<u16>,
     // Stores the last read value. Used for embedded_hal::spi::FullDuplex.
     #[cfg(any(feature = "embedded-hal-0", feature = "embedded-hal-nb"))]
     last_read: Option<u8>,
@@ -436,20 +606,47 @@ impl Spi {
     ///
     /// `mode` selects the clock polarity and phase.
     pub fn new(bus: Bus, slave_select: SlaveSelect, clock_speed: u32, mode: Mode) -> Result<Spi> {
+        Spi::with_path(
+            format!("/dev/spidev{}.{}", bus as u8, slave_select as u8),
+            bus,
+            clock_speed,
+            mode,
+        )
+    }
+
+    /// Constructs a new `Spi` using the spidev device node at the specified path.
+    ///
+    /// [`new`] expects the device node for `bus` and `slave_select` at
+    /// `/dev/spidev<bus>.<slave_select>`. `with_path` can be used in containers or
+    /// on custom systems where the device nodes are located elsewhere, or have been
+    /// renamed. `bus` should refer to the SPI controller the device node belongs to,
+    /// and is only used to calculate the [`effective_clock_speed`]. Otherwise,
+    /// `with_path` behaves the same as [`new`].
+    ///
+    /// [`new`]: #method.new
+    /// [`effective_clock_speed`]: #method.effective_clock_speed
+    pub fn with_path<P: AsRef<Path>>(
+        path: P,
+        bus: Bus,
+        clock_speed: u32,
+        mode: Mode,
+    ) -> Result<Spi> {
         // The following options currently aren't supported by spidev in Raspbian Stretch on the Pi:
         //
         // LSB_FIRST - ioctl() returns EINVAL when set
-        // 3WIRE - neither MOSI nor MISO show any outgoing data in half-duplex mode
+        // 3WIRE - only supported for half-duplex transfers (see set_3wire())
         // LOOP - ioctl() returns EINVAL when set
         // NO_CS - SS is still set to active (tried both file write() and ioctl())
         // READY - ioctl() returns EINVAL when set
         // TX_DUAL/TX_QUAD/RX_DUAL/RX_QUAD - Not supported by BCM283x
         // bits per word - any value other than 0 or 8 returns EINVAL when set
 
+        let path = path.as_ref().to_path_buf();
         let spidev = OpenOptions::new()
             .read(true)
             .write(true)
-            .open(format!("/dev/spidev{}.{}", bus as u8, slave_select as u8))?;
+            .open(&path)
+            .map_err(open_error)?;
 
         // Reset all mode flags
         if let Err(e) = ioctl::set_mode32(spidev.as_raw_fd(), mode as u32) {
@@ -462,6 +659,12 @@ impl Spi {
 
         let spi = Spi {
             spidev,
+            path,
+            bus,
+            reverse_bits: Cell::new(false),
+            word_delay: Cell::new(0),
+            ss_setup_delay: Cell::new(0),
+            ss_hold_delay: Cell::new(0),
             #[cfg(any(feature = "embedded-hal-0", feature = "embedded-hal-nb"))]
             last_read: None,
             not_sync: PhantomData,
@@ -474,8 +677,61 @@ impl Spi {
         Ok(spi)
     }
 
+    /// Constructs a new `Spi` for the same bus and Slave Select pin.
+    ///
+    /// `Spi` can't be shared between threads. Instead of wrapping `Spi` in an
+    /// `Arc<Mutex<Spi>>`, `try_clone` can be used to give each thread its own instance.
+    /// Each instance opens the spidev device node separately. Transfers from different
+    /// instances are serialized by the kernel, so they never interleave on the bus.
+    ///
+    /// The clock speed, mode, bit order and other settings are stored by the spidev
+    /// driver for each Slave Select pin, so changing them on one instance affects
+    /// all instances, including transfers that are already queued by other threads.
+    /// Use the same settings on all instances, or configure them once before calling
+    /// `try_clone`.
+    ///
+    /// Settings that `Spi` emulates in software are stored per instance instead. These
+    /// include the [`LsbFirst`] bit order on drivers that don't support it, where the
+    /// bits are reversed in software, and the delays configured through
+    /// [`set_word_delay`], [`set_ss_setup_delay`] and [`set_ss_hold_delay`]. The new
+    /// instance starts out with the same values, but changing them afterwards only
+    /// affects the instance they're changed on.
+    ///
+    /// [`LsbFirst`]: enum.BitOrder.html
+    /// [`set_word_delay`]: #method.set_word_delay
+    /// [`set_ss_setup_delay`]: #method.set_ss_setup_delay
+    /// [`set_ss_hold_delay`]: #method.set_ss_hold_delay
+    pub fn try_clone(&self) -> Result<Spi> {
+        let spidev = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(&self.path)
+            .map_err(open_error)?;
+
+        Ok(Spi {
+            spidev,
+            path: self.path.clone(),
+            bus: self.bus,
+            reverse_bits: Cell::new(self.reverse_bits.get()),
+            word_delay: Cell::new(self.word_delay.get()),
+            ss_setup_delay: Cell::new(self.ss_setup_delay.get()),
+            ss_hold_delay: Cell::new(self.ss_hold_delay.get()),
+            #[cfg(any(feature = "embedded-hal-0", feature = "embedded-hal-nb"))]
+            last_read: None,
+            not_sync: PhantomData,
+        })
+    }
+
     /// Gets the bit order.
+    ///
+    /// Returns [`LsbFirst`] if the bit order is reversed in software.
+    ///
+    /// [`LsbFirst`]: enum.BitOrder.html
     pub fn bit_order(&self) -> Result<BitOrder> {
+        if self.reverse_bits.get() {
+            return Ok(BitOrder::LsbFirst);
+        }
+
         let mut bit_order: u8 = 0;
         ioctl::lsb_first(self.spidev.as_raw_fd(), &mut bit_order)?;
 
@@ -487,20 +743,39 @@ impl Spi {
 
     /// Sets the order in which bits are shifted out and in.
     ///
-    /// The Raspberry Pi currently only supports the [`MsbFirst`] bit order. If you
-    /// need the [`LsbFirst`] bit order, you can use the [`reverse_bits`] function
-    /// instead to reverse the bit order in software by converting your write
-    /// buffer before sending it to the slave device, and your read buffer after
-    /// reading any incoming data.
+    /// `set_bit_order` first tries to set the `SPI_LSB_FIRST` mode flag. The SPI
+    /// drivers on the Raspberry Pi currently only support the [`MsbFirst`] bit order
+    /// in hardware. If the driver rejects [`LsbFirst`], the bit order is reversed in
+    /// software instead. Outgoing data is copied and reversed before it's sent to the
+    /// slave device, and incoming data is reversed after it's been received, which
+    /// applies to [`read`], [`write`], [`transfer`] and [`transfer_segments`].
+    ///
+    /// Software bit order reversal doesn't set the `lsb_first` field returned by
+    /// [`flags`]. It only applies to 8 bits per word.
     ///
     /// By default, `bit_order` is set to `MsbFirst`.
     ///
     /// [`MsbFirst`]: enum.BitOrder.html
     /// [`LsbFirst`]: enum.BitOrder.html
-    /// [`reverse_bits`]: fn.reverse_bits.html
+    /// [`read`]: #method.read
+    /// [`write`]: #method.write
+    /// [`transfer`]: #method.transfer
+    /// [`transfer_segments`]: #method.transfer_segments
+    /// [`flags`]: #method.flags
     pub fn set_bit_order(&self, bit_order: BitOrder) -> Result<()> {
         match ioctl::set_lsb_first(self.spidev.as_raw_fd(), bit_order as u8) {
-            Ok(_) => Ok(()),
+            Ok(_) => {
+                self.reverse_bits.set(false);
+
+                Ok(())
+            }
+            Err(ref e)
+                if e.kind() == io::ErrorKind::InvalidInput && bit_order == BitOrder::LsbFirst =>
+            {
+                self.reverse_bits.set(true);
+
+                Ok(())
+            }
             Err(ref e) if e.kind() == io::ErrorKind::InvalidInput => {
                 Err(Error::BitOrderNotSupported(bit_order))
             }
@@ -531,7 +806,14 @@ impl Spi {
         }
     }
 
-    /// Gets the clock frequency in hertz (Hz).
+    /// Gets the maximum clock frequency in hertz (Hz).
+    ///
+    /// `clock_speed` returns the value set with [`set_clock_speed`]. The frequency
+    /// that's actually used may be lower. Use [`effective_clock_speed`] to calculate
+    /// the actual frequency.
+    ///
+    /// [`set_clock_speed`]: #method.set_clock_speed
+    /// [`effective_clock_speed`]: #method.effective_clock_speed
     pub fn clock_speed(&self) -> Result<u32> {
         let mut clock_speed: u32 = 0;
         ioctl::clock_speed(self.spidev.as_raw_fd(), &mut clock_speed)?;
@@ -539,9 +821,30 @@ impl Spi {
         Ok(clock_speed)
     }
 
-    /// Sets the clock frequency in hertz (Hz).
+    /// Calculates the clock frequency in hertz (Hz) that's actually used.
+    ///
+    /// `effective_clock_speed` applies the SPI controller's divider rules to the
+    /// value returned by [`clock_speed`], based on the default source clock frequency
+    /// for the Raspberry Pi's SoC. More information can be found [here].
+    ///
+    /// The result doesn't account for changes to the core clock due to CPU frequency
+    /// scaling, or a `core_freq` setting in `/boot/firmware/config.txt`.
+    ///
+    /// [`clock_speed`]: #method.clock_speed
+    /// [here]: index.html#clock-speed
+    pub fn effective_clock_speed(&self) -> Result<u32> {
+        let clock_speed = self.clock_speed()?;
+        let soc = DeviceInfo::new().map_err(|_| Error::UnknownModel)?.soc();
+
+        Ok(effective_clock_speed(soc, self.bus, clock_speed))
+    }
+
+    /// Sets the maximum clock frequency in hertz (Hz).
     ///
     /// The SPI driver will automatically round down to the closest valid frequency.
+    /// More information can be found [here].
+    ///
+    /// [here]: index.html#clock-speed
     pub fn set_clock_speed(&self, clock_speed: u32) -> Result<()> {
         match ioctl::set_clock_speed(self.spidev.as_raw_fd(), clock_speed) {
             Ok(_) => Ok(()),
@@ -552,7 +855,73 @@ impl Spi {
         }
     }
 
+    /// Gets the delay in microseconds (µs) between words.
+    pub fn word_delay(&self) -> u8 {
+        self.word_delay.get()
+    }
+
+    /// Sets a delay in microseconds (µs) between words for all transfers.
+    ///
+    /// The delay is applied to every [`Segment`] that doesn't specify its own
+    /// inter-word delay through [`Segment::set_word_delay`], and to all reads and writes.
+    ///
+    /// Inter-word delays require Linux kernel 5.0 or newer. Whether the delay is
+    /// applied depends on the SPI controller driver.
+    ///
+    /// By default, `word_delay` is set to `0`.
+    ///
+    /// [`Segment`]: struct.Segment.html
+    /// [`Segment::set_word_delay`]: struct.Segment.html#method.set_word_delay
+    pub fn set_word_delay(&self, word_delay: u8) {
+        self.word_delay.set(word_delay);
+    }
+
+    /// Gets the delay in microseconds (µs) between Slave Select becoming active
+    /// and the start of a transfer.
+    pub fn ss_setup_delay(&self) -> u16 {
+        self.ss_setup_delay.get()
+    }
+
+    /// Sets a delay in microseconds (µs) between Slave Select becoming active and
+    /// the first clock pulse of every transfer.
+    ///
+    /// `spidev` doesn't provide a way to configure the Slave Select setup time directly.
+    /// Instead, the delay is added by starting each transfer with an empty segment.
+    /// Some SPI controller drivers add a short delay of their own, so this is a
+    /// minimum, rather than an exact value.
+    ///
+    /// By default, `ss_setup_delay` is set to `0`.
+    pub fn set_ss_setup_delay(&self, delay: u16) {
+        self.ss_setup_delay.set(delay);
+    }
+
+    /// Gets the delay in microseconds (µs) between the end of a transfer and
+    /// Slave Select becoming inactive.
+    pub fn ss_hold_delay(&self) -> u16 {
+        self.ss_hold_delay.get()
+    }
+
+    /// Sets a delay in microseconds (µs) between the last clock pulse of every
+    /// transfer and Slave Select becoming inactive.
+    ///
+    /// The delay is applied to the last [`Segment`] of each transfer, unless that
+    /// segment specifies its own delay through [`Segment::set_delay`]. Slave Select
+    /// changes in between segments configured with [`Segment::set_ss_change`] aren't
+    /// affected.
+    ///
+    /// By default, `ss_hold_delay` is set to `0`.
+    ///
+    /// [`Segment`]: struct.Segment.html
+    /// [`Segment::set_delay`]: struct.Segment.html#method.set_delay
+    /// [`Segment::set_ss_change`]: struct.Segment.html#method.set_ss_change
+    pub fn set_ss_hold_delay(&self, delay: u16) {
+        self.ss_hold_delay.set(delay);
+    }
+
     /// Gets the SPI mode.
+    ///
+    /// `mode` reads the clock polarity and phase back from the `spidev` device, so the
+    /// result reflects any changes made by other processes using the same device.
     pub fn mode(&self) -> Result<Mode> {
         let mut mode: u8 = 0;
         ioctl::mode(self.spidev.as_raw_fd(), &mut mode)?;
@@ -565,6 +934,27 @@ impl Spi {
         })
     }
 
+    /// Gets the mode flags.
+    ///
+    /// `flags` reads the `spidev` mode flags through `SPI_IOC_RD_MODE32`, which can be
+    /// used to verify the configuration, since the flags are shared by all processes
+    /// using the same device.
+    pub fn flags(&self) -> Result<Flags> {
+        let mut mode: u32 = 0;
+        ioctl::mode32(self.spidev.as_raw_fd(), &mut mode)?;
+
+        let flag = |value: u8| mode & u32::from(value) != 0;
+
+        Ok(Flags {
+            cs_high: flag(ioctl::MODE_CS_HIGH),
+            lsb_first: flag(ioctl::MODE_LSB_FIRST),
+            three_wire: flag(ioctl::MODE_3WIRE),
+            loopback: flag(ioctl::MODE_LOOP),
+            no_cs: flag(ioctl::MODE_NO_CS),
+            ready: flag(ioctl::MODE_READY),
+        })
+    }
+
     /// Sets the SPI mode.
     ///
     /// The SPI mode indicates the serial clock polarity and phase. Some modes
@@ -619,6 +1009,41 @@ impl Spi {
         }
     }
 
+    /// Returns `true` if three-wire (bidirectional) mode is enabled.
+    pub fn is_3wire(&self) -> Result<bool> {
+        let mut mode: u8 = 0;
+        ioctl::mode(self.spidev.as_raw_fd(), &mut mode)?;
+
+        Ok((mode & ioctl::MODE_3WIRE) != 0)
+    }
+
+    /// Enables or disables three-wire (bidirectional) mode.
+    ///
+    /// In three-wire mode, MOSI is used as a single bidirectional data line, and only
+    /// half-duplex reads and writes are supported. More information can be found [here].
+    ///
+    /// By default, three-wire mode is disabled.
+    ///
+    /// [here]: index.html#three-wire-mode
+    pub fn set_3wire(&self, enabled: bool) -> Result<()> {
+        let mut new_mode: u8 = 0;
+        ioctl::mode(self.spidev.as_raw_fd(), &mut new_mode)?;
+
+        if enabled {
+            new_mode |= ioctl::MODE_3WIRE;
+        } else {
+            new_mode &= !ioctl::MODE_3WIRE;
+        }
+
+        match ioctl::set_mode(self.spidev.as_raw_fd(), new_mode) {
+            Ok(_) => Ok(()),
+            Err(ref e) if e.kind() == io::ErrorKind::InvalidInput => {
+                Err(Error::ThreeWireNotSupported)
+            }
+            Err(e) => Err(Error::Io(e)),
+        }
+    }
+
     /// Receives incoming data from the slave device and writes it to `buffer`.
     ///
     /// The SPI protocol doesn't indicate how much incoming data is waiting,
@@ -633,7 +1058,20 @@ impl Spi {
     ///
     /// Returns how many bytes were read.
     pub fn read(&mut self, buffer: &mut [u8]) -> Result<usize> {
-        Ok(self.spidev.read(buffer)?)
+        if self.has_delays() {
+            let segment = Segment::with_read(buffer);
+            self.transfer_segments(&[segment])?;
+
+            return Ok(segment.len());
+        }
+
+        let bytes_read = self.spidev.read(buffer)?;
+
+        if self.reverse_bits.get() {
+            reverse_bits(&mut buffer[..bytes_read]);
+        }
+
+        Ok(bytes_read)
     }
 
     /// Sends the outgoing data contained in `buffer` to the slave device.
@@ -645,9 +1083,92 @@ impl Spi {
     ///
     /// Returns how many bytes were written.
     pub fn write(&mut self, buffer: &[u8]) -> Result<usize> {
+        if self.has_delays() {
+            let segment = Segment::with_write(buffer);
+            self.transfer_segments(&[segment])?;
+
+            return Ok(segment.len());
+        }
+
+        if self.reverse_bits.get() {
+            let mut buffer = buffer.to_vec();
+            reverse_bits(&mut buffer);
+
+            return Ok(self.spidev.write(&buffer)?);
+        }
+
         Ok(self.spidev.write(buffer)?)
     }
 
+    /// Sends up to `len` bytes read from `reader` to the slave device.
+    ///
+    /// `write_from` reads the outgoing data in chunks that fit in the `spidev`
+    /// buffer, and sends each chunk as a separate transfer, which avoids allocating
+    /// a buffer for the entire transfer. Any data received on the MISO line from
+    /// the slave is ignored.
+    ///
+    /// Slave Select is set to active at the start of the write, and is kept active
+    /// in between chunks by setting the `cs_change` flag on every chunk except the
+    /// last one. Slave Select is set to inactive when the write completes, or when
+    /// `reader` reaches the end of its data before `len` bytes have been sent. If
+    /// reading from `reader` or sending a chunk fails, `write_from` sends an empty
+    /// transfer to set Slave Select to inactive before returning the error.
+    ///
+    /// Returns how many bytes were written.
+    ///
+    /// ## Note
+    ///
+    /// The chunk size is based on `/sys/module/spidev/parameters/bufsiz`. More
+    /// information on the buffer size can be found [here].
+    ///
+    /// Keeping Slave Select active in between transfers is a hint for the SPI
+    /// controller driver, which the Raspberry Pi's driver supports. Any transfers
+    /// started through different `Spi` instances for the same Slave Select pin could
+    /// be sent in between chunks.
+    ///
+    /// [here]: index.html#buffer-size-limits
+    pub fn write_from<R: Read>(&self, reader: &mut R, len: usize) -> Result<usize> {
+        let chunk_size = bufsiz().min(len);
+        let mut current = vec![0u8; chunk_size];
+        let mut next = vec![0u8; chunk_size];
+
+        let mut bytes_written = 0;
+        let mut current_len = fill_buffer(reader, &mut current)?;
+        // Set when the previous chunk kept Slave Select active
+        let mut ss_active = false;
+
+        while current_len > 0 {
+            bytes_written += current_len;
+
+            // Read ahead, so we know whether Slave Select should stay active
+            let next_size = chunk_size.min(len - bytes_written);
+            let result = fill_buffer(reader, &mut next[..next_size])
+                .map_err(Error::Io)
+                .and_then(|next_len| {
+                    let mut segment = Segment::with_write(&current[..current_len]);
+                    segment.set_ss_change(next_len > 0);
+                    self.transfer_segments(&[segment]).map(|_| next_len)
+                });
+
+            let next_len = match result {
+                Ok(next_len) => next_len,
+                Err(e) => {
+                    if ss_active {
+                        let _ = self.transfer_segments(&[Segment::with_write(&[])]);
+                    }
+
+                    return Err(e);
+                }
+            };
+
+            ss_active = next_len > 0;
+            mem::swap(&mut current, &mut next);
+            current_len = next_len;
+        }
+
+        Ok(bytes_written)
+    }
+
     /// Sends and receives data at the same time.
     ///
     /// SPI is a full-duplex protocol that shifts out bits to the slave device
@@ -665,7 +1186,7 @@ impl Spi {
     pub fn transfer(&self, read_buffer: &mut [u8], write_buffer: &[u8]) -> Result<usize> {
         let segment = Segment::new(read_buffer, write_buffer);
 
-        ioctl::transfer(self.spidev.as_raw_fd(), &[segment])?;
+        self.transfer_segments(&[segment])?;
 
         Ok(segment.len())
     }
@@ -683,10 +1204,117 @@ impl Spi {
     /// [`Segment`]: struct.Segment.html
     /// [`Segment::set_ss_change`]: struct.Segment.html#method.set_ss_change
     pub fn transfer_segments(&self, segments: &[Segment<'_, '_>]) -> Result<()> {
-        ioctl::transfer(self.spidev.as_raw_fd(), segments)?;
+        if !self.has_delays() || segments.is_empty() {
+            return self.transfer_raw(segments);
+        }
+
+        let mut adjusted = Vec::with_capacity(segments.len() + 1);
+
+        if self.ss_setup_delay.get() > 0 {
+            adjusted.push(Segment::with_settings(
+                None,
+                None,
+                0,
+                self.ss_setup_delay.get(),
+                0,
+                false,
+            ));
+        }
+
+        for segment in segments {
+            let mut segment = *segment;
+            if segment.word_delay() == 0 {
+                segment.set_word_delay(self.word_delay.get());
+            }
+
+            adjusted.push(segment);
+        }
+
+        if let Some(last) = adjusted.last_mut() {
+            if last.delay() == 0 {
+                last.set_delay(self.ss_hold_delay.get());
+            }
+        }
+
+        self.transfer_raw(&adjusted)
+    }
+
+    fn has_delays(&self) -> bool {
+        self.word_delay.get() > 0 || self.ss_setup_delay.get() > 0 || self.ss_hold_delay.get() > 0
+    }
+
+    fn transfer_raw(&self, segments: &[Segment<'_, '_>]) -> Result<()> {
+        if !self.reverse_bits.get() {
+            ioctl::transfer(self.spidev.as_raw_fd(), segments)?;
+
+            return Ok(());
+        }
+
+        let write_buffers: Vec<Option<Vec<u8>>> = segments
+            .iter()
+            .map(|segment| {
+                segment.write_buffer().map(|buffer| {
+                    let mut buffer = buffer.to_vec();
+                    reverse_bits(&mut buffer);
+                    buffer
+                })
+            })
+            .collect();
+
+        let reversed_segments: Vec<Segment<'_, '_>> = segments
+            .iter()
+            .zip(write_buffers.iter())
+            .map(|(segment, write_buffer)| match write_buffer {
+                Some(write_buffer) => segment.with_write_buffer(write_buffer),
+                None => *segment,
+            })
+            .collect();
+
+        ioctl::transfer(self.spidev.as_raw_fd(), &reversed_segments)?;
+
+        Segment::reverse_read_bits(segments);
 
         Ok(())
     }
+
+    /// Performs a loopback self-test.
+    ///
+    /// `loopback_test` sends a known test pattern during a full-duplex transfer, and
+    /// compares the incoming data to the outgoing data. The test pattern contains
+    /// alternating bit patterns, followed by every possible byte value.
+    ///
+    /// Before calling `loopback_test`, disconnect any slave devices, and physically connect
+    /// the MOSI pin to the MISO pin with a jumper wire.
+    ///
+    /// Returns the indices of any bytes that weren't received correctly. An empty `Vec`
+    /// means the test passed. If every byte is reported as mismatched, check the wiring.
+    pub fn loopback_test(&self) -> Result<Vec<usize>> {
+        let mut write_buffer = vec![0x00, 0xff, 0x55, 0xaa, 0x0f, 0xf0];
+        write_buffer.extend(0..=u8::MAX);
+
+        let mut read_buffer = vec![0u8; write_buffer.len()];
+        self.transfer(&mut read_buffer, &write_buffer)?;
+
+        Ok(write_buffer
+            .iter()
+            .zip(read_buffer.iter())
+            .enumerate()
+            .filter(|(_, (w, r))| w != r)
+            .map(|(index, _)| index)
+            .collect())
+    }
+}
+
+impl AsRawFd for Spi {
+    /// Returns the raw file descriptor for the underlying `spidev` character device.
+    ///
+    /// The file descriptor remains owned by `Spi`. It shouldn't be closed, and
+    /// shouldn't be used after `Spi` goes out of scope. Changing any of the
+    /// device's settings through the file descriptor may cause `Spi` to report
+    /// incorrect values.
+    fn as_raw_fd(&self) -> RawFd {
+        self.spidev.as_raw_fd()
+    }
 }
 
 // Send is safe for Spi, but we're marked !Send because of the dummy pointer that's
@@ -695,6 +1323,79 @@ unsafe impl Send for Spi {}
 
 impl fmt::Debug for Spi {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.debug_struct("Spi").field("spidev", &self.spidev).finish()
+        f.debug_struct("Spi")
+            .field("spidev", &self.spidev)
+            .field("path", &self.path)
+            .field("bus", &self.bus)
+            .field("reverse_bits", &self.reverse_bits.get())
+            .field("word_delay", &self.word_delay.get())
+            .field("ss_setup_delay", &self.ss_setup_delay.get())
+            .field("ss_hold_delay", &self.ss_hold_delay.get())
+            .finish()
+    }
+}
+
+// Calculates the clock speed generated by the SPI controller when clock_speed is
+// requested, based on the divider calculations in the spi-bcm2835, spi-bcm2835aux
+// and spi-dw kernel drivers.
+fn effective_clock_speed(soc: SoC, bus: Bus, clock_speed: u32) -> u32 {
+    let source_clock: u64 = match soc {
+        SoC::Bcm2835 | SoC::Bcm2836 => 250_000_000,
+        SoC::Bcm2837A1 | SoC::Bcm2837B0 => 400_000_000,
+        SoC::Bcm2711 => 500_000_000,
+        SoC::Bcm2712 => 200_000_000,
+    };
+
+    if clock_speed == 0 {
+        return 0;
+    }
+
+    let clock_speed = u64::from(clock_speed);
+
+    if soc == SoC::Bcm2712 {
+        // Even divider between 2 and 65534
+        let divider = ((source_clock + clock_speed - 1) / clock_speed).clamp(2, 65534);
+        return (source_clock / (divider + (divider & 1))) as u32;
     }
+
+    match bus {
+        Bus::Spi1 | Bus::Spi2 => {
+            // Divider of 2 * (n + 1), with n limited to 12 bits
+            let n = ((source_clock + 2 * clock_speed - 1) / (2 * clock_speed))
+                .saturating_sub(1)
+                .min(0xfff);
+            (source_clock / (2 * (n + 1))) as u32
+        }
+        _ => {
+            // Even divider between 2 and 65536
+            let divider = (source_clock + clock_speed - 1) / clock_speed;
+            let divider = (divider + (divider & 1)).clamp(2, 65536);
+            (source_clock / divider) as u32
+        }
+    }
+}
+
+// Returns the maximum number of bytes spidev can handle in a single transfer.
+fn bufsiz() -> usize {
+    fs::read_to_string(PATH_BUFSIZ)
+        .ok()
+        .and_then(|bufsiz| bufsiz.trim().parse().ok())
+        .filter(|&bufsiz| bufsiz > 0)
+        .unwrap_or(DEFAULT_BUFSIZ)
+}
+
+// Reads from reader until buffer is full, or the end of the data is reached.
+fn fill_buffer<R: Read>(reader: &mut R, buffer: &mut [u8]) -> io::Result<usize> {
+    let mut len = 0;
+
+    while len < buffer.len() {
+        match reader.read(&mut buffer[len..]) {
+            Ok(0) => break,
+            Ok(bytes_read) => len += bytes_read,
+            Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+
+    Ok(len)
 }
@@ -0,0 +1,87 @@
+use std::io;
+
+use super::{Error, Result, Spi};
+
+// Number of input channels
+const CHANNELS: u8 = 8;
+// Start bit, sent in the first byte to align the response with the last two bytes
+const START_BIT: u8 = 0x01;
+// Selects single-ended mode when set, and differential mode when cleared
+const SINGLE_ENDED: u8 = 0x80;
+
+/// MCP3008 input configurations.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum InputMode {
+    /// Measures the voltage on the channel's input pin relative to ground.
+    SingleEnded,
+    /// Measures the voltage difference between two adjacent input pins.
+    ///
+    /// Channel 0 measures CH0 (IN+) relative to CH1 (IN-), and channel 1 measures
+    /// CH1 (IN+) relative to CH0 (IN-). The other channels follow the same pattern
+    /// for the pairs CH2/CH3, CH4/CH5 and CH6/CH7.
+    Differential,
+}
+
+/// MCP3008 8-channel 10-bit analog-to-digital converter.
+///
+/// The Raspberry Pi doesn't have any built-in analog inputs. The MCP3008 is a commonly
+/// used SPI ADC, which converts the voltage on one of its eight input channels to a
+/// 10-bit value between `0` and `1023`, relative to the voltage on its VREF pin.
+///
+/// `Mcp3008` doesn't change the configuration of the [`Spi`] instance. The MCP3008
+/// supports [`Mode0`] and [`Mode3`]. Its maximum clock speed depends on its supply
+/// voltage, and is 1.35 MHz at 2.7 V, and 3.6 MHz at 5 V. When the MCP3008 is powered
+/// from the Raspberry Pi's 3.3 V pin, a clock speed of 1 MHz is a safe choice.
+///
+/// [`Spi`]: struct.Spi.html
+/// [`Mode0`]: enum.Mode.html#variant.Mode0
+/// [`Mode3`]: enum.Mode.html#variant.Mode3
+#[derive(Debug)]
+pub struct Mcp3008 {
+    spi: Spi,
+}
+
+impl Mcp3008 {
+    /// Constructs a new `Mcp3008`.
+    pub fn new(spi: Spi) -> Mcp3008 {
+        Mcp3008 { spi }
+    }
+
+    /// Reads the 10-bit value for the specified channel.
+    ///
+    /// `channel` selects one of the input channels `0`-`7`. `mode` determines whether
+    /// the channel is measured relative to ground, or relative to the other input
+    /// of its channel pair.
+    ///
+    /// If `channel` is out of range, `read_channel` returns `Err(`[`Error::Io`]`)`
+    /// with an `io::ErrorKind::InvalidInput` error.
+    ///
+    /// [`Error::Io`]: enum.Error.html#variant.Io
+    pub fn read_channel(&self, channel: u8, mode: InputMode) -> Result<u16> {
+        if channel >= CHANNELS {
+            return Err(Error::Io(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "MCP3008 channel out of range",
+            )));
+        }
+
+        let config = match mode {
+            InputMode::SingleEnded => SINGLE_ENDED,
+            InputMode::Differential => 0,
+        } | (channel << 4);
+
+        let mut read_buffer = [0u8; 3];
+        self.spi
+            .transfer(&mut read_buffer, &[START_BIT, config, 0])?;
+
+        // The result starts with a null bit, followed by 10 data bits, MSB first
+        Ok((u16::from(read_buffer[1] & 0x03) << 8) | u16::from(read_buffer[2]))
+    }
+
+    /// Consumes the `Mcp3008` and returns the [`Spi`] instance.
+    ///
+    /// [`Spi`]: struct.Spi.html
+    pub fn into_spi(self) -> Spi {
+        self.spi
+    }
+}
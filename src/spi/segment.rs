@@ -1,5 +1,6 @@
 use std::fmt;
 use std::marker;
+use std::slice;
 
 /// Part of a multi-segment transfer.
 ///
@@ -35,8 +36,10 @@ pub struct Segment<'a, 'b> {
     tx_nbits: u8,
     // Number of incoming lines used for dual/quad SPI. Not supported on the Raspberry Pi. Default = 0.
     rx_nbits: u8,
+    // Delay between words within this segment. Requires Linux 5.0 or newer. Default = 0.
+    word_delay_usecs: u8,
     // Padding. Set to 0 for forward compatibility.
-    pad: u16,
+    pad: u8,
     // Zero-sized variable used to link this struct to the read buffer lifetime.
     read_buffer_lifetime: marker::PhantomData<&'a mut [u8]>,
     // Zero-sized variable used to link this struct to the write buffer lifetime.
@@ -153,6 +156,7 @@ impl<'a, 'b> Segment<'a, 'b> {
             cs_change: ss_change as u8,
             tx_nbits: 0,
             rx_nbits: 0,
+            word_delay_usecs: 0,
             pad: 0,
             read_buffer_lifetime: marker::PhantomData,
             write_buffer_lifetime: marker::PhantomData,
@@ -175,6 +179,57 @@ impl<'a, 'b> Segment<'a, 'b> {
         self.len == 0
     }
 
+    // Returns the part of the write buffer that will be transferred, if any.
+    pub(crate) fn write_buffer(&self) -> Option<&'b [u8]> {
+        if self.tx_buf == 0 {
+            None
+        } else {
+            Some(unsafe { slice::from_raw_parts(self.tx_buf as *const u8, self.len as usize) })
+        }
+    }
+
+    // Returns a copy of this segment that sends the contents of buffer instead. buffer
+    // needs to be at least as long as the original write buffer.
+    pub(crate) fn with_write_buffer<'c>(&self, buffer: &'c [u8]) -> Segment<'a, 'c> {
+        debug_assert!(buffer.len() >= self.len as usize);
+
+        Segment {
+            tx_buf: buffer.as_ptr() as u64,
+            rx_buf: self.rx_buf,
+            len: self.len,
+            speed_hz: self.speed_hz,
+            delay_usecs: self.delay_usecs,
+            bits_per_word: self.bits_per_word,
+            cs_change: self.cs_change,
+            tx_nbits: self.tx_nbits,
+            rx_nbits: self.rx_nbits,
+            word_delay_usecs: self.word_delay_usecs,
+            pad: self.pad,
+            read_buffer_lifetime: marker::PhantomData,
+            write_buffer_lifetime: marker::PhantomData,
+        }
+    }
+
+    // Reverses the bit order of every byte in the parts of the read buffers that were
+    // transferred. The read buffers are exclusively borrowed by the segments, and are
+    // written to by the driver in the same way. Since Segment is Copy, the same read
+    // buffer can show up in multiple segments, so each one is only reversed once.
+    pub(crate) fn reverse_read_bits(segments: &[Segment<'_, '_>]) {
+        let mut read_buffers: Vec<(u64, u32)> = segments
+            .iter()
+            .filter(|segment| segment.rx_buf != 0)
+            .map(|segment| (segment.rx_buf, segment.len))
+            .collect();
+
+        read_buffers.sort_unstable();
+        read_buffers.dedup();
+
+        for (rx_buf, len) in read_buffers {
+            let buffer = unsafe { slice::from_raw_parts_mut(rx_buf as *mut u8, len as usize) };
+            super::reverse_bits(buffer);
+        }
+    }
+
     /// Gets the custom clock speed in hertz (Hz) for this segment.
     pub fn clock_speed(&self) -> u32 {
         self.speed_hz
@@ -207,6 +262,24 @@ impl<'a, 'b> Segment<'a, 'b> {
         self.delay_usecs = delay;
     }
 
+    /// Gets the delay in microseconds (µs) between words for this segment.
+    pub fn word_delay(&self) -> u8 {
+        self.word_delay_usecs
+    }
+
+    /// Sets a delay in microseconds (µs) between words for this segment.
+    ///
+    /// `set_word_delay` adds a delay after each word is transferred, which gives slow
+    /// slave devices time to process the incoming data.
+    ///
+    /// Inter-word delays require Linux kernel 5.0 or newer. Whether the delay is
+    /// applied depends on the SPI controller driver.
+    ///
+    /// By default, `word_delay` is set to `0`.
+    pub fn set_word_delay(&mut self, word_delay: u8) {
+        self.word_delay_usecs = word_delay;
+    }
+
     /// Gets the number of bits per word for this segment.
     pub fn bits_per_word(&self) -> u8 {
         self.bits_per_word
@@ -255,6 +328,7 @@ impl<'a, 'b> fmt::Debug for Segment<'a, 'b> {
             .field("cs_change", &self.cs_change)
             .field("tx_nbits", &self.tx_nbits)
             .field("rx_nbits", &self.rx_nbits)
+            .field("word_delay_usecs", &self.word_delay_usecs)
             .field("pad", &self.pad)
             .finish()
     }
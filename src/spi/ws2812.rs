@@ -0,0 +1,133 @@
+use super::{Mode, Result, Spi};
+
+// Each WS2812 data bit is encoded as 3 SPI bits, which results in a 417 ns pulse
+// for a 0 bit (T0H), and an 833 ns pulse for a 1 bit (T1H).
+const CLOCK_SPEED: u32 = 2_400_000;
+// Number of SPI bytes needed to encode a single color byte
+const BYTES_PER_COLOR: usize = 3;
+// Number of SPI bytes needed to encode a single LED (GRB)
+const BYTES_PER_LED: usize = BYTES_PER_COLOR * 3;
+// Keep the data line low for at least 300 µs to latch the data. The original WS2812
+// requires 50 µs, while newer revisions (WS2812B-V5, SK6812) require 280 µs. At
+// 2.4 MHz, each byte takes 3.33 µs.
+const RESET_BYTES: usize = 90;
+
+/// RGB color value for a single LED.
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Default)]
+pub struct Rgb {
+    /// Red component.
+    pub r: u8,
+    /// Green component.
+    pub g: u8,
+    /// Blue component.
+    pub b: u8,
+}
+
+impl Rgb {
+    /// Constructs a new `Rgb`.
+    pub fn new(r: u8, g: u8, b: u8) -> Rgb {
+        Rgb { r, g, b }
+    }
+}
+
+/// WS2812 (NeoPixel) LED strip driver.
+///
+/// WS2812 LEDs use a single-wire protocol with tight timing requirements, where each
+/// bit's value is encoded in the width of a high pulse. Instead of toggling a GPIO pin,
+/// `Ws2812` encodes each data bit as 3 SPI bits, which lets the SPI peripheral generate
+/// the required pulse widths. Connect the LED strip's data input to the SPI bus's MOSI
+/// pin. SCLK and Slave Select aren't used.
+///
+/// The LED data is sent in a single transfer, followed by a reset period of at
+/// least 300 µs, during which the data line is kept low to latch the new colors.
+///
+/// ## Buffer size limits
+///
+/// Each LED requires 9 bytes, and the reset period adds 90 bytes. With the default
+/// `spidev` buffer size of 4096 bytes, a single [`write`] can update up to 445 LEDs.
+/// More information on increasing the buffer size can be found [here].
+///
+/// ## Clock speed
+///
+/// `Ws2812` sets the SPI clock speed to 2.4 MHz. On the BCM283x, the SPI clock is derived
+/// from the core clock, which may change when the CPU frequency scales. If your LEDs
+/// flicker or show the wrong colors, add `core_freq_min=250` (or 500 on the BCM2711) to
+/// `/boot/firmware/config.txt` to keep the core clock fixed.
+///
+/// Most WS2812 LEDs expect a 5 V data signal. Depending on the LEDs and the wiring, you
+/// may need a level shifter to reliably drive the data line from the 3.3 V MOSI pin.
+///
+/// [`write`]: #method.write
+/// [here]: index.html#buffer-size-limits
+#[derive(Debug)]
+pub struct Ws2812 {
+    spi: Spi,
+    buffer: Vec<u8>,
+}
+
+impl Ws2812 {
+    /// Constructs a new `Ws2812`.
+    ///
+    /// `new` changes the clock speed of `spi` to 2.4 MHz, and its mode to [`Mode0`].
+    ///
+    /// [`Mode0`]: enum.Mode.html#variant.Mode0
+    pub fn new(spi: Spi) -> Result<Ws2812> {
+        spi.set_clock_speed(CLOCK_SPEED)?;
+        spi.set_mode(Mode::Mode0)?;
+
+        Ok(Ws2812 {
+            spi,
+            buffer: Vec::new(),
+        })
+    }
+
+    /// Sends the colors to the LED strip.
+    ///
+    /// The first color is sent to the LED closest to the Raspberry Pi. `write` blocks
+    /// until all data and the reset period have been sent.
+    pub fn write(&mut self, leds: &[Rgb]) -> Result<()> {
+        self.buffer.clear();
+        self.buffer
+            .reserve(leds.len() * BYTES_PER_LED + RESET_BYTES);
+
+        // WS2812 LEDs expect their colors in GRB order
+        for led in leds {
+            for &color in &[led.g, led.r, led.b] {
+                encode(color, &mut self.buffer);
+            }
+        }
+
+        self.buffer.resize(self.buffer.len() + RESET_BYTES, 0);
+
+        self.spi.write(&self.buffer)?;
+
+        Ok(())
+    }
+
+    /// Turns off all LEDs.
+    ///
+    /// `clear` is a convenience method that sends black (`0, 0, 0`) to `count` LEDs.
+    pub fn clear(&mut self, count: usize) -> Result<()> {
+        self.write(&vec![Rgb::default(); count])
+    }
+
+    /// Consumes the `Ws2812` and returns the [`Spi`] instance.
+    ///
+    /// [`Spi`]: struct.Spi.html
+    pub fn into_spi(self) -> Spi {
+        self.spi
+    }
+}
+
+// Encodes a single color byte as 24 SPI bits, MSB first. A 0 bit is encoded as 0b100,
+// and a 1 bit as 0b110.
+fn encode(color: u8, buffer: &mut Vec<u8>) {
+    let mut bits: u32 = 0;
+
+    for bit in (0..8).rev() {
+        bits <<= 3;
+        bits |= if color & (1 << bit) > 0 { 0b110 } else { 0b100 };
+    }
+
+    buffer.extend_from_slice(&bits.to_be_bytes()[1..=BYTES_PER_COLOR]);
+}
@@ -1,25 +1,72 @@
 //! Raspberry Pi system-related tools.
 //!
 //! Use [`DeviceInfo`] to identify the Raspberry Pi's model and SoC.
+//! [`DeviceInfo::summary`] combines the available hardware details into a single
+//! [`DeviceSummary`], which can be logged or attached to bug reports.
 //!
 //! [`DeviceInfo`]: struct.DeviceInfo.html
+//! [`DeviceInfo::summary`]: struct.DeviceInfo.html#method.summary
+//! [`DeviceSummary`]: struct.DeviceSummary.html
 
 use std::error;
 use std::fmt;
 use std::fs;
 use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::{self, BufRead, BufReader};
+use std::mem;
 use std::result;
 
 // Peripheral base address
-const PERIPHERAL_BASE_RPI: u32 = 0x2000_0000;
-const PERIPHERAL_BASE_RPI2: u32 = 0x3f00_0000;
-const PERIPHERAL_BASE_RPI4: u32 = 0xfe00_0000;
-const PERIPHERAL_BASE_RP1: u32 = 0x4000_0000;
+const PERIPHERAL_BASE_RPI: u64 = 0x2000_0000;
+const PERIPHERAL_BASE_RPI2: u64 = 0x3f00_0000;
+const PERIPHERAL_BASE_RPI4: u64 = 0xfe00_0000;
+const PERIPHERAL_BASE_RP1: u64 = 0x4000_0000;
 
 // Offset from the peripheral base address
 const GPIO_OFFSET: u32 = 0x20_0000;
 const GPIO_OFFSET_RP1: u32 = 0x0d_0000;
+const CM_OFFSET: u64 = 0x10_1000;
+// PADS0 (GPIO 0-27), followed by PADS1 (GPIO 28-45) and PADS2 (GPIO 46-53)
+const PADS_OFFSET: u64 = 0x10_002c;
+const PWM0_OFFSET: u64 = 0x20_c000;
+// SPI0 is a dedicated controller. SPI1 and SPI2 are part of the auxiliary peripherals.
+const SPI_OFFSETS_BCM283X: [Option<u64>; 7] = [
+    Some(0x20_4000),
+    Some(0x21_5080),
+    Some(0x21_50c0),
+    None,
+    None,
+    None,
+    None,
+];
+const SPI_OFFSETS_BCM2711: [Option<u64>; 7] = [
+    Some(0x20_4000),
+    Some(0x21_5080),
+    Some(0x21_50c0),
+    Some(0x20_4600),
+    Some(0x20_4800),
+    Some(0x20_4a00),
+    Some(0x20_4c00),
+];
+// BSC2 is dedicated to the HDMI interface, and isn't exposed as an I2C bus
+const BSC_OFFSETS_BCM283X: [Option<u64>; 7] = [
+    Some(0x20_5000),
+    Some(0x80_4000),
+    None,
+    None,
+    None,
+    None,
+    None,
+];
+const BSC_OFFSETS_BCM2711: [Option<u64>; 7] = [
+    Some(0x20_5000),
+    Some(0x80_4000),
+    None,
+    Some(0x20_5600),
+    Some(0x20_5800),
+    Some(0x20_5a00),
+    Some(0x20_5c00),
+];
 
 // Number of GPIO lines
 const GPIO_LINES_BCM283X: u8 = 54;
@@ -28,6 +75,18 @@ const GPIO_LINES_BCM2711: u8 = 58;
 // specified as internal-use only, so we'll ignore those.
 const GPIO_LINES_RP1: u8 = 28;
 
+// Label of the gpiochip character device that controls the GPIO pins on the 40-pin header
+const GPIOCHIP_LABEL_BCM283X: &str = "pinctrl-bcm2835";
+const GPIOCHIP_LABEL_BCM2711: &str = "pinctrl-bcm2711";
+const GPIOCHIP_LABEL_RP1: &str = "pinctrl-rp1";
+
+// PWM clock rate (Hz) as configured by the device tree
+const PWM_CLOCK_RATE_BCM283X: u32 = 10_000_000;
+const PWM_CLOCK_RATE_RP1: u32 = 50_000_000;
+
+// Logic level voltage for all GPIO pins
+pub(crate) const GPIO_VOLTAGE: f32 = 3.3;
+
 /// Errors that can occur when trying to identify the Raspberry Pi hardware.
 #[derive(Debug)]
 pub enum Error {
@@ -154,6 +213,136 @@ impl fmt::Display for SoC {
     }
 }
 
+/// Functions of the physical pins on the GPIO header.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum HeaderPin {
+    /// GPIO pin with the specified BCM GPIO number.
+    ///
+    /// All GPIO pins use 3.3 V logic levels, and aren't 5 V tolerant.
+    Gpio(u8),
+    /// 3.3 V power supply.
+    Power3v3,
+    /// 5 V power supply.
+    Power5v,
+    /// Ground.
+    Ground,
+}
+
+impl fmt::Display for HeaderPin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            HeaderPin::Gpio(pin) => write!(f, "GPIO {}", pin),
+            HeaderPin::Power3v3 => write!(f, "3.3 V"),
+            HeaderPin::Power5v => write!(f, "5 V"),
+            HeaderPin::Ground => write!(f, "Ground"),
+        }
+    }
+}
+
+// 26-pin header on the original Raspberry Pi B Rev 1
+const HEADER_26_REV1: [HeaderPin; 26] = [
+    HeaderPin::Power3v3,
+    HeaderPin::Power5v,
+    HeaderPin::Gpio(0),
+    HeaderPin::Power5v,
+    HeaderPin::Gpio(1),
+    HeaderPin::Ground,
+    HeaderPin::Gpio(4),
+    HeaderPin::Gpio(14),
+    HeaderPin::Ground,
+    HeaderPin::Gpio(15),
+    HeaderPin::Gpio(17),
+    HeaderPin::Gpio(18),
+    HeaderPin::Gpio(21),
+    HeaderPin::Ground,
+    HeaderPin::Gpio(22),
+    HeaderPin::Gpio(23),
+    HeaderPin::Power3v3,
+    HeaderPin::Gpio(24),
+    HeaderPin::Gpio(10),
+    HeaderPin::Ground,
+    HeaderPin::Gpio(9),
+    HeaderPin::Gpio(25),
+    HeaderPin::Gpio(11),
+    HeaderPin::Gpio(8),
+    HeaderPin::Ground,
+    HeaderPin::Gpio(7),
+];
+
+// 26-pin header on the Raspberry Pi A and B Rev 2
+const HEADER_26_REV2: [HeaderPin; 26] = [
+    HeaderPin::Power3v3,
+    HeaderPin::Power5v,
+    HeaderPin::Gpio(2),
+    HeaderPin::Power5v,
+    HeaderPin::Gpio(3),
+    HeaderPin::Ground,
+    HeaderPin::Gpio(4),
+    HeaderPin::Gpio(14),
+    HeaderPin::Ground,
+    HeaderPin::Gpio(15),
+    HeaderPin::Gpio(17),
+    HeaderPin::Gpio(18),
+    HeaderPin::Gpio(27),
+    HeaderPin::Ground,
+    HeaderPin::Gpio(22),
+    HeaderPin::Gpio(23),
+    HeaderPin::Power3v3,
+    HeaderPin::Gpio(24),
+    HeaderPin::Gpio(10),
+    HeaderPin::Ground,
+    HeaderPin::Gpio(9),
+    HeaderPin::Gpio(25),
+    HeaderPin::Gpio(11),
+    HeaderPin::Gpio(8),
+    HeaderPin::Ground,
+    HeaderPin::Gpio(7),
+];
+
+// 40-pin header used by all models since the Raspberry Pi A+ and B+
+const HEADER_40: [HeaderPin; 40] = [
+    HeaderPin::Power3v3,
+    HeaderPin::Power5v,
+    HeaderPin::Gpio(2),
+    HeaderPin::Power5v,
+    HeaderPin::Gpio(3),
+    HeaderPin::Ground,
+    HeaderPin::Gpio(4),
+    HeaderPin::Gpio(14),
+    HeaderPin::Ground,
+    HeaderPin::Gpio(15),
+    HeaderPin::Gpio(17),
+    HeaderPin::Gpio(18),
+    HeaderPin::Gpio(27),
+    HeaderPin::Ground,
+    HeaderPin::Gpio(22),
+    HeaderPin::Gpio(23),
+    HeaderPin::Power3v3,
+    HeaderPin::Gpio(24),
+    HeaderPin::Gpio(10),
+    HeaderPin::Ground,
+    HeaderPin::Gpio(9),
+    HeaderPin::Gpio(25),
+    HeaderPin::Gpio(11),
+    HeaderPin::Gpio(8),
+    HeaderPin::Ground,
+    HeaderPin::Gpio(7),
+    HeaderPin::Gpio(0),
+    HeaderPin::Gpio(1),
+    HeaderPin::Gpio(5),
+    HeaderPin::Ground,
+    HeaderPin::Gpio(6),
+    HeaderPin::Gpio(12),
+    HeaderPin::Gpio(13),
+    HeaderPin::Ground,
+    HeaderPin::Gpio(19),
+    HeaderPin::Gpio(16),
+    HeaderPin::Gpio(26),
+    HeaderPin::Gpio(20),
+    HeaderPin::Ground,
+    HeaderPin::Gpio(21),
+];
+
 // Identify Pi model based on /proc/cpuinfo
 fn parse_proc_cpuinfo() -> Result<Model> {
     let proc_cpuinfo = BufReader::new(match File::open("/proc/cpuinfo") {
@@ -217,6 +406,100 @@ fn parse_proc_cpuinfo() -> Result<Model> {
     Ok(model)
 }
 
+// Retrieve the value of the specified field from /proc/cpuinfo
+fn parse_proc_cpuinfo_field(name: &str) -> Option<String> {
+    let proc_cpuinfo = BufReader::new(File::open("/proc/cpuinfo").ok()?);
+
+    for line in proc_cpuinfo.lines().map_while(result::Result::ok) {
+        if let Some((key, value)) = line.split_once(':') {
+            if key.trim() == name {
+                return Some(String::from(value.trim()));
+            }
+        }
+    }
+
+    None
+}
+
+// Retrieve a big-endian integer value from /sys/firmware/devicetree/base/system
+fn parse_base_system(name: &str) -> Option<u64> {
+    let buffer = fs::read(format!("/sys/firmware/devicetree/base/system/{}", name)).ok()?;
+    if buffer.is_empty() || buffer.len() > 8 {
+        return None;
+    }
+
+    Some(
+        buffer
+            .iter()
+            .fold(0u64, |value, &byte| (value << 8) | u64::from(byte)),
+    )
+}
+
+// Retrieve the peripheral base address from the SoC's device tree ranges property,
+// which maps the peripherals' bus addresses to physical addresses. This is the same
+// approach used by bcm_host_get_peripheral_address() in the Raspberry Pi userland.
+fn parse_soc_ranges() -> Option<u64> {
+    let buffer = fs::read("/sys/firmware/devicetree/base/soc/ranges").ok()?;
+
+    // The parent address is stored as a single cell on the BCM283x, and as two cells
+    // on the BCM2711, where the upper cell is non-zero in high peripheral mode.
+    let address_cells = fs::read("/sys/firmware/devicetree/base/#address-cells")
+        .ok()
+        .filter(|cells| cells.len() == 4)
+        .map_or(1, |cells| {
+            u32::from_be_bytes([cells[0], cells[1], cells[2], cells[3]])
+        });
+
+    let cell = |idx: usize| {
+        buffer
+            .get(idx * 4..(idx + 1) * 4)
+            .map(|cell| u64::from(u32::from_be_bytes([cell[0], cell[1], cell[2], cell[3]])))
+    };
+
+    // The first cell contains the child bus address
+    let address = match address_cells {
+        1 => cell(1)?,
+        2 => (cell(1)? << 32) | cell(2)?,
+        _ => return None,
+    };
+
+    if address == 0 {
+        None
+    } else {
+        Some(address)
+    }
+}
+
+// Convert a physical address to an mmap() offset for /dev/mem. When off_t is 32 bits
+// wide, addresses between 2 GiB and 4 GiB wrap around to negative values, which
+// mmap() still interprets correctly. Addresses above 4 GiB, used by the BCM2711 in
+// high peripheral mode, can't be represented.
+pub(crate) fn devmem_offset(address: u64) -> io::Result<libc::off_t> {
+    if mem::size_of::<libc::off_t>() < mem::size_of::<u64>() && address > u64::from(u32::MAX) {
+        return Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            format!("memory address {:#x} out of range for /dev/mem", address),
+        ));
+    }
+
+    Ok(address as libc::off_t)
+}
+
+// Retrieve the board's hardware revision code
+fn parse_revision_code() -> Option<u32> {
+    parse_proc_cpuinfo_field("Revision")
+        .and_then(|revision| u32::from_str_radix(&revision, 16).ok())
+        .or_else(|| parse_base_system("linux,revision").map(|revision| revision as u32))
+}
+
+// Retrieve the board's serial number. Some models and containers report all zeros.
+fn parse_serial() -> Option<u64> {
+    parse_proc_cpuinfo_field("Serial")
+        .and_then(|serial| u64::from_str_radix(&serial, 16).ok())
+        .or_else(|| parse_base_system("linux,serial"))
+        .filter(|&serial| serial != 0)
+}
+
 // Identify Pi model based on /sys/firmware/devicetree/base/compatible
 fn parse_base_compatible() -> Result<Model> {
     let base_compatible = match fs::read_to_string("/sys/firmware/devicetree/base/compatible") {
@@ -324,17 +607,25 @@ pub struct DeviceInfo {
     model: Model,
     soc: SoC,
     // Peripheral base memory address
-    peripheral_base: u32,
+    peripheral_base: u64,
     // Offset from the peripheral base memory address for the GPIO section
     gpio_offset: u32,
     // Number of GPIO lines available for this SoC
     gpio_lines: u8,
     // GPIO interface through the Broadcom SoC or a separate RP1
     gpio_interface: GpioInterface,
+    // Label of the gpiochip that controls the GPIO lines
+    gpiochip_label: &'static str,
     // PWM chip # used for hardware PWM on selected GPIO pins
     pwm_chip: u8,
     // PWM channels used for hardware PWM on selected GPIO pins
     pwm_channels: [u8; 2],
+    // Default PWM clock rate (Hz)
+    pwm_clock_rate: u32,
+    // Hardware revision code
+    revision_code: Option<u32>,
+    // Serial number
+    serial: Option<u64>,
 }
 
 impl DeviceInfo {
@@ -348,6 +639,12 @@ impl DeviceInfo {
         let model = parse_proc_cpuinfo()
             .or_else(|_| parse_base_compatible().or_else(|_| parse_base_model()))?;
 
+        let revision_code = parse_revision_code();
+        let serial = parse_serial();
+        // Prefer the peripheral base from the device tree over the default for the SoC,
+        // so /dev/mem is mapped at the right address even on unexpected configurations.
+        let peripheral_base = parse_soc_ranges();
+
         // Set SoC and memory offsets based on model
         match model {
             Model::RaspberryPiA
@@ -360,33 +657,45 @@ impl DeviceInfo {
             | Model::RaspberryPiZeroW => Ok(DeviceInfo {
                 model,
                 soc: SoC::Bcm2835,
-                peripheral_base: PERIPHERAL_BASE_RPI,
+                peripheral_base: peripheral_base.unwrap_or(PERIPHERAL_BASE_RPI),
                 gpio_offset: GPIO_OFFSET,
                 gpio_lines: GPIO_LINES_BCM283X,
                 gpio_interface: GpioInterface::Bcm,
+                gpiochip_label: GPIOCHIP_LABEL_BCM283X,
                 pwm_chip: 0,
                 pwm_channels: [0, 1],
+                pwm_clock_rate: PWM_CLOCK_RATE_BCM283X,
+                revision_code,
+                serial,
             }),
             Model::RaspberryPi2B => Ok(DeviceInfo {
                 model,
                 soc: SoC::Bcm2836,
-                peripheral_base: PERIPHERAL_BASE_RPI2,
+                peripheral_base: peripheral_base.unwrap_or(PERIPHERAL_BASE_RPI2),
                 gpio_offset: GPIO_OFFSET,
                 gpio_lines: GPIO_LINES_BCM283X,
                 gpio_interface: GpioInterface::Bcm,
+                gpiochip_label: GPIOCHIP_LABEL_BCM283X,
                 pwm_chip: 0,
                 pwm_channels: [0, 1],
+                pwm_clock_rate: PWM_CLOCK_RATE_BCM283X,
+                revision_code,
+                serial,
             }),
             Model::RaspberryPi3B | Model::RaspberryPiComputeModule3 | Model::RaspberryPiZero2W => {
                 Ok(DeviceInfo {
                     model,
                     soc: SoC::Bcm2837A1,
-                    peripheral_base: PERIPHERAL_BASE_RPI2,
+                    peripheral_base: peripheral_base.unwrap_or(PERIPHERAL_BASE_RPI2),
                     gpio_offset: GPIO_OFFSET,
                     gpio_lines: GPIO_LINES_BCM283X,
                     gpio_interface: GpioInterface::Bcm,
+                    gpiochip_label: GPIOCHIP_LABEL_BCM283X,
                     pwm_chip: 0,
                     pwm_channels: [0, 1],
+                    pwm_clock_rate: PWM_CLOCK_RATE_BCM283X,
+                    revision_code,
+                    serial,
                 })
             }
             Model::RaspberryPi3BPlus
@@ -394,12 +703,16 @@ impl DeviceInfo {
             | Model::RaspberryPiComputeModule3Plus => Ok(DeviceInfo {
                 model,
                 soc: SoC::Bcm2837B0,
-                peripheral_base: PERIPHERAL_BASE_RPI2,
+                peripheral_base: peripheral_base.unwrap_or(PERIPHERAL_BASE_RPI2),
                 gpio_offset: GPIO_OFFSET,
                 gpio_lines: GPIO_LINES_BCM283X,
                 gpio_interface: GpioInterface::Bcm,
+                gpiochip_label: GPIOCHIP_LABEL_BCM283X,
                 pwm_chip: 0,
                 pwm_channels: [0, 1],
+                pwm_clock_rate: PWM_CLOCK_RATE_BCM283X,
+                revision_code,
+                serial,
             }),
             Model::RaspberryPi4B
             | Model::RaspberryPi400
@@ -407,12 +720,16 @@ impl DeviceInfo {
             | Model::RaspberryPiComputeModule4S => Ok(DeviceInfo {
                 model,
                 soc: SoC::Bcm2711,
-                peripheral_base: PERIPHERAL_BASE_RPI4,
+                peripheral_base: peripheral_base.unwrap_or(PERIPHERAL_BASE_RPI4),
                 gpio_offset: GPIO_OFFSET,
                 gpio_lines: GPIO_LINES_BCM2711,
                 gpio_interface: GpioInterface::Bcm,
+                gpiochip_label: GPIOCHIP_LABEL_BCM2711,
                 pwm_chip: 0,
                 pwm_channels: [0, 1],
+                pwm_clock_rate: PWM_CLOCK_RATE_BCM283X,
+                revision_code,
+                serial,
             }),
             Model::RaspberryPi5 => Ok(DeviceInfo {
                 model,
@@ -421,8 +738,12 @@ impl DeviceInfo {
                 gpio_offset: GPIO_OFFSET_RP1,
                 gpio_lines: GPIO_LINES_RP1,
                 gpio_interface: GpioInterface::Rp1,
+                gpiochip_label: GPIOCHIP_LABEL_RP1,
                 pwm_chip: 2,
                 pwm_channels: [2, 3],
+                pwm_clock_rate: PWM_CLOCK_RATE_RP1,
+                revision_code,
+                serial,
             }),
         }
     }
@@ -437,14 +758,142 @@ impl DeviceInfo {
         self.soc
     }
 
-    /// Returns the peripheral base memory address.
-    pub(crate) fn peripheral_base(&self) -> u32 {
-        self.peripheral_base
+    /// Returns the hardware revision code.
+    ///
+    /// The revision code is retrieved from `/proc/cpuinfo` or
+    /// `/sys/firmware/devicetree/base/system/linux,revision`, and encodes the
+    /// model, memory size, manufacturer and board revision. More information on
+    /// decoding the revision code can be found in the official Raspberry Pi
+    /// documentation. Returns `None` if the revision code isn't available.
+    pub fn revision_code(&self) -> Option<u32> {
+        self.revision_code
+    }
+
+    /// Returns the serial number.
+    ///
+    /// The serial number is retrieved from `/proc/cpuinfo` or
+    /// `/sys/firmware/devicetree/base/system/linux,serial`. Returns `None` if the
+    /// serial number isn't available, or consists of all zeros, which happens on some
+    /// models and inside certain containers.
+    pub fn serial(&self) -> Option<u64> {
+        self.serial
     }
 
-    /// Returns the offset from the peripheral base memory address for the GPIO section.
-    pub(crate) fn gpio_offset(&self) -> u32 {
-        self.gpio_offset
+    /// Returns the amount of RAM in megabytes (MB).
+    ///
+    /// The memory size is decoded from the new-style revision code used by the Raspberry
+    /// Pi 2 B and later models. Returns `None` if the revision code isn't available,
+    /// or uses the old-style format.
+    pub fn memory_size(&self) -> Option<u32> {
+        self.revision_code
+            // Bit 23 indicates a new-style revision code
+            .filter(|&revision_code| revision_code & (1 << 23) > 0)
+            .map(|revision_code| 256 << ((revision_code >> 20) & 0b111))
+    }
+
+    /// Returns a summary of the Raspberry Pi's hardware details.
+    ///
+    /// The returned [`DeviceSummary`] implements `Display`, which prints a multi-line
+    /// report suitable for logging.
+    ///
+    /// [`DeviceSummary`]: struct.DeviceSummary.html
+    pub fn summary(&self) -> DeviceSummary {
+        DeviceSummary {
+            model: self.model,
+            soc: self.soc,
+            memory_size: self.memory_size(),
+            serial: self.serial,
+            revision_code: self.revision_code,
+            peripheral_base: self.peripheral_base,
+        }
+    }
+
+    /// Returns the logic level voltage used by the GPIO pins.
+    ///
+    /// All Raspberry Pi models use 3.3 V logic levels. The GPIO pins aren't 5 V
+    /// tolerant. Connecting a 5 V signal directly to a GPIO pin may permanently
+    /// damage the SoC. Use a level shifter or a voltage divider to connect
+    /// devices with 5 V logic levels.
+    pub fn gpio_voltage(&self) -> f32 {
+        GPIO_VOLTAGE
+    }
+
+    /// Returns the functions of the physical pins on the GPIO header.
+    ///
+    /// The first element describes physical pin 1, the second element physical pin 2,
+    /// and so on. Models with a 40-pin header return 40 elements, while the original
+    /// Raspberry Pi A and B return 26 elements. Compute Modules don't have a GPIO
+    /// header, and return an empty slice.
+    ///
+    /// The 5 V power pins are connected directly to the power supply, and should never
+    /// be connected to a GPIO pin.
+    pub fn header_pins(&self) -> &'static [HeaderPin] {
+        match self.model {
+            Model::RaspberryPiBRev1 => &HEADER_26_REV1,
+            Model::RaspberryPiA | Model::RaspberryPiBRev2 => &HEADER_26_REV2,
+            Model::RaspberryPiComputeModule
+            | Model::RaspberryPiComputeModule3
+            | Model::RaspberryPiComputeModule3Plus
+            | Model::RaspberryPiComputeModule4
+            | Model::RaspberryPiComputeModule4S => &[],
+            _ => &HEADER_40,
+        }
+    }
+
+    /// Returns the physical pin number on the GPIO header for the specified BCM GPIO
+    /// number, or `None` if the pin isn't available on the header.
+    pub fn physical_pin(&self, gpio: u8) -> Option<u8> {
+        self.header_pins()
+            .iter()
+            .position(|&header_pin| header_pin == HeaderPin::Gpio(gpio))
+            .map(|idx| idx as u8 + 1)
+    }
+
+    /// Returns the physical memory addresses of the SoC's peripherals.
+    ///
+    /// The addresses are based on the peripheral base address specified in the device
+    /// tree, or the default for the detected SoC if the device tree isn't available.
+    /// They can be used to access the peripheral registers directly through `/dev/mem`.
+    ///
+    /// On the Raspberry Pi 5, Raspberry Pi 500 and Compute Module 5, the peripherals
+    /// used by RPPAL are located on the RP1, which only provides the GPIO address.
+    pub fn peripheral_addresses(&self) -> PeripheralAddresses {
+        let base = self.peripheral_base;
+        let offsets = |offsets: [Option<u64>; 7]| {
+            let mut addresses = [None; 7];
+            for (address, offset) in addresses.iter_mut().zip(offsets.iter()) {
+                *address = offset.map(|offset| base + offset);
+            }
+
+            addresses
+        };
+
+        let mut addresses = PeripheralAddresses {
+            peripheral_base: base,
+            gpio: base + u64::from(self.gpio_offset),
+            clock_manager: Some(base + CM_OFFSET),
+            pads: Some(base + PADS_OFFSET),
+            pwm: Some(base + PWM0_OFFSET),
+            spi: offsets(SPI_OFFSETS_BCM283X),
+            i2c: offsets(BSC_OFFSETS_BCM283X),
+        };
+
+        match self.soc {
+            SoC::Bcm2711 => {
+                addresses.spi = offsets(SPI_OFFSETS_BCM2711);
+                addresses.i2c = offsets(BSC_OFFSETS_BCM2711);
+            }
+            SoC::Bcm2712 => {
+                addresses.clock_manager = None;
+                addresses.pads = None;
+                addresses.pwm = None;
+                addresses.spi = [None; 7];
+                addresses.i2c = [None; 7];
+            }
+            _ => {}
+        }
+
+        addresses
     }
 
     /// Returns the number of GPIO lines available for this SoC.
@@ -457,6 +906,11 @@ impl DeviceInfo {
         self.gpio_interface
     }
 
+    /// Returns the label of the gpiochip that controls the GPIO lines.
+    pub(crate) fn gpiochip_label(&self) -> &'static str {
+        self.gpiochip_label
+    }
+
     /// Returns the PWM chip # used for hardware PWM.
     pub(crate) fn pwm_chip(&self) -> u8 {
         self.pwm_chip
@@ -466,4 +920,83 @@ impl DeviceInfo {
     pub(crate) fn pwm_channels(&self) -> [u8; 2] {
         self.pwm_channels
     }
+
+    /// Returns the default clock rate (Hz) of the PWM peripheral.
+    pub(crate) fn pwm_clock_rate(&self) -> u32 {
+        self.pwm_clock_rate
+    }
+}
+
+/// Physical memory addresses of the SoC's peripherals.
+///
+/// `PeripheralAddresses` is returned by [`DeviceInfo::peripheral_addresses`]. Peripherals
+/// that aren't available on the SoC are set to `None`.
+///
+/// `PeripheralAddresses` might be extended with additional fields in a minor or
+/// patch revision, and can't be constructed outside of RPPAL.
+///
+/// [`DeviceInfo::peripheral_addresses`]: struct.DeviceInfo.html#method.peripheral_addresses
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+#[non_exhaustive]
+pub struct PeripheralAddresses {
+    /// The peripheral base memory address.
+    pub peripheral_base: u64,
+    /// The GPIO registers.
+    pub gpio: u64,
+    /// The clock manager registers.
+    pub clock_manager: Option<u64>,
+    /// The GPIO pad control registers, starting with the register for GPIO 0-27.
+    pub pads: Option<u64>,
+    /// The PWM0 registers.
+    pub pwm: Option<u64>,
+    /// The SPI controller registers, indexed by SPI bus number.
+    pub spi: [Option<u64>; 7],
+    /// The BSC (I2C) controller registers, indexed by I2C bus number.
+    pub i2c: [Option<u64>; 7],
+}
+
+/// Summary of the Raspberry Pi's hardware details.
+///
+/// `DeviceSummary` is returned by [`DeviceInfo::summary`]. The `Display` implementation
+/// prints each field on a separate line. Unavailable fields are printed as `Unknown`.
+///
+/// [`DeviceInfo::summary`]: struct.DeviceInfo.html#method.summary
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct DeviceSummary {
+    /// The Raspberry Pi's model.
+    pub model: Model,
+    /// The Raspberry Pi's SoC.
+    pub soc: SoC,
+    /// The amount of RAM in megabytes (MB), if available.
+    pub memory_size: Option<u32>,
+    /// The serial number, if available.
+    pub serial: Option<u64>,
+    /// The hardware revision code, if available.
+    pub revision_code: Option<u32>,
+    /// The peripheral base memory address.
+    pub peripheral_base: u64,
+}
+
+impl fmt::Display for DeviceSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Model: {}", self.model)?;
+        writeln!(f, "SoC: {}", self.soc)?;
+
+        match self.memory_size {
+            Some(memory_size) => writeln!(f, "Memory: {} MB", memory_size)?,
+            None => writeln!(f, "Memory: Unknown")?,
+        }
+
+        match self.serial {
+            Some(serial) => writeln!(f, "Serial: {:016x}", serial)?,
+            None => writeln!(f, "Serial: Unknown")?,
+        }
+
+        match self.revision_code {
+            Some(revision_code) => writeln!(f, "Revision: {:x}", revision_code)?,
+            None => writeln!(f, "Revision: Unknown")?,
+        }
+
+        write!(f, "Peripheral base: {:#010x}", self.peripheral_base)
+    }
 }
@@ -0,0 +1,57 @@
+// These tests require access to the GPIO peripheral, and are skipped when
+// Gpio::new fails, for instance when they're not running on a Raspberry Pi.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+use rppal::gpio::{Error, Gpio};
+
+const THREADS: usize = 16;
+const ITERATIONS: usize = 1000;
+// Retrieving a pin doesn't change its mode or state
+const PIN: u8 = 26;
+
+#[test]
+fn concurrent_new_and_get() {
+    if Gpio::new().is_err() {
+        return;
+    }
+
+    let owners = Arc::new(AtomicUsize::new(0));
+    let acquired = Arc::new(AtomicUsize::new(0));
+
+    let threads: Vec<_> = (0..THREADS)
+        .map(|_| {
+            let owners = owners.clone();
+            let acquired = acquired.clone();
+
+            thread::spawn(move || {
+                for _ in 0..ITERATIONS {
+                    let gpio = Gpio::new().unwrap();
+
+                    match gpio.get(PIN) {
+                        Ok(pin) => {
+                            // No other thread may own the pin at the same time
+                            assert_eq!(owners.fetch_add(1, Ordering::SeqCst), 0);
+                            acquired.fetch_add(1, Ordering::SeqCst);
+                            owners.fetch_sub(1, Ordering::SeqCst);
+                            drop(pin);
+                        }
+                        Err(Error::PinUsed(pin)) => assert_eq!(pin, PIN),
+                        Err(e) => panic!("unexpected error: {}", e),
+                    }
+                }
+            })
+        })
+        .collect();
+
+    for thread in threads {
+        thread.join().unwrap();
+    }
+
+    assert!(acquired.load(Ordering::SeqCst) > 0);
+
+    // The pin is available again after all instances have gone out of scope
+    assert!(Gpio::new().unwrap().get(PIN).is_ok());
+}